@@ -13,8 +13,11 @@ public struct FastMarkdownParser {
         case .success(let output):
             let outJsonData = output.data(using: .utf8)!
             let decoder = JSONDecoder()
-            let outputArray = try decoder.decode([MarkdownNode].self, from: outJsonData)
-            return .success(outputArray)
+            let envelope = try decoder.decode(MarkdownEnvelope.self, from: outJsonData)
+            guard envelope.version <= MarkdownEnvelope.supportedVersion else {
+                return .failure(SomeError(message: "unsupported wire schema version \(envelope.version)"))
+            }
+            return .success(envelope.nodes)
         }
     }
     public static func fastUnsafeParser(source: String) throws -> [ MarkdownNode ] {
@@ -40,7 +43,11 @@ public struct FastMarkdownParser {
         markdown_parser_ffi_byte_vector_free(markupEngineParseResult.output)
         let outputString = String(decoding: outputBytes, as: UTF8.self)
         if markupEngineParseResult.status.rawValue != 0 {
-            return .failure(SomeError(message: outputString))
+            return .failure(SomeError(
+                message: outputString,
+                code: markupEngineParseResult.error_code,
+                position: markupEngineParseResult.error_position
+            ))
         }
         if prettyPrint {
             if let jsonStringData = outputString.data(using: .utf8),
@@ -59,12 +66,44 @@ public struct FastMarkdownParser {
         let outputString = String(cString: parseResult.output.pointer)
         markdown_parser_ffi_rust_c_string_free(parseResult.output)
         if parseResult.status.rawValue != 0 {
-            return .failure(SomeError(message: outputString))
+            return .failure(SomeError(message: outputString, code: parseResult.error_code, position: parseResult.error_position))
         }
         return .success(outputString)
     }
     public struct SomeError: Error {
         public let message: String
+        public var kind: Kind = .other
+        /// Where in the source parsing failed, for errors about one place.
+        public var position: Position? = nil
+        public enum Kind: UInt32 {
+            case other = 0
+            case nullArgument = 1
+            case invalidUTF8 = 2
+            case limitExceeded = 3
+            case syntax = 4
+            case serialization = 5
+            case invalidArgument = 6
+            /// The parser panicked, a bug of the library rather than of the input.
+            case `internal` = 7
+        }
+        public struct Position {
+            /// 1-indexed.
+            public let line: Int
+            /// 1-indexed, in UTF-8 bytes.
+            public let column: Int
+            /// In UTF-8 bytes.
+            public let offset: Int
+        }
+        public init(message: String) {
+            self.message = message
+        }
+        init(message: String, code: MarkdownParserFFIErrorCode, position: MarkdownParserFFIErrorPosition) {
+            self.message = message
+            self.kind = Kind(rawValue: code.rawValue) ?? .other
+            if position.has_position {
+                self.position = Position(line: Int(position.line), column: Int(position.column), offset: Int(position.offset))
+            }
+        }
     }
 }
 
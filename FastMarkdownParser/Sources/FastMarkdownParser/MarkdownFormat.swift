@@ -7,6 +7,16 @@
 
 import Foundation
 
+// MARK: - Wire Format
+
+/// Parsed nodes tagged with the version of the wire schema they follow, as encoded by the FFI.
+public struct MarkdownEnvelope: Codable {
+    /// The wire schema version this package decodes, see `markdown_format::Envelope::VERSION`.
+    public static let supportedVersion: UInt32 = 2
+    public let version: UInt32
+    public let nodes: [MarkdownNode]
+}
+
 // MARK: - Markdown AST
 
 public enum MarkdownNode {
@@ -38,6 +48,12 @@ public enum MarkdownNode {
     case toml(Toml)
     case yaml(Yaml)
     case html(Html)
+    case admonition(Admonition)
+    case wikiLink(WikiLink)
+    case embed(Embed)
+    case definitionList(DefinitionList)
+    case definitionTerm(DefinitionTerm)
+    case definitionDescription(DefinitionDescription)
 }
 
 extension MarkdownNode: Codable {
@@ -106,6 +122,18 @@ extension MarkdownNode: Codable {
             self = .yaml(try Yaml(from: decoder))
         case "Html":
             self = .html(try Html(from: decoder))
+        case "Admonition":
+            self = .admonition(try Admonition(from: decoder))
+        case "WikiLink":
+            self = .wikiLink(try WikiLink(from: decoder))
+        case "Embed":
+            self = .embed(try Embed(from: decoder))
+        case "DefinitionList":
+            self = .definitionList(try DefinitionList(from: decoder))
+        case "DefinitionTerm":
+            self = .definitionTerm(try DefinitionTerm(from: decoder))
+        case "DefinitionDescription":
+            self = .definitionDescription(try DefinitionDescription(from: decoder))
         default:
             let context = DecodingError.Context(codingPath: container.codingPath, debugDescription: "Unknown node type: \(type)")
             throw DecodingError.dataCorrupted(context)
@@ -200,6 +228,24 @@ extension MarkdownNode: Codable {
         case .html(let html):
             try container.encode("Html", forKey: .type)
             try html.encode(to: encoder)
+        case .admonition(let admonition):
+            try container.encode("Admonition", forKey: .type)
+            try admonition.encode(to: encoder)
+        case .wikiLink(let wikiLink):
+            try container.encode("WikiLink", forKey: .type)
+            try wikiLink.encode(to: encoder)
+        case .embed(let embed):
+            try container.encode("Embed", forKey: .type)
+            try embed.encode(to: encoder)
+        case .definitionList(let definitionList):
+            try container.encode("DefinitionList", forKey: .type)
+            try definitionList.encode(to: encoder)
+        case .definitionTerm(let definitionTerm):
+            try container.encode("DefinitionTerm", forKey: .type)
+            try definitionTerm.encode(to: encoder)
+        case .definitionDescription(let definitionDescription):
+            try container.encode("DefinitionDescription", forKey: .type)
+            try definitionDescription.encode(to: encoder)
         }
     }
 }
@@ -240,6 +286,20 @@ extension MarkdownNode {
         public let value: String
         public let lang: String?
         public let meta: String?
+        /// Present when parsed with `highlight` and `lang` is known.
+        public let highlights: [HighlightSpan]?
+    }
+
+    public struct HighlightSpan: Codable {
+        /// UTF-8 byte offsets into the code block's `value`.
+        public let range: ByteRange
+        /// TextMate scope, such as `keyword.control.rust`.
+        public let scope: String
+
+        public struct ByteRange: Codable {
+            public let start: Int
+            public let end: Int
+        }
     }
 
     public struct InlineCode: Codable {
@@ -269,12 +329,49 @@ extension MarkdownNode {
         public let title: String?
     }
 
+    public struct WikiLink: Codable {
+        public let position: SourceRange?
+        public let target: String
+        public let alias: String?
+        public let headingAnchor: String?
+        /// Set once resolved.
+        public let url: String?
+
+        enum CodingKeys: String, CodingKey {
+            case position
+            case target
+            case alias
+            case headingAnchor = "heading_anchor"
+            case url
+        }
+    }
+
+    public struct Embed: Codable {
+        public let position: SourceRange?
+        public let target: String
+        public let alias: String?
+        public let headingAnchor: String?
+        /// Set once resolved.
+        public let url: String?
+
+        enum CodingKeys: String, CodingKey {
+            case position
+            case target
+            case alias
+            case headingAnchor = "heading_anchor"
+            case url
+        }
+    }
+
     public struct LinkReference: Codable {
         public let position: SourceRange?
         public let children: [MarkdownNode]
         public let referenceKind: ReferenceKind
         public let identifier: String
         public let label: String?
+        /// Set once resolved against the matching definition.
+        public let url: String?
+        public let title: String?
     }
 
     public struct Image: Codable {
@@ -290,6 +387,9 @@ extension MarkdownNode {
         public let referenceKind: ReferenceKind
         public let identifier: String
         public let label: String?
+        /// Set once resolved against the matching definition.
+        public let url: String?
+        public let title: String?
     }
 
     // MARK: - Markup
@@ -316,6 +416,14 @@ extension MarkdownNode {
         public let children: [MarkdownNode]
     }
 
+    public struct Admonition: Codable {
+        public let position: SourceRange?
+        public let children: [MarkdownNode]
+        /// Lowercased, such as `note` or `warning`.
+        public let kind: String
+        public let title: String?
+    }
+
     public struct Paragraph: Codable {
         public let position: SourceRange?
         public let children: [MarkdownNode]
@@ -340,12 +448,16 @@ extension MarkdownNode {
         public let children: [MarkdownNode]
         public let identifier: String
         public let label: String?
+        /// 1-indexed, in order of first reference.
+        public let number: Int?
     }
 
     public struct FootnoteReference: Codable {
         public let position: SourceRange?
         public let identifier: String
         public let label: String?
+        /// 1-indexed, in order of first reference.
+        public let number: Int?
     }
 
     public struct HorizontalDivider: Codable {
@@ -392,6 +504,24 @@ extension MarkdownNode {
         case unordered
     }
 
+    // MARK: - Definition Lists
+
+    public struct DefinitionList: Codable {
+        public let position: SourceRange?
+        public let children: [MarkdownNode]
+        public let spread: Bool
+    }
+
+    public struct DefinitionTerm: Codable {
+        public let position: SourceRange?
+        public let children: [MarkdownNode]
+    }
+
+    public struct DefinitionDescription: Codable {
+        public let position: SourceRange?
+        public let children: [MarkdownNode]
+    }
+
     // MARK: - Common Enums
 
     public enum ReferenceKind: String, Codable {
@@ -429,5 +559,17 @@ extension MarkdownNode {
     public struct Position: Codable {
         public let line: UInt
         public let column: UInt
+        /// UTF-16 code unit offset (`NSRange` location), present when parsed
+        /// with `unicode_offsets`.
+        public let utf16Offset: UInt?
+        /// Unicode scalar offset, present when parsed with `unicode_offsets`.
+        public let scalarOffset: UInt?
+
+        enum CodingKeys: String, CodingKey {
+            case line
+            case column
+            case utf16Offset = "utf16_offset"
+            case scalarOffset = "scalar_offset"
+        }
     }
 }
@@ -0,0 +1,141 @@
+//! The FFI entry points driven the way a C caller drives them: through raw
+//! pointers, with `NULL`s and out of bounds indices, freeing what it's given.
+//!
+//! ```sh
+//! cargo +nightly fuzz run ffi
+//! ```
+#![no_main]
+use std::ptr::{null, null_mut};
+use libfuzzer_sys::fuzz_target;
+use markdown_parser_ffi::*;
+
+mod panics;
+
+fuzz_target!(|data: &[u8]| {
+    panics::ignore_markdown_panics();
+    let Some((&selector, source)) = data.split_first() else { return };
+    let config = match selector % 4 {
+        0 => markdown_parser_ffi_parse_config_default(),
+        1 => markdown_parser_ffi_parse_config_commonmark(),
+        2 => markdown_parser_ffi_parse_config_gfm(),
+        _ => markdown_parser_ffi_parse_config_everything(),
+    };
+
+    // Byte vectors, possibly invalid UTF-8.
+    let byte_vector = markdown_parser_ffi_byte_vector_new();
+    for byte in source {
+        markdown_parser_ffi_byte_vector_push(byte_vector, *byte);
+    }
+    // `0` and `4` aren't formats.
+    for format in 0..=4 {
+        let result = markdown_parser_ffi_utf8_byte_vector_parse_to_format(byte_vector, &config, format);
+        read_byte_vector(result.output);
+        markdown_parser_ffi_byte_vector_free(result.output);
+    }
+    markdown_parser_ffi_byte_vector_free(byte_vector);
+
+    // Null terminated strings, cut at the first null byte like C would.
+    let c_string = std::ffi::CString::new(source.split(|x| *x == 0).next().unwrap()).unwrap();
+    let result = markdown_parser_ffi_utf8_parse_to_json_string_with_config(c_string.as_ptr(), &config);
+    markdown_parser_ffi_rust_c_string_free(result.output);
+    let result = markdown_parser_ffi_utf8_outline_to_format(c_string.as_ptr(), &config, DataModelFormatType::JSON as u32);
+    markdown_parser_ffi_byte_vector_free(result.output);
+    let result = markdown_parser_ffi_utf8_lint_to_format(c_string.as_ptr(), &config, null(), DataModelFormatType::JSON as u32);
+    markdown_parser_ffi_byte_vector_free(result.output);
+
+    // The binary encoding, validated against its source, and arbitrary bytes validated against an
+    // empty source.
+    let result = markdown_parser_ffi_utf8_parse_to_binary(c_string.as_ptr(), &config);
+    if let ErrorStatus::Ok = result.status {
+        let source = ByteArray { data: c_string.as_ptr().cast(), length: c_string.as_bytes().len() };
+        let buffer = markdown_parser_ffi_byte_vector_data(result.output);
+        assert!(matches!(markdown_parser_ffi_binary_validate(buffer, source), ErrorStatus::Ok));
+    }
+    markdown_parser_ffi_byte_vector_free(result.output);
+    let array = ByteArray { data: source.as_ptr(), length: source.len() };
+    markdown_parser_ffi_binary_validate(array, ByteArray { data: null(), length: 0 });
+
+    // Documents, walked past their last node.
+    let array = ByteArray { data: source.as_ptr(), length: source.len() };
+    let result = markdown_parser_ffi_document_parse_byte_array(array, &config);
+    markdown_parser_ffi_byte_vector_free(result.error);
+    walk(result.document);
+    markdown_parser_ffi_document_free(result.document);
+
+    nulls();
+});
+
+fn read_byte_vector(byte_vector: *const ByteVector) {
+    let length = markdown_parser_ffi_byte_vector_length(byte_vector);
+    let mut byte = 0;
+    for index in [0, length / 2, length.saturating_sub(1), length, usize::MAX] {
+        let result = markdown_parser_ffi_byte_vector_get(byte_vector, index);
+        let status = markdown_parser_ffi_byte_vector_read(byte_vector, index, &mut byte);
+        assert_eq!(matches!(result.status, ErrorStatus::Ok), index < length);
+        assert_eq!(matches!(status, ErrorStatus::Ok), index < length);
+    }
+}
+
+fn walk(document: *const Document) {
+    let count = markdown_parser_ffi_document_node_count(document);
+    let roots = markdown_parser_ffi_document_roots(document);
+    assert!(roots.start + roots.length <= count);
+    for index in 0..=count {
+        markdown_parser_ffi_document_node_kind(document, index);
+        let children = markdown_parser_ffi_document_node_children(document, index);
+        assert!(children.start + children.length <= count);
+        let mut parent = 0;
+        if let ErrorStatus::Ok = markdown_parser_ffi_document_node_parent(document, index, &mut parent) {
+            assert!(parent < count);
+        }
+        markdown_parser_ffi_document_node_parent(document, index, null_mut());
+        // `0` and `13` aren't fields.
+        for field in 0..=13 {
+            let string = markdown_parser_ffi_document_node_string(document, index, field);
+            if !string.data.is_null() {
+                let bytes = unsafe { std::slice::from_raw_parts(string.data, string.length) };
+                assert!(std::str::from_utf8(bytes).is_ok());
+            }
+        }
+        markdown_parser_ffi_document_node_attributes(document, index);
+        markdown_parser_ffi_document_node_position(document, index);
+        let columns = markdown_parser_ffi_document_table_column_count(document, index);
+        for column in 0..=columns {
+            markdown_parser_ffi_document_table_column_alignment(document, index, column);
+        }
+        let highlights = markdown_parser_ffi_document_code_highlight_count(document, index);
+        for highlight in 0..=highlights {
+            markdown_parser_ffi_document_code_highlight(document, index, highlight);
+        }
+    }
+}
+
+/// Every entry point taking pointers, given `NULL`.
+fn nulls() {
+    read_byte_vector(null());
+    markdown_parser_ffi_byte_vector_push(null_mut(), 0);
+    markdown_parser_ffi_byte_vector_free(null_mut());
+    let result = markdown_parser_ffi_utf8_byte_vector_parse_to_format(null(), null(), DataModelFormatType::JSON as u32);
+    assert!(matches!(result.status, ErrorStatus::Error));
+    markdown_parser_ffi_byte_vector_free(result.output);
+    let result = markdown_parser_ffi_utf8_parse_to_json_string_with_config(null(), null());
+    assert!(matches!(result.status, ErrorStatus::Error));
+    markdown_parser_ffi_rust_c_string_free(result.output);
+    let result = markdown_parser_ffi_utf8_lint_to_format(null(), null(), null(), DataModelFormatType::JSON as u32);
+    assert!(matches!(result.status, ErrorStatus::Error));
+    markdown_parser_ffi_byte_vector_free(result.output);
+    let result = markdown_parser_ffi_document_parse(null(), null());
+    assert!(matches!(result.status, ErrorStatus::Error));
+    markdown_parser_ffi_byte_vector_free(result.error);
+    let result = markdown_parser_ffi_document_parse_byte_array(ByteArray { data: null(), length: 1 }, null());
+    assert!(matches!(result.status, ErrorStatus::Error));
+    markdown_parser_ffi_byte_vector_free(result.error);
+    let result = markdown_parser_ffi_utf8_parse_to_binary(null(), null());
+    assert!(matches!(result.status, ErrorStatus::Error));
+    markdown_parser_ffi_byte_vector_free(result.output);
+    assert!(markdown_parser_ffi_byte_vector_data(null()).data.is_null());
+    let array = || ByteArray { data: null(), length: 1 };
+    assert!(matches!(markdown_parser_ffi_binary_validate(array(), array()), ErrorStatus::Error));
+    walk(null());
+    markdown_parser_ffi_document_free(null_mut());
+}
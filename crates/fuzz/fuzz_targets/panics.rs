@@ -0,0 +1,21 @@
+//! libfuzzer-sys aborts on any panic, even on the ones of the `markdown` crate
+//! that `markdown_format::parse_with` turns into errors.
+
+/// Lets the panics raised in the `markdown` crate unwind, call it first thing
+/// in a target.
+pub fn ignore_markdown_panics() {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            let in_markdown = info.location().is_some_and(|location| {
+                location.file().split(['/', '\\']).any(|x| {
+                    x.strip_prefix("markdown-").is_some_and(|version| version.starts_with(|x: char| x.is_ascii_digit()))
+                })
+            });
+            if !in_markdown {
+                hook(info)
+            }
+        }));
+    });
+}
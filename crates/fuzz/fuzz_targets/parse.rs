@@ -0,0 +1,50 @@
+//! `markdown_format::parse_with` and the passes over the AST on arbitrary input.
+//!
+//! The first byte picks the preset, the rest is the source:
+//!
+//! ```sh
+//! cargo +nightly fuzz run parse
+//! ```
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use markdown_format::ParseConfig;
+
+mod panics;
+
+fuzz_target!(|data: &[u8]| {
+    panics::ignore_markdown_panics();
+    let Some((&selector, source)) = data.split_first() else { return };
+    let Ok(source) = std::str::from_utf8(source) else { return };
+    let mut config = match selector % 4 {
+        0 => ParseConfig::default(),
+        1 => ParseConfig::commonmark(),
+        2 => ParseConfig::gfm(),
+        _ => ParseConfig::everything(),
+    };
+    config.unicode_offsets = selector & 4 != 0;
+    let mut nodes = match markdown_format::parse_with(source, &config) {
+        Ok(nodes) => nodes,
+        // Limits, and panics of the `markdown` crate (see the
+        // `frontmatter-after-setext-1` seed), fail without aborting.
+        Err(error) => {
+            if let Some(offset) = error.offset() {
+                assert!(source.is_char_boundary(offset), "{error} is not at a char boundary");
+            }
+            return
+        }
+    };
+    for node in markdown_format::visit::depth_first(&nodes) {
+        if let Some(position) = node.position() {
+            let range = position.start.offset..position.end.offset;
+            assert!(source.get(range.clone()).is_some(), "{range:?} is not a range of the source: {node:?}");
+        }
+    }
+    markdown_format::resolve::resolve(&mut nodes);
+    let bytes = markdown_format::binary::encode(&nodes, source).unwrap();
+    let document = markdown_format::binary::BinaryDocument::new(&bytes, source).unwrap();
+    assert_eq!(document.decode(), nodes);
+    markdown_format::html::render(&nodes, &Default::default());
+    markdown_format::plain_text::to_plain_text(&nodes, &Default::default());
+    markdown_format::outline::outline(&nodes);
+    markdown_format::lint::lint(source, &nodes);
+});
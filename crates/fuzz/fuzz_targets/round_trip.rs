@@ -0,0 +1,29 @@
+//! The printer invariants: `parse(print(parse(x))) == parse(x)` modulo
+//! positions, and printing a printed document again changes nothing.
+//!
+//! ```sh
+//! cargo +nightly fuzz run round_trip
+//! ```
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use markdown_format::printer;
+use common::without_positions;
+
+#[path = "../../markdown-format/tests/common/mod.rs"]
+mod common;
+mod panics;
+
+fuzz_target!(|data: &[u8]| {
+    panics::ignore_markdown_panics();
+    let Ok(source) = std::str::from_utf8(data) else { return };
+    let Ok(nodes) = markdown_format::parse(source) else { return };
+    let options = printer::PrinterOptions::default();
+    let printed = printer::print(&nodes, &options);
+    let reparsed = markdown_format::parse(&printed).expect("printed Markdown parses");
+    assert_eq!(
+        without_positions(serde_json::to_value(&nodes).unwrap()),
+        without_positions(serde_json::to_value(&reparsed).unwrap()),
+        "printed:\n{printed}",
+    );
+    assert_eq!(printer::print(&reparsed, &options), printed, "printing again changed the output");
+});
@@ -0,0 +1,89 @@
+//! Size and speed of the binary encoding next to JSON and binary property
+//! lists, over the samples repeated into one large document.
+//!
+//! ```sh
+//! cargo bench -p markdown-format --bench binary
+//! ```
+//!
+//! Times are the fastest of [`RUNS`] runs. Decoding the binary encoding in
+//! place only validates it and reads every node's kind, position and value,
+//! the way a caller walking the buffer through the FFI would.
+#[path = "../tests/common/mod.rs"]
+mod common;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use markdown_format::binary::{self, BinaryDocument, Field};
+use markdown_format::{Node, ParseConfig};
+
+const RUNS: usize = 30;
+const REPEAT: usize = 20;
+
+fn main() {
+    let source = samples().repeat(REPEAT);
+    let nodes = markdown_format::parse_with(&source, &ParseConfig::everything()).unwrap();
+    let node_count = markdown_format::visit::depth_first(&nodes).count();
+    println!("{} bytes of Markdown, {node_count} nodes\n", source.len());
+    println!("{:<24} {:>10} {:>12} {:>12}", "format", "bytes", "encode", "decode");
+
+    let json = serde_json::to_vec(&nodes).unwrap();
+    report(
+        "JSON",
+        json.len(),
+        time(|| serde_json::to_vec(&nodes).unwrap()),
+        time(|| serde_json::from_slice::<Vec<Node>>(&json).unwrap()),
+    );
+
+    let encode_plist = || {
+        let mut output = Vec::new();
+        plist::to_writer_binary(&mut output, &nodes).unwrap();
+        output
+    };
+    let plist = encode_plist();
+    report(
+        "binary property list",
+        plist.len(),
+        time(encode_plist),
+        time(|| plist::from_bytes::<Vec<Node>>(&plist).unwrap()),
+    );
+
+    let encoded = binary::encode(&nodes, &source).unwrap();
+    let encode = time(|| binary::encode(&nodes, &source).unwrap());
+    report(
+        "binary",
+        encoded.len(),
+        encode,
+        time(|| BinaryDocument::new(&encoded, &source).unwrap().decode()),
+    );
+    report("binary, read in place", encoded.len(), encode, time(|| read_in_place(&encoded, &source)));
+}
+
+fn read_in_place(bytes: &[u8], source: &str) -> usize {
+    let document = BinaryDocument::new(bytes, source).unwrap();
+    let mut total = 0;
+    for index in 0..document.len() {
+        let node = document.node(index).unwrap();
+        black_box((node.kind(), node.position()));
+        total += node.string(Field::Value).map_or(0, str::len);
+    }
+    total
+}
+
+fn time<T>(mut run: impl FnMut() -> T) -> Duration {
+    (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(run());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(format: &str, size: usize, encode: Duration, decode: Duration) {
+    println!("{format:<24} {size:>10} {:>12} {:>12}", format!("{encode:.2?}"), format!("{decode:.2?}"));
+}
+
+fn samples() -> String {
+    common::samples().into_iter().map(|(_, source)| source + "\n").collect()
+}
@@ -0,0 +1,9 @@
+//! Prints the JSON Schema of the wire format.
+//!
+//! ```sh
+//! cargo run -p markdown-format --example json_schema --features schema > markdown-format/schema/envelope.schema.json
+//! ```
+fn main() {
+    let schema = markdown_format::json_schema();
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
@@ -0,0 +1,328 @@
+//! Admonitions: GitHub-style callouts and `:::` containers.
+//!
+//! Both are recognized after parsing, in trees where they came through as
+//! block quotes and paragraphs:
+//!
+//! ```markdown
+//! > [!NOTE]
+//! > Callouts take an optional title after the marker.
+//!
+//! :::warning Optional title
+//! Containers end at a line with at least as many colons, or at the end of
+//! their parent. Fences are whole lines, longer ones nest shorter ones.
+//! :::
+//! ```
+//!
+//! ```
+//! use markdown_format::{Node, ParseConfig};
+//! let config = ParseConfig { admonition: true, ..ParseConfig::gfm() };
+//! let nodes = markdown_format::parse_with("> [!TIP]\n> Use *it*\n", &config).unwrap();
+//! let Node::Admonition(admonition) = &nodes[0] else { panic!() };
+//! assert_eq!((admonition.kind.as_str(), admonition.title.as_deref()), ("tip", None));
+//! ```
+use std::ops::Range;
+use crate::{Admonition, Node, Paragraph, Point, SourceRange, Text};
+use crate::text::line_start;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Turns callouts and containers into [`Admonition`] nodes, at any depth.
+/// `source` is the document the positions of `nodes` point into.
+pub fn admonitions(source: &str, nodes: &mut Vec<Node>) {
+    let mut pass = Pass { source };
+    pass.nodes(nodes);
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+struct Pass<'a> {
+    source: &'a str,
+}
+
+/// A `:::` container whose closing fence hasn't been seen yet.
+struct Open {
+    admonition: Admonition,
+    colons: usize,
+}
+
+impl Pass<'_> {
+    fn nodes(&mut self, nodes: &mut Vec<Node>) {
+        for node in nodes.iter_mut() {
+            if let Some(children) = node.children_mut() {
+                self.nodes(children);
+            }
+            if let Node::Blockquote(blockquote) = node {
+                if let Some(admonition) = self.callout(blockquote) {
+                    *node = Node::Admonition(admonition);
+                }
+            }
+        }
+        if has_fences(nodes) {
+            self.containers(nodes);
+        }
+    }
+    /// A block quote whose first line is `[!KIND]`, optionally followed by a
+    /// title.
+    fn callout(&self, blockquote: &mut crate::Blockquote) -> Option<Admonition> {
+        let Some(Node::Paragraph(paragraph)) = blockquote.children.first_mut() else { return None };
+        let line = first_line(paragraph)?;
+        let (kind, title) = line.strip_prefix("[!")?.split_once(']')?;
+        if kind.is_empty() || !kind.chars().all(|x| x.is_ascii_alphabetic()) {
+            return None
+        }
+        let admonition = Admonition {
+            kind: kind.to_ascii_lowercase(),
+            title: Some(title.trim()).filter(|x| !x.is_empty()).map(str::to_owned),
+            children: Vec::new(),
+            position: blockquote.position.clone(),
+        };
+        if !self.remove_first_line(paragraph) {
+            blockquote.children.remove(0);
+        }
+        Some(Admonition { children: std::mem::take(&mut blockquote.children), ..admonition })
+    }
+    /// Groups the siblings between `:::` fences, which are whole lines of
+    /// paragraphs.
+    fn containers(&mut self, nodes: &mut Vec<Node>) {
+        let mut output = Vec::with_capacity(nodes.len());
+        let mut stack: Vec<Open> = Vec::new();
+        for node in std::mem::take(nodes) {
+            match node {
+                Node::Paragraph(paragraph) => self.paragraph(paragraph, &mut stack, &mut output),
+                node => push(&mut stack, &mut output, node),
+            }
+        }
+        // Unclosed containers run to the end of their parent.
+        while let Some(Open { admonition, .. }) = stack.pop() {
+            push(&mut stack, &mut output, Node::Admonition(admonition));
+        }
+        *nodes = output;
+    }
+    /// Splits the paragraph at its fence lines, the text between them becomes
+    /// paragraphs of its own.
+    fn paragraph(&self, paragraph: Paragraph, stack: &mut Vec<Open>, output: &mut Vec<Node>) {
+        let Paragraph { children, position } = paragraph;
+        let mut inlines = Vec::with_capacity(children.len());
+        let mut is_split = false;
+        let mut at_line_start = true;
+        let mut skip_newline = false;
+        let mut children = children.into_iter().peekable();
+        while let Some(child) = children.next() {
+            let text = match child {
+                Node::Text(text) => text,
+                // A hard break after a fence ends its line.
+                Node::Newline(_) if skip_newline => {
+                    skip_newline = false;
+                    at_line_start = true;
+                    continue
+                }
+                child => {
+                    at_line_start = matches!(child, Node::Newline(_));
+                    inlines.push(child);
+                    continue
+                }
+            };
+            skip_newline = false;
+            let ends_line = matches!(children.peek(), None | Some(Node::Newline(_)));
+            let lines: Vec<&str> = text.value.split('\n').map(|x| x.trim_end_matches('\r')).collect();
+            let mut rest = 0;
+            for (index, line) in lines.iter().enumerate() {
+                let is_whole_line = (index > 0 || at_line_start) && (index + 1 < lines.len() || ends_line);
+                let Some(fence) = is_whole_line.then(|| fence(line, stack)).flatten() else { continue };
+                if let Some(before) = text_lines(self.source, &text, &lines, rest..index) {
+                    inlines.push(Node::Text(before));
+                }
+                flush(stack, output, &mut inlines);
+                let range = line_range(self.source, &text, &lines, index);
+                match fence {
+                    Fence::Open { colons, kind, title } => stack.push(Open {
+                        admonition: Admonition { kind, title, children: Vec::new(), position: range },
+                        colons,
+                    }),
+                    Fence::Close => {
+                        let Some(Open { mut admonition, .. }) = stack.pop() else { continue };
+                        if let (Some(position), Some(range)) = (admonition.position.as_mut(), range) {
+                            position.end = range.end;
+                        }
+                        push(stack, output, Node::Admonition(admonition));
+                    }
+                }
+                is_split = true;
+                rest = index + 1;
+                skip_newline = rest == lines.len();
+            }
+            at_line_start = text.value.ends_with('\n');
+            if let Some(after) = text_lines(self.source, &text, &lines, rest..lines.len()) {
+                inlines.push(Node::Text(after));
+            }
+        }
+        match is_split {
+            true => flush(stack, output, &mut inlines),
+            false => push(stack, output, Node::Paragraph(Paragraph { children: inlines, position })),
+        }
+    }
+    /// Drops the first line of the paragraph, returns whether anything is
+    /// left.
+    fn remove_first_line(&self, paragraph: &mut Paragraph) -> bool {
+        let Some(Node::Text(text)) = paragraph.children.first_mut() else { return false };
+        let lines: Vec<&str> = text.value.split('\n').map(|x| x.trim_end_matches('\r')).collect();
+        match text_lines(self.source, text, &lines, 1..lines.len()) {
+            Some(rest) => *text = rest,
+            None => {
+                paragraph.children.remove(0);
+                // A hard break ends the line too.
+                if let Some(Node::Newline(_)) = paragraph.children.first() {
+                    paragraph.children.remove(0);
+                }
+            }
+        }
+        let start = paragraph.children.first().and_then(|x| x.position()).map(|x| x.start.clone());
+        if let (Some(position), Some(start)) = (paragraph.position.as_mut(), start) {
+            position.start = start;
+        }
+        !paragraph.children.is_empty()
+    }
+}
+
+/// The text made of a range of the lines of `text`, `None` if empty.
+pub(crate) fn text_lines(source: &str, text: &Text, lines: &[&str], range: Range<usize>) -> Option<Text> {
+    if range.len() == lines.len() {
+        return Some(text.clone())
+    }
+    let value = lines.get(range.clone())?.join("\n");
+    if value.is_empty() {
+        return None
+    }
+    let start = line_range(source, text, lines, range.start)?.start;
+    let end = line_range(source, text, lines, range.end - 1)?.end;
+    Some(Text { value, position: Some(SourceRange { start, end }) })
+}
+
+/// Where line `index` of the value of `text` is in the source:
+/// continuation lines lose their indent and container prefixes in the
+/// value.
+pub(crate) fn line_range(source: &str, text: &Text, lines: &[&str], index: usize) -> Option<SourceRange> {
+    let position = text.position.as_ref()?;
+    let line = lines.get(index)?;
+    let start = match index {
+        0 => position.start.clone(),
+        _ => {
+            let mut offset = position.start.offset;
+            for _ in 0..index {
+                offset += source.get(offset..)?.find('\n')? + 1;
+            }
+            let line_end = offset + source[offset..].find(['\n', '\r']).unwrap_or(source.len() - offset);
+            let offset = offset + source[offset..line_end].find(line).unwrap_or(0);
+            point(source, offset, position.start.line + index)
+        }
+    };
+    let end = match index + 1 == lines.len() {
+        true => position.end.clone(),
+        false => point(source, start.offset + line.len(), start.line),
+    };
+    Some(SourceRange { start, end })
+}
+
+/// A point without Unicode offsets, which are added after all passes.
+pub(crate) fn point(source: &str, offset: usize, line: usize) -> Point {
+    Point {
+        line,
+        column: offset - line_start(source, offset) + 1,
+        offset,
+        utf16_offset: None,
+        scalar_offset: None,
+    }
+}
+
+/// What a whole line of a paragraph does to the open containers.
+enum Fence {
+    Open { colons: usize, kind: String, title: Option<String> },
+    Close,
+}
+
+fn fence(line: &str, stack: &[Open]) -> Option<Fence> {
+    if let Some((colons, kind, title)) = opening_fence(line) {
+        return Some(Fence::Open { colons, kind, title })
+    }
+    let open = stack.last()?;
+    (closing_fence(line) >= open.colons).then_some(Fence::Close)
+}
+
+/// Adds the pending inlines as a paragraph, dropping a trailing hard break.
+fn flush(stack: &mut [Open], output: &mut Vec<Node>, inlines: &mut Vec<Node>) {
+    while let Some(Node::Newline(_)) = inlines.last() {
+        inlines.pop();
+    }
+    if inlines.is_empty() {
+        return
+    }
+    let children = std::mem::take(inlines);
+    let start = children.first().and_then(|x| x.position()).map(|x| x.start.clone());
+    let end = children.last().and_then(|x| x.position()).map(|x| x.end.clone());
+    let position = start.zip(end).map(|(start, end)| SourceRange { start, end });
+    push(stack, output, Node::Paragraph(Paragraph { children, position }));
+}
+
+/// Adds the node to the innermost open container, or to the output.
+fn push(stack: &mut [Open], output: &mut Vec<Node>, node: Node) {
+    let Some(open) = stack.last_mut() else {
+        output.push(node);
+        return
+    };
+    let end = node.position().map(|x| x.end.clone());
+    if let (Some(position), Some(end)) = (open.admonition.position.as_mut(), end) {
+        position.end = end;
+    }
+    open.admonition.children.push(node);
+}
+
+/// The first line of a paragraph that starts with text.
+fn first_line(paragraph: &Paragraph) -> Option<String> {
+    let Some(Node::Text(text)) = paragraph.children.first() else { return None };
+    let line = text.value.split('\n').next()?;
+    let is_whole_line = line.len() < text.value.len()
+        || paragraph.children.len() == 1
+        || matches!(paragraph.children.get(1), Some(Node::Newline(_)));
+    is_whole_line.then(|| line.to_owned())
+}
+
+/// `:::kind`, `:::kind Title` or `:::kind[Title]`, returns the number of
+/// colons, the kind and the title.
+fn opening_fence(line: &str) -> Option<(usize, String, Option<String>)> {
+    let colons = line.len() - line.trim_start_matches(':').len();
+    if colons < 3 {
+        return None
+    }
+    let rest = &line[colons..];
+    let kind_length = rest.find(|x: char| !x.is_ascii_alphanumeric() && x != '-' && x != '_').unwrap_or(rest.len());
+    if kind_length == 0 {
+        return None
+    }
+    let (kind, title) = rest.split_at(kind_length);
+    let title = match title.strip_prefix('[') {
+        Some(title) => title.strip_suffix(']')?,
+        None if title.is_empty() || title.starts_with([' ', '\t']) => title.trim(),
+        None => return None,
+    };
+    let title = Some(title).filter(|x| !x.is_empty()).map(str::to_owned);
+    Some((colons, kind.to_ascii_lowercase(), title))
+}
+
+/// The number of colons of a closing fence, `0` if the line is not one.
+fn closing_fence(line: &str) -> usize {
+    let line = line.trim_end();
+    match line.len() >= 3 && line.chars().all(|x| x == ':') {
+        true => line.len(),
+        false => 0,
+    }
+}
+
+/// Whether a paragraph among the nodes may contain a fence.
+fn has_fences(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| {
+        let Node::Paragraph(paragraph) = node else { return false };
+        paragraph.children.iter().any(|x| matches!(x, Node::Text(text) if text.value.contains(":::")))
+    })
+}
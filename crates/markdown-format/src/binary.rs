@@ -0,0 +1,949 @@
+//! Flat binary encoding of the AST that is read in place.
+//!
+//! Unlike the JSON and property list encodings, which repeat every string of
+//! the tree, the binary encoding is a table of fixed size node records plus a
+//! table of string fields that point either into the source the nodes were
+//! parsed from or into a deduplicated string pool. Reading it takes the
+//! source next to the buffer, see [`BinaryDocument`].
+//!
+//! ```
+//! use markdown_format::binary::{self, BinaryDocument, Field};
+//! let source = "# Hello *World*";
+//! let nodes = markdown_format::parse(source).unwrap();
+//! let bytes = binary::encode(&nodes, source).unwrap();
+//! let document = BinaryDocument::new(&bytes, source).unwrap();
+//! let heading = document.node(0).unwrap();
+//! let text = document.node(heading.children().start).unwrap();
+//! assert_eq!(text.string(Field::Value), Some("Hello "));
+//! assert_eq!(document.decode(), nodes);
+//! ```
+//!
+//! # Layout
+//!
+//! Integers are little-endian, every section starts at a multiple of 4 bytes
+//! from the start of the buffer. The buffer itself is a `Vec<u8>`, aligned to
+//! 1 byte only, so readers load integers unaligned or byte by byte rather
+//! than through pointers to `u32`s or records.
+//!
+//! - The header, [`HEADER_SIZE`] bytes: [`MAGIC`] followed by the `u32`s
+//!   `version`, `flags`, `node_count`, `root_count`, `field_count`,
+//!   `pool_length` and `source_length`.
+//! - `node_count` node records of [`NODE_SIZE`] bytes in level order, so that
+//!   the children of a node are contiguous and the top-level nodes are
+//!   `0..root_count`:
+//!   - `u8` kind, 1-indexed in the order of [`crate::visit::NodeKind`].
+//!   - `u8` flags, see [`FLAG_HAS_POSITION`] and the others.
+//!   - `u8` detail: the heading level, the reference kind of references (1
+//!     shortcut, 2 collapsed, 3 full) or the checked state of list items (0
+//!     none, 1 unchecked, 2 checked).
+//!   - `u8` padding.
+//!   - `u32`s `parent` ([`NONE`] for top-level nodes), `children_start`,
+//!     `children_length`, `fields_start` (the fields of a node run up to the
+//!     `fields_start` of the next node, or `field_count`) and `number` (the
+//!     start of ordered lists or the number of footnotes, see
+//!     [`FLAG_HAS_NUMBER`]).
+//!   - `u32`s `line`, `column` and `offset` of the start and of the end.
+//! - If the header has [`HEADER_UNICODE_OFFSETS`], `node_count` records of
+//!   [`UNICODE_OFFSETS_SIZE`] bytes: the `u32`s `utf16_offset` and
+//!   `scalar_offset` of the start and of the end, [`NONE`] when unknown.
+//! - `field_count` field records of [`FIELD_SIZE`] bytes: a `u16` [`Field`],
+//!   a `u16` storage ([`STORAGE_POOL`] or [`STORAGE_SOURCE`]) and the `u32`s
+//!   `offset` and `length` of its bytes.
+//! - The string pool, `pool_length` bytes.
+//!
+//! [`Field::Alignment`] has one byte per column in the pool (0 none, 1 left,
+//! 2 right, 3 center) and [`Field::Highlights`] has a record of
+//! [`HIGHLIGHT_SIZE`] bytes per token in the pool: the `u32`s `start` and
+//! `end` in the value of the code block, then the pool `offset` and `length`
+//! of the scope.
+use std::collections::HashMap;
+use std::ops::Range;
+use crate::{
+    Admonition, AlignKind, Blockquote, CodeBlock, Definition, DefinitionDescription, DefinitionList,
+    DefinitionTerm, DisplayMath, Embed, Emphasis, Error, FootnoteDefinition, FootnoteReference,
+    Heading, HeadingLevel, HighlightSpan, HorizontalDivider, Html, Image, ImageReference,
+    InlineCode, InlineMath, Link, LinkReference, List, ListItem, Newline, Node, Paragraph, Point,
+    ReferenceKind, SourceRange, Strikethrough, Strong, Table, TableCell, TableRow, Text, Toml,
+    WikiLink, Yaml,
+};
+use crate::visit::{self, NodeKind};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # LAYOUT
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+pub const MAGIC: [u8; 4] = *b"MDAB";
+/// The layout version written by this crate, bumped whenever existing readers
+/// can't read the new layout.
+pub const VERSION: u32 = 1;
+pub const HEADER_SIZE: usize = 32;
+pub const NODE_SIZE: usize = 48;
+pub const UNICODE_OFFSETS_SIZE: usize = 16;
+pub const FIELD_SIZE: usize = 12;
+pub const HIGHLIGHT_SIZE: usize = 16;
+/// A missing parent or Unicode offset.
+pub const NONE: u32 = u32::MAX;
+
+/// Header flag: the Unicode offsets section is present.
+pub const HEADER_UNICODE_OFFSETS: u32 = 1;
+
+/// Node flag: the position words are set.
+pub const FLAG_HAS_POSITION: u8 = 1;
+/// Node flag of lists.
+pub const FLAG_ORDERED: u8 = 2;
+/// Node flag of lists, list items and definition lists.
+pub const FLAG_SPREAD: u8 = 4;
+/// Node flag: the `number` word is set.
+pub const FLAG_HAS_NUMBER: u8 = 8;
+const FLAGS: u8 = FLAG_HAS_POSITION | FLAG_ORDERED | FLAG_SPREAD | FLAG_HAS_NUMBER;
+
+/// The field's bytes are in the string pool.
+pub const STORAGE_POOL: u16 = 0;
+/// The field's bytes are in the source, which isn't part of the encoding.
+pub const STORAGE_SOURCE: u16 = 1;
+
+/// The fields of a node other than its children and attributes, numbered as
+/// stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum Field {
+    /// Content of text, code, math, frontmatter and HTML.
+    Value = 1,
+    Url = 2,
+    Title = 3,
+    Alt = 4,
+    Lang = 5,
+    Meta = 6,
+    Identifier = 7,
+    Label = 8,
+    /// Of admonitions.
+    Kind = 9,
+    Target = 10,
+    Alias = 11,
+    HeadingAnchor = 12,
+    /// Of tables, not a string.
+    Alignment = 13,
+    /// Of code blocks, not a string.
+    Highlights = 14,
+}
+
+const FIELDS: [Field; 14] = [
+    Field::Value,
+    Field::Url,
+    Field::Title,
+    Field::Alt,
+    Field::Lang,
+    Field::Meta,
+    Field::Identifier,
+    Field::Label,
+    Field::Kind,
+    Field::Target,
+    Field::Alias,
+    Field::HeadingAnchor,
+    Field::Alignment,
+    Field::Highlights,
+];
+
+const KINDS: [NodeKind; 34] = [
+    NodeKind::Text,
+    NodeKind::Newline,
+    NodeKind::Emphasis,
+    NodeKind::Strong,
+    NodeKind::Strikethrough,
+    NodeKind::Image,
+    NodeKind::ImageReference,
+    NodeKind::Link,
+    NodeKind::LinkReference,
+    NodeKind::CodeBlock,
+    NodeKind::InlineCode,
+    NodeKind::List,
+    NodeKind::ListItem,
+    NodeKind::Heading,
+    NodeKind::Table,
+    NodeKind::TableRow,
+    NodeKind::TableCell,
+    NodeKind::HorizontalDivider,
+    NodeKind::Definition,
+    NodeKind::Paragraph,
+    NodeKind::Blockquote,
+    NodeKind::FootnoteReference,
+    NodeKind::FootnoteDefinition,
+    NodeKind::DisplayMath,
+    NodeKind::InlineMath,
+    NodeKind::Toml,
+    NodeKind::Yaml,
+    NodeKind::Html,
+    NodeKind::Admonition,
+    NodeKind::WikiLink,
+    NodeKind::Embed,
+    NodeKind::DefinitionList,
+    NodeKind::DefinitionTerm,
+    NodeKind::DefinitionDescription,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryError {
+    /// Doesn't start with [`MAGIC`].
+    NotBinary,
+    /// Written by a newer version of this crate.
+    UnsupportedVersion(u32),
+    /// Encoded from a source of another length than the one given.
+    SourceMismatch,
+    /// Truncated, or a record that is out of range or not a tree.
+    Malformed(&'static str),
+    /// Counts and offsets are 32 bit.
+    TooLarge,
+}
+
+impl std::fmt::Display for BinaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotBinary => f.write_str("not a binary encoded document"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported binary layout version {version} (expected at most {VERSION})")
+            }
+            Self::SourceMismatch => f.write_str("the document was encoded from another source"),
+            Self::Malformed(reason) => write!(f, "malformed binary document: {reason}"),
+            Self::TooLarge => f.write_str("document too large for the binary encoding"),
+        }
+    }
+}
+impl std::error::Error for BinaryError {}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # ENCODE
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Encodes the nodes parsed from `source`, strings that appear verbatim in
+/// the source within their node's position are stored as references to it.
+pub fn encode(nodes: &[Node], source: &str) -> Result<Vec<u8>, Error> {
+    Writer::new(source).write(nodes).map_err(|x| Error::Serialization(Box::new(x)))
+}
+
+struct Writer<'a> {
+    source: &'a str,
+    nodes: Vec<u8>,
+    unicode_offsets: Vec<u8>,
+    has_unicode_offsets: bool,
+    fields: Vec<u8>,
+    field_count: usize,
+    pool: Vec<u8>,
+    pooled: HashMap<&'a str, u32>,
+    /// Byte range of the source of the node being written.
+    span: Option<Range<usize>>,
+}
+
+impl<'a> Writer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            nodes: Vec::new(),
+            unicode_offsets: Vec::new(),
+            has_unicode_offsets: false,
+            fields: Vec::new(),
+            field_count: 0,
+            pool: Vec::new(),
+            pooled: HashMap::new(),
+            span: None,
+        }
+    }
+    fn write(mut self, roots: &'a [Node]) -> Result<Vec<u8>, BinaryError> {
+        let nodes = visit::breadth_first(roots).collect::<Vec<_>>();
+        if nodes.len() >= NONE as usize {
+            return Err(BinaryError::TooLarge)
+        }
+        let mut parents = vec![NONE; roots.len()];
+        let mut next_child = roots.len();
+        for (index, node) in nodes.iter().enumerate() {
+            let children = node.children().len();
+            let children_start = if children == 0 { 0 } else { next_child };
+            self.node(node, parents[index], children_start..children_start + children)?;
+            parents.resize(parents.len() + children, index as u32);
+            next_child += children;
+        }
+        let mut output = Vec::with_capacity(
+            HEADER_SIZE + self.nodes.len() + self.unicode_offsets.len() + self.fields.len() + self.pool.len()
+        );
+        output.extend_from_slice(&MAGIC);
+        let flags = if self.has_unicode_offsets { HEADER_UNICODE_OFFSETS } else { 0 };
+        for word in [VERSION, flags, to_u32(nodes.len())?, to_u32(roots.len())?, to_u32(self.field_count)?, to_u32(self.pool.len())?, to_u32(self.source.len())?] {
+            output.extend_from_slice(&word.to_le_bytes());
+        }
+        output.extend_from_slice(&self.nodes);
+        if self.has_unicode_offsets {
+            output.extend_from_slice(&self.unicode_offsets);
+        }
+        output.extend_from_slice(&self.fields);
+        output.extend_from_slice(&self.pool);
+        Ok(output)
+    }
+    fn node(&mut self, node: &'a Node, parent: u32, children: Range<usize>) -> Result<(), BinaryError> {
+        let position = node.position();
+        self.span = position.map(|x| x.start.offset..x.end.offset);
+        let fields_start = to_u32(self.field_count)?;
+        let mut flags = 0;
+        let mut detail = 0;
+        let mut number = None;
+        match node {
+            Node::Text(x) => self.string(Field::Value, Some(&x.value))?,
+            Node::Image(x) => {
+                self.string(Field::Alt, Some(&x.alt))?;
+                self.string(Field::Url, Some(&x.url))?;
+                self.string(Field::Title, x.title.as_deref())?;
+            }
+            Node::ImageReference(x) => {
+                self.string(Field::Alt, Some(&x.alt))?;
+                self.reference(&x.identifier, x.label.as_deref(), x.url.as_deref(), x.title.as_deref())?;
+                detail = reference_kind_code(&x.reference_kind);
+            }
+            Node::Link(x) => {
+                self.string(Field::Url, Some(&x.url))?;
+                self.string(Field::Title, x.title.as_deref())?;
+            }
+            Node::LinkReference(x) => {
+                self.reference(&x.identifier, x.label.as_deref(), x.url.as_deref(), x.title.as_deref())?;
+                detail = reference_kind_code(&x.reference_kind);
+            }
+            Node::CodeBlock(x) => {
+                self.string(Field::Value, Some(&x.value))?;
+                self.string(Field::Lang, x.lang.as_deref())?;
+                self.string(Field::Meta, x.meta.as_deref())?;
+                if let Some(highlights) = &x.highlights {
+                    let mut payload = Vec::with_capacity(highlights.len() * HIGHLIGHT_SIZE);
+                    for span in highlights {
+                        let scope = self.pooled(&span.scope)?;
+                        for word in [to_u32(span.range.start)?, to_u32(span.range.end)?, scope, to_u32(span.scope.len())?] {
+                            payload.extend_from_slice(&word.to_le_bytes());
+                        }
+                    }
+                    self.payload(Field::Highlights, &payload)?;
+                }
+            }
+            Node::List(x) => {
+                flags |= if x.ordered { FLAG_ORDERED } else { 0 };
+                flags |= if x.spread { FLAG_SPREAD } else { 0 };
+                number = x.start;
+            }
+            Node::ListItem(x) => {
+                flags |= if x.spread { FLAG_SPREAD } else { 0 };
+                detail = match x.checked {
+                    None => 0,
+                    Some(false) => 1,
+                    Some(true) => 2,
+                };
+            }
+            Node::Heading(x) => {
+                detail = match x.level {
+                    HeadingLevel::H1 => 1,
+                    HeadingLevel::H2 => 2,
+                    HeadingLevel::H3 => 3,
+                    HeadingLevel::H4 => 4,
+                    HeadingLevel::H5 => 5,
+                    HeadingLevel::H6 => 6,
+                };
+            }
+            Node::Table(x) if !x.alignment.is_empty() => {
+                let payload = x.alignment
+                    .iter()
+                    .map(|x| match x {
+                        AlignKind::None => 0,
+                        AlignKind::Left => 1,
+                        AlignKind::Right => 2,
+                        AlignKind::Center => 3,
+                    })
+                    .collect::<Vec<u8>>();
+                self.payload(Field::Alignment, &payload)?;
+            }
+            Node::Definition(x) => {
+                self.string(Field::Url, Some(&x.url))?;
+                self.string(Field::Title, x.title.as_deref())?;
+                self.string(Field::Identifier, Some(&x.identifier))?;
+                self.string(Field::Label, x.label.as_deref())?;
+            }
+            Node::FootnoteReference(x) => {
+                self.string(Field::Identifier, Some(&x.identifier))?;
+                self.string(Field::Label, x.label.as_deref())?;
+                number = x.number.map(to_u32).transpose()?;
+            }
+            Node::FootnoteDefinition(x) => {
+                self.string(Field::Identifier, Some(&x.identifier))?;
+                self.string(Field::Label, x.label.as_deref())?;
+                number = x.number.map(to_u32).transpose()?;
+            }
+            Node::DisplayMath(x) => {
+                self.string(Field::Value, Some(&x.value))?;
+                self.string(Field::Meta, x.meta.as_deref())?;
+            }
+            Node::InlineCode(InlineCode { value, .. })
+            | Node::InlineMath(InlineMath { value, .. })
+            | Node::Toml(Toml { value, .. })
+            | Node::Yaml(Yaml { value, .. })
+            | Node::Html(Html { value, .. }) => self.string(Field::Value, Some(value))?,
+            Node::Admonition(x) => {
+                self.string(Field::Kind, Some(&x.kind))?;
+                self.string(Field::Title, x.title.as_deref())?;
+            }
+            Node::DefinitionList(x) => flags |= if x.spread { FLAG_SPREAD } else { 0 },
+            Node::WikiLink(x) => self.wiki_link(&x.target, x.alias.as_deref(), x.heading_anchor.as_deref(), x.url.as_deref())?,
+            Node::Embed(x) => self.wiki_link(&x.target, x.alias.as_deref(), x.heading_anchor.as_deref(), x.url.as_deref())?,
+            _ => (),
+        }
+        flags |= if position.is_some() { FLAG_HAS_POSITION } else { 0 };
+        flags |= if number.is_some() { FLAG_HAS_NUMBER } else { 0 };
+        self.nodes.extend_from_slice(&[node.kind() as u8 + 1, flags, detail, 0]);
+        let (start, end) = match position {
+            Some(position) => (&position.start, &position.end),
+            None => (&ZERO, &ZERO),
+        };
+        let words = [
+            parent,
+            to_u32(children.start)?,
+            to_u32(children.len())?,
+            fields_start,
+            number.unwrap_or_default(),
+            to_u32(start.line)?,
+            to_u32(start.column)?,
+            to_u32(start.offset)?,
+            to_u32(end.line)?,
+            to_u32(end.column)?,
+            to_u32(end.offset)?,
+        ];
+        for word in words {
+            self.nodes.extend_from_slice(&word.to_le_bytes());
+        }
+        for offset in [start.utf16_offset, start.scalar_offset, end.utf16_offset, end.scalar_offset] {
+            self.has_unicode_offsets |= offset.is_some();
+            let word = offset.map(to_u32).transpose()?.unwrap_or(NONE);
+            self.unicode_offsets.extend_from_slice(&word.to_le_bytes());
+        }
+        Ok(())
+    }
+    fn reference(&mut self, identifier: &'a str, label: Option<&'a str>, url: Option<&'a str>, title: Option<&'a str>) -> Result<(), BinaryError> {
+        self.string(Field::Identifier, Some(identifier))?;
+        self.string(Field::Label, label)?;
+        self.string(Field::Url, url)?;
+        self.string(Field::Title, title)
+    }
+    fn wiki_link(&mut self, target: &'a str, alias: Option<&'a str>, heading_anchor: Option<&'a str>, url: Option<&'a str>) -> Result<(), BinaryError> {
+        self.string(Field::Target, Some(target))?;
+        self.string(Field::Alias, alias)?;
+        self.string(Field::HeadingAnchor, heading_anchor)?;
+        self.string(Field::Url, url)
+    }
+    /// Stores `None` as a missing field.
+    fn string(&mut self, key: Field, value: Option<&'a str>) -> Result<(), BinaryError> {
+        let Some(value) = value else {
+            return Ok(())
+        };
+        let in_source = self.span
+            .clone()
+            .filter(|_| !value.is_empty())
+            .and_then(|span| Some(span.start + self.source.get(span)?.find(value)?));
+        match in_source {
+            Some(offset) => self.field(key, STORAGE_SOURCE, to_u32(offset)?, value.len()),
+            None => {
+                let offset = self.pooled(value)?;
+                self.field(key, STORAGE_POOL, offset, value.len())
+            }
+        }
+    }
+    fn payload(&mut self, key: Field, payload: &[u8]) -> Result<(), BinaryError> {
+        let offset = to_u32(self.pool.len())?;
+        self.pool.extend_from_slice(payload);
+        self.field(key, STORAGE_POOL, offset, payload.len())
+    }
+    fn field(&mut self, key: Field, storage: u16, offset: u32, length: usize) -> Result<(), BinaryError> {
+        self.fields.extend_from_slice(&(key as u16).to_le_bytes());
+        self.fields.extend_from_slice(&storage.to_le_bytes());
+        self.fields.extend_from_slice(&offset.to_le_bytes());
+        self.fields.extend_from_slice(&to_u32(length)?.to_le_bytes());
+        self.field_count += 1;
+        Ok(())
+    }
+    /// Pool offset of the string, written once.
+    fn pooled(&mut self, value: &'a str) -> Result<u32, BinaryError> {
+        if let Some(offset) = self.pooled.get(value) {
+            return Ok(*offset)
+        }
+        let offset = to_u32(self.pool.len())?;
+        self.pool.extend_from_slice(value.as_bytes());
+        self.pooled.insert(value, offset);
+        Ok(offset)
+    }
+}
+
+const ZERO: Point = Point { line: 0, column: 0, offset: 0, utf16_offset: None, scalar_offset: None };
+
+fn to_u32(value: usize) -> Result<u32, BinaryError> {
+    u32::try_from(value).map_err(|_| BinaryError::TooLarge)
+}
+
+fn reference_kind_code(kind: &ReferenceKind) -> u8 {
+    match kind {
+        ReferenceKind::Shortcut => 1,
+        ReferenceKind::Collapsed => 2,
+        ReferenceKind::Full => 3,
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # READ
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A validated view of an encoded document, its strings borrow from the
+/// buffer and the source.
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryDocument<'a> {
+    source: &'a str,
+    root_count: usize,
+    nodes: &'a [u8],
+    unicode_offsets: Option<&'a [u8]>,
+    fields: &'a [u8],
+    pool: &'a [u8],
+}
+
+/// A node of a [`BinaryDocument`].
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryNode<'a> {
+    document: BinaryDocument<'a>,
+    index: usize,
+}
+
+impl<'a> BinaryDocument<'a> {
+    /// Checks the whole buffer once, in time linear in the number of nodes
+    /// and pooled bytes, so that reading it can't go out of bounds.
+    pub fn new(bytes: &'a [u8], source: &'a str) -> Result<Self, BinaryError> {
+        if bytes.get(..MAGIC.len()) != Some(&MAGIC[..]) {
+            return Err(BinaryError::NotBinary)
+        }
+        let header = bytes.get(..HEADER_SIZE).ok_or(BinaryError::Malformed("truncated header"))?;
+        let word = |index: usize| word_at(header, index * 4) as usize;
+        let version = word(1) as u32;
+        if version == 0 || version > VERSION {
+            return Err(BinaryError::UnsupportedVersion(version))
+        }
+        let flags = word(2) as u32;
+        if flags & !HEADER_UNICODE_OFFSETS != 0 {
+            return Err(BinaryError::Malformed("unknown header flags"))
+        }
+        let (node_count, root_count, field_count, pool_length) = (word(3), word(4), word(5), word(6));
+        if word(7) != source.len() {
+            return Err(BinaryError::SourceMismatch)
+        }
+        if root_count > node_count {
+            return Err(BinaryError::Malformed("more top-level nodes than nodes"))
+        }
+        let unicode_offsets_count = if flags & HEADER_UNICODE_OFFSETS != 0 { node_count } else { 0 };
+        let sections = [
+            node_count.checked_mul(NODE_SIZE),
+            unicode_offsets_count.checked_mul(UNICODE_OFFSETS_SIZE),
+            field_count.checked_mul(FIELD_SIZE),
+            Some(pool_length),
+        ];
+        let mut rest = &bytes[HEADER_SIZE..];
+        let mut sections = sections.map(|length| {
+            let section = rest.get(..length?)?;
+            rest = &rest[section.len()..];
+            Some(section)
+        });
+        if !rest.is_empty() || sections.iter().any(Option::is_none) {
+            return Err(BinaryError::Malformed("section lengths don't add up to the buffer length"))
+        }
+        let mut take = |index: usize| sections[index].take().unwrap_or_default();
+        let document = BinaryDocument {
+            source,
+            root_count,
+            nodes: take(0),
+            unicode_offsets: Some(take(1)).filter(|_| unicode_offsets_count > 0),
+            fields: take(2),
+            pool: take(3),
+        };
+        document.validate()?;
+        Ok(document)
+    }
+    /// Number of nodes, valid indices are `0..len`.
+    pub fn len(&self) -> usize {
+        self.nodes.len() / NODE_SIZE
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    /// The indices of the top-level nodes.
+    pub fn roots(&self) -> Range<usize> {
+        0..self.root_count
+    }
+    pub fn node(&self, index: usize) -> Option<BinaryNode<'a>> {
+        (index < self.len()).then_some(BinaryNode { document: *self, index })
+    }
+    /// Rebuilds the AST, equal to the encoded one.
+    pub fn decode(&self) -> Vec<Node> {
+        // Children come after their parent, so building from the back has them
+        // ready (without recursing as deep as the tree).
+        let mut decoded: Vec<Option<Node>> = Vec::with_capacity(self.len());
+        decoded.resize_with(self.len(), || None);
+        for index in (0..self.len()).rev() {
+            let node = BinaryNode { document: *self, index };
+            let children = node.children().map(|x| decoded[x].take().unwrap()).collect();
+            decoded[index] = Some(node.to_node(children));
+        }
+        decoded.truncate(self.root_count);
+        decoded.into_iter().map(Option::unwrap).collect()
+    }
+    fn validate(&self) -> Result<(), BinaryError> {
+        let field_count = self.fields.len() / FIELD_SIZE;
+        let mut fields_start = 0;
+        for index in 0..self.len() {
+            let node = BinaryNode { document: *self, index };
+            let record = node.record();
+            let kind = record[0] as usize;
+            if kind == 0 || kind > KINDS.len() {
+                return Err(BinaryError::Malformed("unknown node kind"))
+            }
+            if record[1] & !FLAGS != 0 {
+                return Err(BinaryError::Malformed("unknown node flags"))
+            }
+            let details = match KINDS[kind - 1] {
+                NodeKind::Heading => 1..=6,
+                NodeKind::ImageReference | NodeKind::LinkReference => 1..=3,
+                NodeKind::ListItem => 0..=2,
+                _ => 0..=0,
+            };
+            if !details.contains(&record[2]) {
+                return Err(BinaryError::Malformed("invalid node detail"))
+            }
+            let parent = node.word(1);
+            let is_child_of_parent = match parent {
+                NONE => index < self.root_count,
+                parent => (parent as usize) < index && self.node(parent as usize).is_some_and(|x| x.children().contains(&index)),
+            };
+            if !is_child_of_parent || (parent == NONE) != (index < self.root_count) {
+                return Err(BinaryError::Malformed("a node isn't a child of its parent"))
+            }
+            let (children_start, children_length) = (node.word(2) as usize, node.word(3) as usize);
+            if children_length > 0 {
+                let in_range = children_start > index
+                    && children_start.checked_add(children_length).is_some_and(|end| end <= self.len());
+                if !in_range || !is_parent(KINDS[kind - 1]) {
+                    return Err(BinaryError::Malformed("invalid children"))
+                }
+                let children = children_start..children_start + children_length;
+                if children.into_iter().any(|x| word_at(self.nodes, x * NODE_SIZE + 4) as usize != index) {
+                    return Err(BinaryError::Malformed("a child isn't a child of its parent"))
+                }
+            }
+            let start = node.word(4) as usize;
+            if start < fields_start || start > field_count {
+                return Err(BinaryError::Malformed("invalid fields"))
+            }
+            fields_start = start;
+        }
+        for index in 0..field_count {
+            let (key, storage, range) = self.field(index);
+            let Some(&key) = FIELDS.get((key as usize).wrapping_sub(1)) else {
+                return Err(BinaryError::Malformed("unknown field"))
+            };
+            let is_valid = match (key, storage) {
+                (Field::Alignment, STORAGE_POOL) => self.pool.get(range).is_some_and(|x| x.iter().all(|x| *x <= 3)),
+                (Field::Highlights, STORAGE_POOL) => self.pool.get(range).is_some_and(|payload| {
+                    payload.len() % HIGHLIGHT_SIZE == 0 && payload.chunks(HIGHLIGHT_SIZE).all(|record| {
+                        let scope = word_at(record, 8) as usize..(word_at(record, 8) as usize).saturating_add(word_at(record, 12) as usize);
+                        word_at(record, 0) <= word_at(record, 4) && self.pool.get(scope).is_some_and(|x| std::str::from_utf8(x).is_ok())
+                    })
+                }),
+                (Field::Alignment | Field::Highlights, _) => false,
+                (_, STORAGE_POOL) => self.pool.get(range).is_some_and(|x| std::str::from_utf8(x).is_ok()),
+                (_, STORAGE_SOURCE) => self.source.get(range).is_some(),
+                _ => false,
+            };
+            if !is_valid {
+                return Err(BinaryError::Malformed("invalid field"))
+            }
+        }
+        Ok(())
+    }
+    /// Key, storage and byte range of the field at `index`.
+    fn field(&self, index: usize) -> (u16, u16, Range<usize>) {
+        let record = &self.fields[index * FIELD_SIZE..(index + 1) * FIELD_SIZE];
+        let key = u16::from_le_bytes([record[0], record[1]]);
+        let storage = u16::from_le_bytes([record[2], record[3]]);
+        let offset = word_at(record, 4) as usize;
+        (key, storage, offset..offset.saturating_add(word_at(record, 8) as usize))
+    }
+}
+
+impl<'a> BinaryNode<'a> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+    pub fn kind(&self) -> NodeKind {
+        KINDS[self.record()[0] as usize - 1]
+    }
+    /// `None` for top-level nodes.
+    pub fn parent(&self) -> Option<usize> {
+        Some(self.word(1)).filter(|x| *x != NONE).map(|x| x as usize)
+    }
+    /// The indices of the node's children.
+    pub fn children(&self) -> Range<usize> {
+        match self.word(3) as usize {
+            0 => 0..0,
+            length => self.word(2) as usize..self.word(2) as usize + length,
+        }
+    }
+    pub fn position(&self) -> Option<SourceRange> {
+        if self.record()[1] & FLAG_HAS_POSITION == 0 {
+            return None
+        }
+        let offsets = self.document.unicode_offsets.map(|x| &x[self.index * UNICODE_OFFSETS_SIZE..][..UNICODE_OFFSETS_SIZE]);
+        let offset = |index: usize| offsets.map(|x| word_at(x, index * 4)).filter(|x| *x != NONE).map(|x| x as usize);
+        let point = |word: usize, unicode: usize| Point {
+            line: self.word(word) as usize,
+            column: self.word(word + 1) as usize,
+            offset: self.word(word + 2) as usize,
+            utf16_offset: offset(unicode),
+            scalar_offset: offset(unicode + 1),
+        };
+        Some(SourceRange { start: point(6, 0), end: point(9, 2) })
+    }
+    /// The string field, `None` if the node has no such field (or it is
+    /// `None`).
+    pub fn string(&self, field: Field) -> Option<&'a str> {
+        let (storage, range) = self.field(field)?;
+        match storage {
+            STORAGE_SOURCE => self.document.source.get(range),
+            _ => std::str::from_utf8(self.document.pool.get(range)?).ok(),
+        }
+    }
+    /// Of lists.
+    pub fn ordered(&self) -> bool {
+        self.record()[1] & FLAG_ORDERED != 0
+    }
+    /// Of lists, list items and definition lists.
+    pub fn spread(&self) -> bool {
+        self.record()[1] & FLAG_SPREAD != 0
+    }
+    /// Of list items.
+    pub fn checked(&self) -> Option<bool> {
+        match (self.kind(), self.record()[2]) {
+            (NodeKind::ListItem, 1) => Some(false),
+            (NodeKind::ListItem, 2) => Some(true),
+            _ => None,
+        }
+    }
+    pub fn heading_level(&self) -> Option<HeadingLevel> {
+        if self.kind() != NodeKind::Heading {
+            return None
+        }
+        let levels = [HeadingLevel::H1, HeadingLevel::H2, HeadingLevel::H3, HeadingLevel::H4, HeadingLevel::H5, HeadingLevel::H6];
+        levels.get((self.record()[2] as usize).wrapping_sub(1)).cloned()
+    }
+    /// Of link and image references.
+    pub fn reference_kind(&self) -> Option<ReferenceKind> {
+        match (self.kind(), self.record()[2]) {
+            (NodeKind::LinkReference | NodeKind::ImageReference, 1) => Some(ReferenceKind::Shortcut),
+            (NodeKind::LinkReference | NodeKind::ImageReference, 2) => Some(ReferenceKind::Collapsed),
+            (NodeKind::LinkReference | NodeKind::ImageReference, 3) => Some(ReferenceKind::Full),
+            _ => None,
+        }
+    }
+    /// The start of ordered lists or the number of footnotes.
+    pub fn number(&self) -> Option<u32> {
+        Some(self.word(5)).filter(|_| self.record()[1] & FLAG_HAS_NUMBER != 0)
+    }
+    /// Of tables, empty for other nodes.
+    pub fn alignment(&self) -> impl Iterator<Item = AlignKind> + 'a {
+        let payload = self.payload(Field::Alignment).unwrap_or_default();
+        payload.iter().map(|x| match x {
+            1 => AlignKind::Left,
+            2 => AlignKind::Right,
+            3 => AlignKind::Center,
+            _ => AlignKind::None,
+        })
+    }
+    /// Byte range in the value and scope of the tokens of code blocks, `None`
+    /// unless parsed with [`crate::ParseConfig::highlight`].
+    pub fn highlights(&self) -> Option<impl Iterator<Item = (Range<usize>, &'a str)> + 'a> {
+        let pool = self.document.pool;
+        let payload = self.payload(Field::Highlights)?;
+        Some(payload.chunks_exact(HIGHLIGHT_SIZE).map(move |record| {
+            let scope = word_at(record, 8) as usize..word_at(record, 8) as usize + word_at(record, 12) as usize;
+            let range = word_at(record, 0) as usize..word_at(record, 4) as usize;
+            (range, std::str::from_utf8(&pool[scope]).unwrap_or_default())
+        }))
+    }
+    fn record(&self) -> &'a [u8] {
+        &self.document.nodes[self.index * NODE_SIZE..(self.index + 1) * NODE_SIZE]
+    }
+    /// The `u32` at `index * 4` of the record, the first one holds the kind,
+    /// flags and detail bytes.
+    fn word(&self, index: usize) -> u32 {
+        word_at(self.record(), index * 4)
+    }
+    fn fields(&self) -> Range<usize> {
+        let start = self.word(4) as usize;
+        let end = match self.document.node(self.index + 1) {
+            Some(next) => next.word(4) as usize,
+            None => self.document.fields.len() / FIELD_SIZE,
+        };
+        start..end
+    }
+    fn field(&self, field: Field) -> Option<(u16, Range<usize>)> {
+        self.fields()
+            .map(|index| self.document.field(index))
+            .find(|(key, _, _)| *key == field as u16)
+            .map(|(_, storage, range)| (storage, range))
+    }
+    fn payload(&self, field: Field) -> Option<&'a [u8]> {
+        self.document.pool.get(self.field(field)?.1)
+    }
+    fn to_node(self, children: Vec<Node>) -> Node {
+        let position = self.position();
+        let string = |field| self.string(field).map(str::to_owned);
+        let value = || string(Field::Value).unwrap_or_default();
+        let reference_kind = || self.reference_kind().unwrap_or(ReferenceKind::Shortcut);
+        let number = || self.number().map(|x| x as usize);
+        match self.kind() {
+            NodeKind::Text => Node::Text(Text { value: value(), position }),
+            NodeKind::Newline => Node::Newline(Newline { position }),
+            NodeKind::Emphasis => Node::Emphasis(Emphasis { children, position }),
+            NodeKind::Strong => Node::Strong(Strong { children, position }),
+            NodeKind::Strikethrough => Node::Strikethrough(Strikethrough { children, position }),
+            NodeKind::Image => Node::Image(Image {
+                position,
+                alt: string(Field::Alt).unwrap_or_default(),
+                url: string(Field::Url).unwrap_or_default(),
+                title: string(Field::Title),
+            }),
+            NodeKind::ImageReference => Node::ImageReference(ImageReference {
+                position,
+                alt: string(Field::Alt).unwrap_or_default(),
+                reference_kind: reference_kind(),
+                identifier: string(Field::Identifier).unwrap_or_default(),
+                label: string(Field::Label),
+                url: string(Field::Url),
+                title: string(Field::Title),
+            }),
+            NodeKind::Link => Node::Link(Link {
+                children,
+                position,
+                url: string(Field::Url).unwrap_or_default(),
+                title: string(Field::Title),
+            }),
+            NodeKind::LinkReference => Node::LinkReference(LinkReference {
+                children,
+                position,
+                reference_kind: reference_kind(),
+                identifier: string(Field::Identifier).unwrap_or_default(),
+                label: string(Field::Label),
+                url: string(Field::Url),
+                title: string(Field::Title),
+            }),
+            NodeKind::CodeBlock => Node::CodeBlock(CodeBlock {
+                value: value(),
+                position,
+                lang: string(Field::Lang),
+                meta: string(Field::Meta),
+                highlights: self.highlights().map(|highlights| {
+                    highlights.map(|(range, scope)| HighlightSpan { range, scope: scope.to_owned() }).collect()
+                }),
+            }),
+            NodeKind::InlineCode => Node::InlineCode(InlineCode { value: value(), position }),
+            NodeKind::List => Node::List(List {
+                children,
+                position,
+                ordered: self.ordered(),
+                start: self.number(),
+                spread: self.spread(),
+            }),
+            NodeKind::ListItem => Node::ListItem(ListItem {
+                children,
+                position,
+                spread: self.spread(),
+                checked: self.checked(),
+            }),
+            NodeKind::Heading => Node::Heading(Heading {
+                level: self.heading_level().unwrap_or(HeadingLevel::H1),
+                children,
+                position,
+            }),
+            NodeKind::Table => Node::Table(Table { children, position, alignment: self.alignment().collect() }),
+            NodeKind::TableRow => Node::TableRow(TableRow { children, position }),
+            NodeKind::TableCell => Node::TableCell(TableCell { children, position }),
+            NodeKind::HorizontalDivider => Node::HorizontalDivider(HorizontalDivider { position }),
+            NodeKind::Definition => Node::Definition(Definition {
+                position,
+                url: string(Field::Url).unwrap_or_default(),
+                title: string(Field::Title),
+                identifier: string(Field::Identifier).unwrap_or_default(),
+                label: string(Field::Label),
+            }),
+            NodeKind::Paragraph => Node::Paragraph(Paragraph { children, position }),
+            NodeKind::Blockquote => Node::Blockquote(Blockquote { children, position }),
+            NodeKind::FootnoteReference => Node::FootnoteReference(FootnoteReference {
+                position,
+                identifier: string(Field::Identifier).unwrap_or_default(),
+                label: string(Field::Label),
+                number: number(),
+            }),
+            NodeKind::FootnoteDefinition => Node::FootnoteDefinition(FootnoteDefinition {
+                children,
+                position,
+                identifier: string(Field::Identifier).unwrap_or_default(),
+                label: string(Field::Label),
+                number: number(),
+            }),
+            NodeKind::DisplayMath => Node::DisplayMath(DisplayMath { value: value(), position, meta: string(Field::Meta) }),
+            NodeKind::InlineMath => Node::InlineMath(InlineMath { value: value(), position }),
+            NodeKind::Toml => Node::Toml(Toml { value: value(), position }),
+            NodeKind::Yaml => Node::Yaml(Yaml { value: value(), position }),
+            NodeKind::Html => Node::Html(Html { value: value(), position }),
+            NodeKind::Admonition => Node::Admonition(Admonition {
+                children,
+                position,
+                kind: string(Field::Kind).unwrap_or_default(),
+                title: string(Field::Title),
+            }),
+            NodeKind::WikiLink => Node::WikiLink(WikiLink {
+                position,
+                target: string(Field::Target).unwrap_or_default(),
+                alias: string(Field::Alias),
+                heading_anchor: string(Field::HeadingAnchor),
+                url: string(Field::Url),
+            }),
+            NodeKind::Embed => Node::Embed(Embed {
+                position,
+                target: string(Field::Target).unwrap_or_default(),
+                alias: string(Field::Alias),
+                heading_anchor: string(Field::HeadingAnchor),
+                url: string(Field::Url),
+            }),
+            NodeKind::DefinitionList => Node::DefinitionList(DefinitionList { children, position, spread: self.spread() }),
+            NodeKind::DefinitionTerm => Node::DefinitionTerm(DefinitionTerm { children, position }),
+            NodeKind::DefinitionDescription => Node::DefinitionDescription(DefinitionDescription { children, position }),
+        }
+    }
+}
+
+fn word_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn is_parent(kind: NodeKind) -> bool {
+    matches!(
+        kind,
+        NodeKind::Emphasis
+            | NodeKind::Strong
+            | NodeKind::Strikethrough
+            | NodeKind::Link
+            | NodeKind::LinkReference
+            | NodeKind::List
+            | NodeKind::ListItem
+            | NodeKind::Heading
+            | NodeKind::Table
+            | NodeKind::TableRow
+            | NodeKind::TableCell
+            | NodeKind::Paragraph
+            | NodeKind::Blockquote
+            | NodeKind::FootnoteDefinition
+            | NodeKind::Admonition
+            | NodeKind::DefinitionList
+            | NodeKind::DefinitionTerm
+            | NodeKind::DefinitionDescription
+    )
+}
@@ -0,0 +1,428 @@
+//! Parser configuration.
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PARSE CONFIG
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Selects which Markdown constructs are recognized by [`crate::parse_with`]
+/// and which positional info is computed.
+///
+/// Start from one of the presets and override individual constructs with
+/// struct update syntax:
+///
+/// ```
+/// let config = markdown_format::ParseConfig {
+///     gfm_task_list_item: false,
+///     ..markdown_format::ParseConfig::gfm()
+/// };
+/// let nodes = markdown_format::parse_with("- [ ] a", &config).unwrap();
+/// ```
+///
+/// MDX constructs are never enabled since the AST has no representation for
+/// them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseConfig {
+    /// Attention (emphasis, strong).
+    ///
+    /// ```markdown
+    /// > | a *b* c **d**.
+    ///       ^^^   ^^^^^
+    /// ```
+    pub attention: bool,
+    /// Autolink.
+    ///
+    /// ```markdown
+    /// > | a <https://example.com> b <user@example.org>.
+    ///       ^^^^^^^^^^^^^^^^^^^^^   ^^^^^^^^^^^^^^^^^^
+    /// ```
+    pub autolink: bool,
+    /// Block quote.
+    ///
+    /// ```markdown
+    /// > | > a
+    ///     ^^^
+    /// ```
+    pub block_quote: bool,
+    /// Character escape.
+    ///
+    /// ```markdown
+    /// > | a \* b
+    ///       ^^
+    /// ```
+    pub character_escape: bool,
+    /// Character reference.
+    ///
+    /// ```markdown
+    /// > | a &amp; b
+    ///       ^^^^^
+    /// ```
+    pub character_reference: bool,
+    /// Code (indented).
+    ///
+    /// ```markdown
+    /// > |     a
+    ///     ^^^^^
+    /// ```
+    pub code_indented: bool,
+    /// Code (fenced).
+    ///
+    /// ```markdown
+    /// > | ~~~js
+    ///     ^^^^^
+    /// > | console.log(1)
+    ///     ^^^^^^^^^^^^^^
+    /// > | ~~~
+    ///     ^^^
+    /// ```
+    pub code_fenced: bool,
+    /// Code (text).
+    ///
+    /// ```markdown
+    /// > | a `b` c
+    ///       ^^^
+    /// ```
+    pub code_text: bool,
+    /// Definition.
+    ///
+    /// ```markdown
+    /// > | [a]: b "c"
+    ///     ^^^^^^^^^^
+    /// ```
+    pub definition: bool,
+    /// Frontmatter (YAML or TOML).
+    ///
+    /// ```markdown
+    /// > | ---
+    ///     ^^^
+    /// > | title: Neptune
+    ///     ^^^^^^^^^^^^^^
+    /// > | ---
+    ///     ^^^
+    /// ```
+    pub frontmatter: bool,
+    /// GFM: autolink literal.
+    ///
+    /// ```markdown
+    /// > | https://example.com
+    ///     ^^^^^^^^^^^^^^^^^^^
+    /// ```
+    pub gfm_autolink_literal: bool,
+    /// GFM: footnote definition.
+    ///
+    /// ```markdown
+    /// > | [^a]: b
+    ///     ^^^^^^^
+    /// ```
+    pub gfm_footnote_definition: bool,
+    /// GFM: footnote label start.
+    ///
+    /// ```markdown
+    /// > | a[^b]
+    ///      ^^
+    /// ```
+    pub gfm_label_start_footnote: bool,
+    /// GFM: strikethrough.
+    ///
+    /// ```markdown
+    /// > | a ~b~ c.
+    ///       ^^^
+    /// ```
+    pub gfm_strikethrough: bool,
+    /// GFM: table.
+    ///
+    /// ```markdown
+    /// > | | a |
+    ///     ^^^^^
+    /// > | | - |
+    ///     ^^^^^
+    /// ```
+    pub gfm_table: bool,
+    /// GFM: task list item.
+    ///
+    /// ```markdown
+    /// > | * [x] y.
+    ///       ^^^
+    /// ```
+    pub gfm_task_list_item: bool,
+    /// Hard break (escape).
+    ///
+    /// ```markdown
+    /// > | a\
+    ///      ^
+    ///   | b
+    /// ```
+    pub hard_break_escape: bool,
+    /// Hard break (trailing).
+    ///
+    /// ```markdown
+    /// > | a␠␠
+    ///      ^^
+    ///   | b
+    /// ```
+    pub hard_break_trailing: bool,
+    /// Heading (atx).
+    ///
+    /// ```markdown
+    /// > | # a
+    ///     ^^^
+    /// ```
+    pub heading_atx: bool,
+    /// Heading (setext).
+    ///
+    /// ```markdown
+    /// > | a
+    ///     ^
+    /// > | ==
+    ///     ^^
+    /// ```
+    pub heading_setext: bool,
+    /// HTML (flow).
+    ///
+    /// ```markdown
+    /// > | <div>
+    ///     ^^^^^
+    /// ```
+    pub html_flow: bool,
+    /// HTML (text).
+    ///
+    /// ```markdown
+    /// > | a <b> c
+    ///       ^^^
+    /// ```
+    pub html_text: bool,
+    /// Label start (image).
+    ///
+    /// ```markdown
+    /// > | a ![b](c) d
+    ///       ^^
+    /// ```
+    pub label_start_image: bool,
+    /// Label start (link).
+    ///
+    /// ```markdown
+    /// > | a [b](c) d
+    ///       ^
+    /// ```
+    pub label_start_link: bool,
+    /// Label end.
+    ///
+    /// ```markdown
+    /// > | a [b](c) d
+    ///         ^^^^
+    /// ```
+    pub label_end: bool,
+    /// List items.
+    ///
+    /// ```markdown
+    /// > | * a
+    ///     ^^^
+    /// ```
+    pub list_item: bool,
+    /// Math (flow).
+    ///
+    /// ```markdown
+    /// > | $$
+    ///     ^^
+    /// > | \frac{1}{2}
+    ///     ^^^^^^^^^^^
+    /// > | $$
+    ///     ^^
+    /// ```
+    pub math_flow: bool,
+    /// Math (text).
+    ///
+    /// ```markdown
+    /// > | a $b$ c
+    ///       ^^^
+    /// ```
+    pub math_text: bool,
+    /// Thematic break.
+    ///
+    /// ```markdown
+    /// > | ***
+    ///     ^^^
+    /// ```
+    pub thematic_break: bool,
+    /// Admonitions (extension): GitHub-style callouts and `:::` containers,
+    /// recognized after parsing, see [`crate::admonition`].
+    ///
+    /// ```markdown
+    /// > | > [!NOTE]
+    ///     ^^^^^^^^^
+    /// > | :::warning Careful
+    ///     ^^^^^^^^^^^^^^^^^^
+    /// ```
+    pub admonition: bool,
+    /// Wiki links and embeds (extension), recognized in text after parsing,
+    /// see [`crate::wikilink`].
+    ///
+    /// ```markdown
+    /// > | a [[Page|alias]] b ![[image.png]]
+    ///       ^^^^^^^^^^^^^^   ^^^^^^^^^^^^^^
+    /// ```
+    pub wikilink: bool,
+    /// Definition lists (extension): lines starting with `: ` after the
+    /// lines of their terms, recognized after parsing, see
+    /// [`crate::definition_list`].
+    ///
+    /// ```markdown
+    /// > | Term
+    ///     ^^^^
+    /// > | : Description
+    ///     ^^^^^^^^^^^^^
+    /// ```
+    pub definition_list: bool,
+    /// Not a construct: also fills in [`crate::Point::utf16_offset`] and
+    /// [`crate::Point::scalar_offset`], for hosts that index strings by
+    /// UTF-16 code units (`NSString`, JavaScript) or by characters.
+    pub unicode_offsets: bool,
+    /// Not a construct: also fills in [`crate::CodeBlock::highlights`].
+    /// Requires the `highlight` feature, ignored without it.
+    pub highlight: bool,
+    /// Not a construct: bounds on the document, see [`ParseLimits`].
+    pub limits: ParseLimits,
+}
+
+impl ParseConfig {
+    /// Plain CommonMark, nothing more.
+    pub fn commonmark() -> Self {
+        Self {
+            attention: true,
+            autolink: true,
+            block_quote: true,
+            character_escape: true,
+            character_reference: true,
+            code_indented: true,
+            code_fenced: true,
+            code_text: true,
+            definition: true,
+            frontmatter: false,
+            gfm_autolink_literal: false,
+            gfm_footnote_definition: false,
+            gfm_label_start_footnote: false,
+            gfm_strikethrough: false,
+            gfm_table: false,
+            gfm_task_list_item: false,
+            hard_break_escape: true,
+            hard_break_trailing: true,
+            heading_atx: true,
+            heading_setext: true,
+            html_flow: true,
+            html_text: true,
+            label_start_image: true,
+            label_start_link: true,
+            label_end: true,
+            list_item: true,
+            math_flow: false,
+            math_text: false,
+            thematic_break: true,
+            admonition: false,
+            wikilink: false,
+            definition_list: false,
+            unicode_offsets: false,
+            highlight: false,
+            limits: ParseLimits::default(),
+        }
+    }
+    /// CommonMark plus the GitHub Flavored Markdown extensions (autolink
+    /// literals, footnotes, strikethrough, tables and task lists).
+    pub fn gfm() -> Self {
+        Self {
+            gfm_autolink_literal: true,
+            gfm_footnote_definition: true,
+            gfm_label_start_footnote: true,
+            gfm_strikethrough: true,
+            gfm_table: true,
+            gfm_task_list_item: true,
+            ..Self::commonmark()
+        }
+    }
+    /// Every construct the AST can represent: GFM plus frontmatter, math,
+    /// admonitions, wiki links and definition lists.
+    pub fn everything() -> Self {
+        Self {
+            admonition: true,
+            wikilink: true,
+            definition_list: true,
+            frontmatter: true,
+            math_flow: true,
+            math_text: true,
+            ..Self::gfm()
+        }
+    }
+}
+
+impl Default for ParseConfig {
+    /// The construct set used by [`crate::parse`].
+    fn default() -> Self {
+        Self {
+            frontmatter: true,
+            gfm_strikethrough: true,
+            gfm_table: true,
+            math_flow: true,
+            math_text: true,
+            ..Self::commonmark()
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// PARSE LIMITS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Bounds on untrusted documents, [`crate::parse_with`] returns
+/// [`crate::Error::LimitExceeded`] for documents beyond them. `None` means
+/// unbounded.
+///
+/// Everything working on the AST (rendering, printing, serializing, dropping
+/// it) recurses into nested nodes, so only [`ParseLimits::max_depth`] is set
+/// by default, to a depth these are known to handle on a 2 MiB thread stack.
+/// It's checked once the source is parsed, which takes time quadratic in the
+/// nesting of blocks, except for block quotes nested on a single line, which
+/// are rejected up front. The others are policy, for hosts that parse
+/// documents of strangers:
+///
+/// ```
+/// use markdown_format::{Error, Limit, ParseConfig, ParseLimits};
+/// let config = ParseConfig {
+///     limits: ParseLimits { max_input_bytes: Some(1 << 20), max_table_cells: Some(10_000), ..ParseLimits::default() },
+///     ..ParseConfig::default()
+/// };
+/// let error = markdown_format::parse_with(">".repeat(1000), &config).unwrap_err();
+/// assert!(matches!(error, Error::LimitExceeded(error) if error.limit == Limit::Depth));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Length of the source in bytes, checked before parsing.
+    pub max_input_bytes: Option<usize>,
+    /// Nesting depth of nodes, top-level nodes are at depth 1.
+    ///
+    /// Block quotes and list items nest blocks, list items count twice (the
+    /// list and the item), inline nodes add to the depth of their block.
+    pub max_depth: Option<usize>,
+    /// Number of nodes in the whole document, counted once it's parsed.
+    pub max_nodes: Option<usize>,
+    /// Number of cells of any one table.
+    pub max_table_cells: Option<usize>,
+}
+
+impl ParseLimits {
+    /// The default [`ParseLimits::max_depth`].
+    pub const DEFAULT_MAX_DEPTH: usize = 128;
+    /// No bounds at all, for trusted documents.
+    pub fn none() -> Self {
+        Self {
+            max_input_bytes: None,
+            max_depth: None,
+            max_nodes: None,
+            max_table_cells: None,
+        }
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: Some(Self::DEFAULT_MAX_DEPTH),
+            ..Self::none()
+        }
+    }
+}
@@ -4,7 +4,8 @@
 // MARKDOWN AST
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 /// A general enumeration of Markdown elements.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum Node {
     Text(Text),
@@ -35,15 +36,23 @@ pub enum Node {
     Toml(Toml),
     Yaml(Yaml),
     Html(Html),
+    Admonition(Admonition),
+    WikiLink(WikiLink),
+    Embed(Embed),
+    DefinitionList(DefinitionList),
+    DefinitionTerm(DefinitionTerm),
+    DefinitionDescription(DefinitionDescription),
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Fragment(pub Vec<Node>);
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // # INLINE NODES
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Text {
     /// Content model.
     pub value: String,
@@ -51,7 +60,8 @@ pub struct Text {
     pub position: Option<SourceRange>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Newline {
     /// Positional info.
     pub position: Option<SourceRange>,
@@ -67,7 +77,8 @@ pub struct Newline {
 /// > | **a**
 ///     ^^^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Strong {
     // Parent.
     /// Content model.
@@ -82,7 +93,8 @@ pub struct Strong {
 /// > | *a*
 ///     ^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Emphasis {
     // Parent.
     /// Content model.
@@ -97,7 +109,8 @@ pub struct Emphasis {
 /// > | ~~a~~
 ///     ^^^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Strikethrough {
     // Parent.
     /// Content model.
@@ -119,7 +132,8 @@ pub struct Strikethrough {
 /// > | ~~~
 ///     ^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CodeBlock {
     // Text.
     /// Content model.
@@ -131,9 +145,25 @@ pub struct CodeBlock {
     pub lang: Option<String>,
     /// Custom info relating to the node.
     pub meta: Option<String>,
+    /// Token spans of `value` for a known `lang`, set when parsed with
+    /// [`crate::ParseConfig::highlight`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<Vec<HighlightSpan>>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// A highlighted token of a [`CodeBlock`].
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HighlightSpan {
+    /// Byte range in the value of the code block.
+    pub range: std::ops::Range<usize>,
+    /// TextMate scope, such as `keyword.control.rust` or
+    /// `string.quoted.double.python`.
+    pub scope: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct InlineCode {
     // Text.
     /// Content model.
@@ -155,7 +185,8 @@ pub struct InlineCode {
 /// > | $$
 ///     ^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DisplayMath {
     // Text.
     /// Content model.
@@ -167,7 +198,8 @@ pub struct DisplayMath {
     pub meta: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct InlineMath {
     // Text.
     /// Content model.
@@ -179,7 +211,8 @@ pub struct InlineMath {
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // LINKS & IMAGES
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Link {
     // Parent.
     /// Content model.
@@ -194,7 +227,8 @@ pub struct Link {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct LinkReference {
     // Parent.
     /// Content model.
@@ -217,9 +251,17 @@ pub struct LinkReference {
     /// space, trim the optional initial and/or final space, and perform
     /// case-folding.
     pub label: Option<String>,
+    // Resolution.
+    /// URL of the matching definition, set by [`crate::resolve::resolve`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Title of the matching definition, set by [`crate::resolve::resolve`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Image {
     // Void.
     /// Positional info.
@@ -236,7 +278,8 @@ pub struct Image {
     pub title: Option<String>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ImageReference {
     // Void.
     /// Positional info.
@@ -261,6 +304,13 @@ pub struct ImageReference {
     /// space, trim the optional initial and/or final space, and perform
     /// case-folding.
     pub label: Option<String>,
+    // Resolution.
+    /// URL of the matching definition, set by [`crate::resolve::resolve`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Title of the matching definition, set by [`crate::resolve::resolve`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
 }
 
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
@@ -276,7 +326,8 @@ pub struct ImageReference {
 /// > | +++
 ///     ^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Toml {
     // Void.
     /// Content model.
@@ -295,7 +346,8 @@ pub struct Toml {
 /// > | ---
 ///     ^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Yaml {
     // Void.
     /// Content model.
@@ -310,7 +362,8 @@ pub struct Yaml {
 /// > | <a>
 ///     ^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Html {
     // Text.
     /// Content model.
@@ -322,7 +375,8 @@ pub struct Html {
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // # BLOCK NODES
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Blockquote {
     // Parent.
     /// Content model.
@@ -331,7 +385,8 @@ pub struct Blockquote {
     pub position: Option<SourceRange>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Paragraph {
     // Parent.
     /// Content model.
@@ -340,7 +395,79 @@ pub struct Paragraph {
     pub position: Option<SourceRange>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+/// Admonition (extension): a GitHub-style callout or a `:::` container.
+///
+/// ```markdown
+/// > | > [!NOTE]
+///     ^^^^^^^^^
+/// > | > a
+///     ^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Admonition {
+    // Parent.
+    /// Content model.
+    pub children: Vec<Node>,
+    /// Positional info.
+    pub position: Option<SourceRange>,
+    // Extra.
+    /// Lowercased type, such as `note`, `tip` or `warning`.
+    pub kind: String,
+    /// Custom title, if given.
+    pub title: Option<String>,
+}
+
+/// Wiki link (extension).
+///
+/// ```markdown
+/// > | a [[Page name#Heading|alias]] b
+///       ^^^^^^^^^^^^^^^^^^^^^^^^^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WikiLink {
+    // Void.
+    /// Positional info.
+    pub position: Option<SourceRange>,
+    // Extra.
+    /// Page name, empty for links to a heading of the current page.
+    pub target: String,
+    /// Text to show instead of the target.
+    pub alias: Option<String>,
+    /// Heading (or `^block` reference) after `#`.
+    pub heading_anchor: Option<String>,
+    /// URL of the target, set by [`crate::wikilink::resolve`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Embed (extension): a wiki link to an image or page to show inline.
+///
+/// ```markdown
+/// > | a ![[image.png|200]] b
+///       ^^^^^^^^^^^^^^^^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Embed {
+    // Void.
+    /// Positional info.
+    pub position: Option<SourceRange>,
+    // Extra.
+    /// File or page name.
+    pub target: String,
+    /// Text after `|`, a caption or a size such as `200` or `200x100`.
+    pub alias: Option<String>,
+    /// Heading (or `^block` reference) after `#`.
+    pub heading_anchor: Option<String>,
+    /// URL of the target, set by [`crate::wikilink::resolve`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Heading {
     pub level: HeadingLevel,
     // Parent.
@@ -356,7 +483,8 @@ pub struct Heading {
 /// > | [a]: b
 ///     ^^^^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Definition {
     // Void.
     /// Positional info.
@@ -388,7 +516,8 @@ pub struct Definition {
 /// > | [^a]: b
 ///     ^^^^^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FootnoteDefinition {
     // Parent.
     /// Content model.
@@ -408,6 +537,11 @@ pub struct FootnoteDefinition {
     /// space, trim the optional initial and/or final space, and perform
     /// case-folding.
     pub label: Option<String>,
+    // Resolution.
+    /// 1-indexed number of the footnote in order of first reference, set by
+    /// [`crate::resolve::resolve`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<usize>,
 }
 
 /// GFM: footnote reference.
@@ -416,7 +550,8 @@ pub struct FootnoteDefinition {
 /// > | [^a]
 ///     ^^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct FootnoteReference {
     // Void.
     /// Positional info.
@@ -434,9 +569,15 @@ pub struct FootnoteReference {
     /// space, trim the optional initial and/or final space, and perform
     /// case-folding.
     pub label: Option<String>,
+    // Resolution.
+    /// 1-indexed number of the footnote in order of first reference, set by
+    /// [`crate::resolve::resolve`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<usize>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HorizontalDivider {
     // Void.
     /// Positional info.
@@ -454,7 +595,8 @@ pub struct HorizontalDivider {
 /// > | | - |
 ///     ^^^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Table {
     // Parent.
     /// Content model.
@@ -472,7 +614,8 @@ pub struct Table {
 /// > | | a |
 ///     ^^^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TableRow {
     // Parent.
     /// Content model.
@@ -487,7 +630,8 @@ pub struct TableRow {
 /// > | | a |
 ///     ^^^^^
 /// ```
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TableCell {
     // Parent.
     /// Content model.
@@ -499,7 +643,8 @@ pub struct TableCell {
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // ## LISTS
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct List {
     // Parent.
     /// Content model.
@@ -517,7 +662,8 @@ pub struct List {
     pub spread: bool,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ListItem {
     // Parent.
     /// Content model.
@@ -533,17 +679,79 @@ pub struct ListItem {
     pub checked: Option<bool>,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ListType {
     Ordered,
     Unordered,
 }
 
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// ## DEFINITION LISTS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Definition list (extension): terms, each followed by one or more
+/// descriptions, as in HTML’s `<dl>`.
+///
+/// ```markdown
+/// > | Term
+///     ^^^^
+/// > | : Description
+///     ^^^^^^^^^^^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DefinitionList {
+    // Parent.
+    /// Content model: [`DefinitionTerm`]s and [`DefinitionDescription`]s.
+    pub children: Vec<Node>,
+    /// Positional info.
+    pub position: Option<SourceRange>,
+    // Extra.
+    /// A blank line separates a term from its descriptions (when `true`), or
+    /// not (when `false`).
+    pub spread: bool,
+}
+
+/// Definition term (extension).
+///
+/// ```markdown
+/// > | Term
+///     ^^^^
+///   | : Description
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DefinitionTerm {
+    // Parent.
+    /// Content model.
+    pub children: Vec<Node>,
+    /// Positional info.
+    pub position: Option<SourceRange>,
+}
+
+/// Definition description (extension), of the terms before it.
+///
+/// ```markdown
+///   | Term
+/// > | : Description
+///     ^^^^^^^^^^^^^
+/// ```
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DefinitionDescription {
+    // Parent.
+    /// Content model.
+    pub children: Vec<Node>,
+    /// Positional info.
+    pub position: Option<SourceRange>,
+}
+
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
 // # COMMON
 //―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ReferenceKind {
     /// The reference is implicit, its identifier inferred from its content.
@@ -554,13 +762,15 @@ pub enum ReferenceKind {
     Full,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum HeadingLevel {
     H1, H2, H3, H4, H5, H6
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum AlignKind {
     /// Left alignment.
@@ -574,7 +784,8 @@ pub enum AlignKind {
 }
 
 /// Location of a node in a source file.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SourceRange {
     /// Represents the place of the first character of the parsed source region.
     pub start: Point,
@@ -584,7 +795,8 @@ pub struct SourceRange {
 }
 
 /// One place in a source file.
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Point {
     /// 1-indexed integer representing a line in a source file.
     pub line: usize,
@@ -592,4 +804,12 @@ pub struct Point {
     pub column: usize,
     /// 0-indexed integer representing a character in a source file.
     pub offset: usize,
+    /// 0-indexed UTF-16 code unit offset, as used by `NSString`/`NSRange`,
+    /// set with [`crate::ParseConfig::unicode_offsets`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utf16_offset: Option<usize>,
+    /// 0-indexed Unicode scalar value (Rust `char`) offset, set with
+    /// [`crate::ParseConfig::unicode_offsets`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scalar_offset: Option<usize>,
 }
@@ -1,5 +1,5 @@
 use itertools::Itertools;
-use pretty_tree::ToPrettyTree;
+use crate::pretty_tree::{self, ToPrettyTree};
 use super::data::*;
 
 impl ToPrettyTree for SourceRange {
@@ -16,6 +16,8 @@ impl ToPrettyTree for Point {
             pretty_tree::field("line", &self.line),
             pretty_tree::field("column", &self.column),
             pretty_tree::field("offset", &self.offset),
+            pretty_tree::field("utf16_offset", &self.utf16_offset),
+            pretty_tree::field("scalar_offset", &self.scalar_offset),
         ])
     }
 }
@@ -51,6 +53,12 @@ impl ToPrettyTree for Node {
             Self::Html(x) => x.to_pretty_tree(),
             Self::DisplayMath(x) => x.to_pretty_tree(),
             Self::InlineMath(x) => x.to_pretty_tree(),
+            Self::Admonition(x) => x.to_pretty_tree(),
+            Self::WikiLink(x) => x.to_pretty_tree(),
+            Self::Embed(x) => x.to_pretty_tree(),
+            Self::DefinitionList(x) => x.to_pretty_tree(),
+            Self::DefinitionTerm(x) => x.to_pretty_tree(),
+            Self::DefinitionDescription(x) => x.to_pretty_tree(),
         }
     }
 }
@@ -147,6 +155,8 @@ impl ToPrettyTree for LinkReference {
             pretty_tree::field("reference_kind", &self.reference_kind),
             pretty_tree::field("identifier", &self.identifier),
             pretty_tree::field("label", &self.label),
+            pretty_tree::field("url", &self.url),
+            pretty_tree::field("title", &self.title),
         ])
     }
 }
@@ -168,6 +178,8 @@ impl ToPrettyTree for ImageReference {
             pretty_tree::field("reference_kind", &self.reference_kind),
             pretty_tree::field("identifier", &self.identifier),
             pretty_tree::field("label", &self.label),
+            pretty_tree::field("url", &self.url),
+            pretty_tree::field("title", &self.title),
         ])
     }
 }
@@ -211,6 +223,16 @@ impl ToPrettyTree for Paragraph {
         ])
     }
 }
+impl ToPrettyTree for Admonition {
+    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
+        pretty_tree::branch_of("Admonition", vec![
+            // pretty_tree::field("position", &self.position),
+            pretty_tree::field("kind", &self.kind),
+            pretty_tree::field("title", &self.title),
+            children("children", &self.children),
+        ])
+    }
+}
 impl ToPrettyTree for Heading {
     fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
         pretty_tree::branch_of("Heading", vec![
@@ -220,6 +242,28 @@ impl ToPrettyTree for Heading {
         ])
     }
 }
+impl ToPrettyTree for WikiLink {
+    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
+        pretty_tree::branch_of("WikiLink", vec![
+            // pretty_tree::field("position", &self.position),
+            pretty_tree::field("target", &self.target),
+            pretty_tree::field("alias", &self.alias),
+            pretty_tree::field("heading_anchor", &self.heading_anchor),
+            pretty_tree::field("url", &self.url),
+        ])
+    }
+}
+impl ToPrettyTree for Embed {
+    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
+        pretty_tree::branch_of("Embed", vec![
+            // pretty_tree::field("position", &self.position),
+            pretty_tree::field("target", &self.target),
+            pretty_tree::field("alias", &self.alias),
+            pretty_tree::field("heading_anchor", &self.heading_anchor),
+            pretty_tree::field("url", &self.url),
+        ])
+    }
+}
 impl ToPrettyTree for Definition {
     fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
         pretty_tree::branch_of("Definition", vec![
@@ -303,10 +347,35 @@ impl ToPrettyTree for ListItem {
         ])
     }
 }
+impl ToPrettyTree for DefinitionList {
+    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
+        pretty_tree::branch_of("DefinitionList", vec![
+            children("children", &self.children),
+            // pretty_tree::field("position", &self.position),
+            pretty_tree::field("spread", &self.spread),
+        ])
+    }
+}
+impl ToPrettyTree for DefinitionTerm {
+    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
+        pretty_tree::branch_of("DefinitionTerm", vec![
+            children("children", &self.children),
+            // pretty_tree::field("position", &self.position),
+        ])
+    }
+}
+impl ToPrettyTree for DefinitionDescription {
+    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
+        pretty_tree::branch_of("DefinitionDescription", vec![
+            children("children", &self.children),
+            // pretty_tree::field("position", &self.position),
+        ])
+    }
+}
 
 fn children<T: pretty_tree::ToPrettyTree>(field_name: &str, children: &[T]) -> pretty_tree::PrettyTree {
     let children = children
-        .into_iter()
+        .iter()
         .map(|x| x.to_pretty_tree())
         .collect_vec();
     pretty_tree::PrettyTree::branch_of(field_name, children)
@@ -0,0 +1,262 @@
+//! Definition lists: terms on lines of their own, each followed by one or
+//! more descriptions on lines starting with `: `.
+//!
+//! They are recognized after parsing, in paragraphs:
+//!
+//! ```markdown
+//! Apple
+//! Pear
+//! : Fruits. Lines without a marker continue
+//! the description before them.
+//! : A second description.
+//!
+//! Spread
+//!
+//! : A blank line between the terms and their descriptions makes the list
+//!   spread (loose), like a blank line between list items.
+//! ```
+//!
+//! ```
+//! use markdown_format::{Node, ParseConfig};
+//! let config = ParseConfig { definition_list: true, ..ParseConfig::gfm() };
+//! let nodes = markdown_format::parse_with("Term\n: *Description*\n", &config).unwrap();
+//! let Node::DefinitionList(list) = &nodes[0] else { panic!() };
+//! assert!(matches!(list.children[..], [Node::DefinitionTerm(_), Node::DefinitionDescription(_)]));
+//! ```
+use crate::{DefinitionDescription, DefinitionList, DefinitionTerm, Node, Paragraph, SourceRange, Text};
+use crate::admonition::{point, text_lines};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Turns paragraphs with description lines into [`DefinitionList`] nodes, at
+/// any depth. `source` is the document the positions of `nodes` point into.
+pub fn definition_lists(source: &str, nodes: &mut Vec<Node>) {
+    let pass = Pass { source };
+    pass.nodes(nodes);
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+struct Pass<'a> {
+    source: &'a str,
+}
+
+/// The inlines of one line of a paragraph.
+#[derive(Default)]
+struct Line {
+    nodes: Vec<Node>,
+    /// The hard break that ends the line, `None` after a soft line break.
+    hard_break: Option<Node>,
+}
+
+impl Pass<'_> {
+    fn nodes(&self, nodes: &mut Vec<Node>) {
+        for node in nodes.iter_mut() {
+            if let Some(children) = node.children_mut() {
+                self.nodes(children);
+            }
+        }
+        if has_markers(nodes) {
+            self.lists(nodes);
+        }
+    }
+    /// Groups the siblings into lists: a paragraph with description lines
+    /// after its term lines, or a paragraph of terms followed by paragraphs
+    /// that start with a description. Adjacent groups form one list.
+    fn lists(&self, nodes: &mut Vec<Node>) {
+        let mut output = Vec::with_capacity(nodes.len());
+        for node in std::mem::take(nodes) {
+            let Node::Paragraph(paragraph) = node else {
+                output.push(node);
+                continue
+            };
+            let Some(mut lines) = self.lines(&paragraph) else {
+                output.push(Node::Paragraph(paragraph));
+                continue
+            };
+            let Some(first_description) = lines.iter().position(|x| self.is_description(x)) else {
+                output.push(Node::Paragraph(paragraph));
+                continue
+            };
+            let (terms, spread) = match (first_description, output.last()) {
+                (0, Some(Node::DefinitionList(_))) => (Vec::new(), true),
+                (0, Some(Node::Paragraph(previous))) => match self.lines(previous) {
+                    Some(terms) if !terms.iter().any(|x| self.is_description(x)) => {
+                        output.pop();
+                        (terms, true)
+                    }
+                    _ => {
+                        output.push(Node::Paragraph(paragraph));
+                        continue
+                    }
+                },
+                (0, _) => {
+                    output.push(Node::Paragraph(paragraph));
+                    continue
+                }
+                (_, _) => (lines.drain(..first_description).collect(), false),
+            };
+            let mut children = terms.into_iter().map(term).collect::<Vec<_>>();
+            let mut description = Vec::new();
+            for line in lines {
+                if self.is_description(&line) && !description.is_empty() {
+                    children.push(self.description(std::mem::take(&mut description)));
+                }
+                description.push(line);
+            }
+            children.push(self.description(description));
+            append(&mut output, children, spread);
+        }
+        *nodes = output;
+    }
+    /// `None` if a text has no position to find its lines by.
+    fn lines(&self, paragraph: &Paragraph) -> Option<Vec<Line>> {
+        let mut lines = vec![Line::default()];
+        for child in &paragraph.children {
+            match child {
+                Node::Text(text) => {
+                    text.position.as_ref()?;
+                    let values: Vec<&str> = text.value.split('\n').map(|x| x.trim_end_matches('\r')).collect();
+                    for index in 0..values.len() {
+                        if index > 0 {
+                            lines.push(Line::default());
+                        }
+                        if let Some(piece) = text_lines(self.source, text, &values, index..index + 1) {
+                            lines.last_mut()?.nodes.push(Node::Text(piece));
+                        }
+                    }
+                }
+                Node::Newline(_) => {
+                    lines.last_mut()?.hard_break = Some(child.clone());
+                    lines.push(Line::default());
+                }
+                child => lines.last_mut()?.nodes.push(child.clone()),
+            }
+        }
+        Some(lines)
+    }
+    /// A line starting with an unescaped `:` and whitespace, followed by
+    /// content.
+    fn is_description(&self, line: &Line) -> bool {
+        let Some(Node::Text(text)) = line.nodes.first() else { return false };
+        let Some(rest) = text.value.strip_prefix(':') else { return false };
+        let Some(offset) = text.position.as_ref().map(|x| x.start.offset) else { return false };
+        let is_marker = rest.starts_with([' ', '\t'])
+            && self.source.get(offset..).is_some_and(|x| x.starts_with(':'))
+            && !self.source.get(..offset).is_some_and(|x| x.ends_with('\\'));
+        is_marker && (!rest.trim().is_empty() || line.nodes.len() > 1)
+    }
+    /// The lines of one description, the first one starting with the marker.
+    fn description(&self, lines: Vec<Line>) -> Node {
+        let start = lines.first().and_then(|x| x.nodes.first()).and_then(Node::position).map(|x| x.start.clone());
+        let mut inlines = Vec::new();
+        let mut hard_break = None;
+        for (index, mut line) in lines.into_iter().enumerate() {
+            if index == 0 {
+                self.remove_marker(&mut line.nodes);
+            }
+            match hard_break.take() {
+                Some(hard_break) => {
+                    inlines.push(hard_break);
+                    inlines.extend(line.nodes);
+                }
+                None if index == 0 => inlines.extend(line.nodes),
+                None => push_line(&mut inlines, line.nodes),
+            }
+            hard_break = line.hard_break;
+        }
+        let paragraph = Paragraph { position: span(&inlines), children: inlines };
+        let position = start.zip(paragraph.position.as_ref()).map(|(start, x)| SourceRange { start, end: x.end.clone() });
+        Node::DefinitionDescription(DefinitionDescription { children: vec![Node::Paragraph(paragraph)], position })
+    }
+    /// Drops the `:` and the whitespace after it.
+    fn remove_marker(&self, nodes: &mut Vec<Node>) {
+        let Some(Node::Text(text)) = nodes.first_mut() else { return };
+        let rest = text.value[1..].trim_start_matches([' ', '\t']);
+        let removed = text.value.len() - rest.len();
+        text.value = rest.to_owned();
+        if let Some(position) = text.position.as_mut() {
+            position.start = point(self.source, position.start.offset + removed, position.start.line);
+        }
+        if text.value.is_empty() {
+            nodes.remove(0);
+        }
+    }
+}
+
+fn term(line: Line) -> Node {
+    let position = span(&line.nodes);
+    Node::DefinitionTerm(DefinitionTerm { children: line.nodes, position })
+}
+
+/// Appends the inlines of a line after a soft line break, which is part of
+/// the text around it.
+fn push_line(inlines: &mut Vec<Node>, line: Vec<Node>) {
+    let mut line = line.into_iter();
+    let Some(first) = line.next() else { return };
+    let end = inlines.last().and_then(Node::position).map(|x| x.end.clone());
+    let start = first.position().map(|x| x.start.clone());
+    match (inlines.last_mut(), first) {
+        (Some(Node::Text(previous)), Node::Text(next)) => {
+            previous.value = format!("{}\n{}", previous.value, next.value);
+            if let (Some(position), Some(next)) = (previous.position.as_mut(), next.position) {
+                position.end = next.end;
+            }
+        }
+        (Some(Node::Text(previous)), first) => {
+            previous.value.push('\n');
+            if let (Some(position), Some(start)) = (previous.position.as_mut(), start) {
+                position.end = start;
+            }
+            inlines.push(first);
+        }
+        (_, Node::Text(mut next)) => {
+            next.value.insert(0, '\n');
+            if let (Some(position), Some(end)) = (next.position.as_mut(), end) {
+                position.start = end;
+            }
+            inlines.push(Node::Text(next));
+        }
+        (_, first) => {
+            let position = end.zip(start).map(|(start, end)| SourceRange { start, end });
+            inlines.push(Node::Text(Text { value: String::from("\n"), position }));
+            inlines.push(first);
+        }
+    }
+    inlines.extend(line);
+}
+
+/// Adds the terms and descriptions to the definition list before them, or
+/// to a new one.
+fn append(output: &mut Vec<Node>, children: Vec<Node>, spread: bool) {
+    if let Some(Node::DefinitionList(list)) = output.last_mut() {
+        let end = children.last().and_then(Node::position).map(|x| x.end.clone());
+        if let (Some(position), Some(end)) = (list.position.as_mut(), end) {
+            position.end = end;
+        }
+        list.spread |= spread;
+        list.children.extend(children);
+        return
+    }
+    let position = span(&children);
+    output.push(Node::DefinitionList(DefinitionList { children, position, spread }));
+}
+
+fn span(nodes: &[Node]) -> Option<SourceRange> {
+    let start = nodes.first()?.position()?.start.clone();
+    let end = nodes.last()?.position()?.end.clone();
+    Some(SourceRange { start, end })
+}
+
+/// Whether a paragraph among the nodes may contain a description line.
+fn has_markers(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| {
+        let Node::Paragraph(paragraph) = node else { return false };
+        paragraph.children.iter().any(|x| match x {
+            Node::Text(text) => text.value.split('\n').any(|x| x.starts_with(": ") || x.starts_with(":\t")),
+            _ => false,
+        })
+    })
+}
@@ -0,0 +1,98 @@
+//! The error type of the crate.
+use crate::{LimitError, Point};
+use crate::incremental::InvalidEditError;
+
+/// Why parsing, or encoding what was parsed, failed.
+///
+/// ```
+/// use markdown_format::{Error, Limit, ParseConfig, ParseLimits};
+/// let config = ParseConfig { limits: ParseLimits { max_depth: Some(2), ..ParseLimits::none() }, ..ParseConfig::default() };
+/// match markdown_format::parse_with("> *a*", &config) {
+///     Err(Error::LimitExceeded(error)) => assert_eq!(error.limit, Limit::Depth),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[derive(Debug)]
+pub enum Error {
+    /// The source is not UTF-8, up to [`std::str::Utf8Error::valid_up_to`].
+    InvalidUtf8(std::str::Utf8Error),
+    /// The document is beyond the [`crate::ParseLimits`] of its config.
+    LimitExceeded(LimitError),
+    /// A syntax error reported by the underlying `markdown` crate, which only
+    /// has them for MDX constructs ([`crate::ParseConfig`] never enables
+    /// these).
+    Syntax {
+        reason: String,
+        position: Option<Point>,
+    },
+    /// Encoding the AST, or something derived from it, failed.
+    Serialization(Box<dyn std::error::Error + Send + Sync>),
+    /// The underlying `markdown` crate panicked on the source, `reason` is
+    /// the panic message.
+    Internal {
+        reason: String,
+    },
+    /// A [`crate::incremental::TextEdit`] whose range is out of bounds, reversed
+    /// or not on `char` boundaries of the source it is applied to.
+    InvalidEdit(InvalidEditError),
+}
+
+impl Error {
+    /// Where in the source it went wrong, for errors about one place.
+    pub fn position(&self) -> Option<&Point> {
+        match self {
+            Self::LimitExceeded(error) => error.position.as_ref(),
+            Self::Syntax { position, .. } => position.as_ref(),
+            Self::InvalidUtf8(_) | Self::Serialization(_) | Self::Internal { .. } | Self::InvalidEdit(_) => None,
+        }
+    }
+    /// Byte offset in the source where it went wrong, also known for invalid
+    /// UTF-8 which has no [`Error::position`].
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            Self::InvalidUtf8(error) => Some(error.valid_up_to()),
+            _ => self.position().map(|x| x.offset),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidUtf8(error) => write!(f, "source is not valid UTF-8: {error}"),
+            Self::LimitExceeded(error) => error.fmt(f),
+            Self::Syntax { reason, position: Some(position) } => {
+                write!(f, "{reason} at {}:{}", position.line, position.column)
+            }
+            Self::Syntax { reason, position: None } => f.write_str(reason),
+            Self::Serialization(error) => write!(f, "serialization failed: {error}"),
+            Self::Internal { reason } => write!(f, "internal parser error: {reason}"),
+            Self::InvalidEdit(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidUtf8(error) => Some(error),
+            Self::LimitExceeded(error) => Some(error),
+            Self::Syntax { .. } | Self::Internal { .. } => None,
+            Self::Serialization(error) => Some(error.as_ref()),
+            Self::InvalidEdit(error) => Some(error),
+        }
+    }
+}
+
+impl From<std::str::Utf8Error> for Error {
+    fn from(error: std::str::Utf8Error) -> Self { Self::InvalidUtf8(error) }
+}
+impl From<LimitError> for Error {
+    fn from(error: LimitError) -> Self { Self::LimitExceeded(error) }
+}
+impl From<InvalidEditError> for Error {
+    fn from(error: InvalidEditError) -> Self { Self::InvalidEdit(error) }
+}
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self { Self::Serialization(Box::new(error)) }
+}
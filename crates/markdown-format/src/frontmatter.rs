@@ -0,0 +1,156 @@
+//! Structured frontmatter.
+//!
+//! ```
+//! let nodes = markdown_format::parse("---\ntitle: Neptune\ntags: [a, b]\n---\n# Hi\n").unwrap();
+//! let frontmatter = markdown_format::frontmatter::Frontmatter::find(&nodes).unwrap();
+//! let value = frontmatter.value().unwrap();
+//! assert_eq!(value["title"], "Neptune");
+//!
+//! #[derive(serde::Deserialize)]
+//! struct Metadata { title: String, tags: Vec<String> }
+//! let metadata: Metadata = frontmatter.deserialize().unwrap();
+//! assert_eq!(metadata.tags, ["a", "b"]);
+//! ```
+use crate::{Node, Point, SourceRange};
+use crate::text::line_endings;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontmatterFormat {
+    /// Fenced with `---`.
+    Yaml,
+    /// Fenced with `+++`.
+    Toml,
+}
+
+/// The frontmatter of a document, see [`Frontmatter::find`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frontmatter {
+    pub format: FrontmatterFormat,
+    /// The content between the fences.
+    pub raw: String,
+    /// Of the whole frontmatter, fences included.
+    pub position: Option<SourceRange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrontmatterError {
+    pub format: FrontmatterFormat,
+    pub message: String,
+    /// Where the error occurred in the original document, if known.
+    pub point: Option<Point>,
+}
+
+impl std::fmt::Display for FrontmatterFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Yaml => f.write_str("YAML"),
+            Self::Toml => f.write_str("TOML"),
+        }
+    }
+}
+
+impl std::fmt::Display for FrontmatterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid {} frontmatter", self.format)?;
+        if let Some(point) = self.point.as_ref() {
+            write!(f, " at {}:{}", point.line, point.column)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+impl std::error::Error for FrontmatterError {}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl Frontmatter {
+    /// The frontmatter of a parsed document, its first node if that is a
+    /// `Yaml` or `Toml` node.
+    pub fn find(nodes: &[Node]) -> Option<Self> {
+        let (format, raw, position) = match nodes.first()? {
+            Node::Yaml(x) => (FrontmatterFormat::Yaml, &x.value, &x.position),
+            Node::Toml(x) => (FrontmatterFormat::Toml, &x.value, &x.position),
+            _ => return None,
+        };
+        Some(Self { format, raw: raw.clone(), position: position.clone() })
+    }
+    /// The data as a JSON-like tree, TOML dates and times become strings.
+    pub fn value(&self) -> Result<serde_json::Value, FrontmatterError> {
+        match self.format {
+            FrontmatterFormat::Yaml => self.deserialize(),
+            FrontmatterFormat::Toml => Ok(toml_to_json(self.deserialize()?)),
+        }
+    }
+    /// Deserializes the data into a user type.
+    pub fn deserialize<T: serde::de::DeserializeOwned>(&self) -> Result<T, FrontmatterError> {
+        // An empty document is an empty mapping rather than YAML’s null.
+        let raw = match (self.format, self.raw.trim().is_empty()) {
+            (FrontmatterFormat::Yaml, true) => "{}",
+            _ => self.raw.as_str(),
+        };
+        match self.format {
+            FrontmatterFormat::Yaml => serde_yaml_ng::from_str(raw).map_err(|error| {
+                let index = error.location().map(|x| x.index());
+                self.error(error.to_string(), index)
+            }),
+            FrontmatterFormat::Toml => toml::from_str(raw).map_err(|error| {
+                let index = error.span().map(|x| x.start);
+                self.error(error.message().to_owned(), index)
+            }),
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl Frontmatter {
+    fn error(&self, message: String, index: Option<usize>) -> FrontmatterError {
+        let point = index.and_then(|index| self.point(index));
+        FrontmatterError { format: self.format, message, point }
+    }
+    /// Maps a byte index into `raw` to the original document.
+    ///
+    /// The content starts on the line after the opening fence, its offset is
+    /// derived from the end of the closing fence.
+    fn point(&self, index: usize) -> Option<Point> {
+        let position = self.position.as_ref()?;
+        let index = index.min(self.raw.len());
+        let before = self.raw.get(..index)?;
+        let line_start = before.rfind(['\n', '\r']).map_or(0, |x| x + 1);
+        let line_ending = match self.raw.contains("\r\n") {
+            true => 2,
+            false => 1,
+        };
+        let closing_line = position.end.offset.checked_sub(position.end.column - 1)?;
+        let content_start = match self.raw.is_empty() {
+            true => closing_line,
+            false => closing_line.checked_sub(self.raw.len() + line_ending)?,
+        };
+        Some(Point {
+            line: position.start.line + 1 + line_endings(before),
+            column: index - line_start + 1,
+            offset: content_start + index,
+            utf16_offset: None,
+            scalar_offset: None,
+        })
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> serde_json::Value {
+    match value {
+        toml::Value::String(x) => serde_json::Value::String(x),
+        toml::Value::Integer(x) => serde_json::Value::from(x),
+        toml::Value::Float(x) => serde_json::Value::from(x),
+        toml::Value::Boolean(x) => serde_json::Value::Bool(x),
+        toml::Value::Datetime(x) => serde_json::Value::String(x.to_string()),
+        toml::Value::Array(x) => x.into_iter().map(toml_to_json).collect(),
+        toml::Value::Table(x) => x
+            .into_iter()
+            .map(|(key, value)| (key, toml_to_json(value)))
+            .collect(),
+    }
+}
@@ -0,0 +1,111 @@
+//! Syntax highlighting of code blocks, behind the `highlight` feature.
+//!
+//! Code is tokenized with the TextMate grammars bundled with `syntect` (Rust,
+//! Python, JavaScript, Go, C, Java, shell, JSON, YAML, HTML, SQL, …), so that
+//! every platform gets the same spans. Themes map scopes to colors, usually
+//! by prefix (`keyword`, `string`, `comment`, …).
+//!
+//! ```
+//! use markdown_format::{Node, ParseConfig};
+//! let config = ParseConfig { highlight: true, ..Default::default() };
+//! let nodes = markdown_format::parse_with("```rust\nlet x = 1;\n```", &config).unwrap();
+//! let Node::CodeBlock(code) = &nodes[0] else { panic!() };
+//! let first = &code.highlights.as_ref().unwrap()[0];
+//! assert_eq!((&code.value[first.range.clone()], first.scope.as_str()), ("let", "storage.type.rust"));
+//! ```
+use std::sync::OnceLock;
+use syntect::parsing::{ParseState, Scope, ScopeStack, SyntaxReference, SyntaxSet};
+use crate::{HighlightSpan, Node};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The spans of `code` in language `lang` (a name or file extension such as
+/// `rust` or `rs`), `None` for unknown languages.
+///
+/// Spans are ordered and don't overlap, text without span is plain. The scope
+/// of a span is its outermost one besides those of (embedded) languages and
+/// `meta.*` ones, the quotes of a string are part of the string for instance.
+pub fn highlight(code: &str, lang: &str) -> Option<Vec<HighlightSpan>> {
+    let syntaxes = syntaxes();
+    let syntax = find_syntax(syntaxes, lang)?;
+    let mut state = ParseState::new(syntax);
+    let mut stack = ScopeStack::new();
+    let mut spans: Vec<HighlightSpan> = Vec::new();
+    let mut push = |start: usize, end: usize, stack: &ScopeStack| {
+        let Some(scope) = stack.as_slice().iter().find(|x| !is_structural(x)) else { return };
+        if start == end {
+            return
+        }
+        let scope = scope.build_string();
+        match spans.last_mut() {
+            Some(last) if last.range.end == start && last.scope == scope => last.range.end = end,
+            _ => spans.push(HighlightSpan { range: start..end, scope }),
+        }
+    };
+    let mut line_start = 0;
+    for line in code.split_inclusive('\n') {
+        let operations = state.parse_line(line, syntaxes).ok()?;
+        let mut start = line_start;
+        for (index, operation) in operations {
+            push(start, line_start + index, &stack);
+            start = line_start + index;
+            stack.apply(&operation).ok()?;
+        }
+        push(start, line_start + line.len(), &stack);
+        line_start += line.len();
+    }
+    Some(spans)
+}
+
+/// Sets [`crate::CodeBlock::highlights`] of every code block with a known
+/// language.
+pub fn add_highlights(nodes: &mut [Node]) {
+    for node in nodes {
+        if let Node::CodeBlock(code) = node {
+            code.highlights = code.lang.as_deref().and_then(|lang| highlight(&code.value, lang));
+        }
+        if let Some(children) = node.children_mut() {
+            add_highlights(children);
+        }
+    }
+}
+
+/// Whether [`highlight`] knows the language.
+pub fn is_supported(lang: &str) -> bool {
+    find_syntax(syntaxes(), lang).is_some()
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Loading the grammars takes a while, they're shared.
+fn syntaxes() -> &'static SyntaxSet {
+    static SYNTAXES: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAXES.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+fn find_syntax<'a>(syntaxes: &'a SyntaxSet, lang: &str) -> Option<&'a SyntaxReference> {
+    let lang = lang.trim();
+    if lang.is_empty() {
+        return None
+    }
+    let lang = match lang.to_ascii_lowercase().as_str() {
+        "sh" | "shell" | "zsh" | "console" => "bash",
+        "jsx" | "mjs" | "cjs" => "js",
+        "py3" => "python",
+        "yml" => "yaml",
+        "c++" => "cpp",
+        _ => lang,
+    };
+    // The plain text grammar has no scopes to offer.
+    syntaxes.find_syntax_by_token(lang).filter(|x| x.name != "Plain Text")
+}
+
+/// Scopes of languages and of regions such as function definitions, which
+/// don't color anything.
+fn is_structural(scope: &Scope) -> bool {
+    static PREFIXES: OnceLock<[Scope; 3]> = OnceLock::new();
+    let prefixes = PREFIXES.get_or_init(|| ["source", "text", "meta"].map(|x| Scope::new(x).unwrap()));
+    prefixes.iter().any(|x| x.is_prefix_of(*scope))
+}
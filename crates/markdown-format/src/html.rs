@@ -0,0 +1,612 @@
+//! HTML renderer.
+//!
+//! Output follows the conventions of the CommonMark reference implementation
+//! (and GitHub for the GFM extensions).
+use std::collections::HashMap;
+use crate::data::*;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// OPTIONS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlOptions {
+    /// Drops raw `Html` nodes and clears URLs with dangerous protocols (such as
+    /// `javascript:`), use it for untrusted documents.
+    pub safe: bool,
+    /// Prepended to every class name the renderer emits, e.g. `md-` turns
+    /// `language-rust` into `md-language-rust`.
+    pub class_prefix: String,
+    /// Prepended to the generated footnote ids to avoid clobbering ids of the
+    /// surrounding page.
+    pub id_prefix: String,
+    /// Label of the footnotes section heading.
+    pub footnote_label: String,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            safe: false,
+            class_prefix: String::default(),
+            id_prefix: String::from("user-content-"),
+            footnote_label: String::from("Footnotes"),
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Renders a document to HTML.
+///
+/// Reference links and images are resolved against the `Definition` nodes of
+/// the document, referenced footnote definitions are collected into a
+/// trailing footnotes section, numbered in order of first reference.
+pub fn render(nodes: &[Node], options: &HtmlOptions) -> String {
+    let mut renderer = Renderer::new(nodes, options);
+    renderer.blocks(nodes, false);
+    renderer.footnote_section();
+    renderer.output
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+struct Renderer<'a> {
+    options: &'a HtmlOptions,
+    output: String,
+    /// Definitions by normalized identifier, the first one wins.
+    definitions: HashMap<String, &'a Definition>,
+    footnote_definitions: HashMap<String, &'a FootnoteDefinition>,
+    /// Normalized identifiers of referenced footnotes, in order of first reference.
+    footnote_order: Vec<String>,
+    /// Number of references rendered so far per footnote.
+    footnote_reference_counts: HashMap<String, usize>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(nodes: &'a [Node], options: &'a HtmlOptions) -> Self {
+        let mut renderer = Self {
+            options,
+            output: String::default(),
+            definitions: HashMap::default(),
+            footnote_definitions: HashMap::default(),
+            footnote_order: Vec::default(),
+            footnote_reference_counts: HashMap::default(),
+        };
+        renderer.collect(nodes);
+        renderer
+    }
+    fn collect(&mut self, nodes: &'a [Node]) {
+        for node in nodes {
+            match node {
+                Node::Definition(definition) => {
+                    self.definitions
+                        .entry(normalize_identifier(&definition.identifier))
+                        .or_insert(definition);
+                }
+                Node::FootnoteDefinition(definition) => {
+                    self.footnote_definitions
+                        .entry(normalize_identifier(&definition.identifier))
+                        .or_insert(definition);
+                    self.collect(&definition.children);
+                }
+                Node::FootnoteReference(reference) => {
+                    let identifier = normalize_identifier(&reference.identifier);
+                    if !self.footnote_order.contains(&identifier) {
+                        self.footnote_order.push(identifier);
+                    }
+                }
+                _ => self.collect(node.children()),
+            }
+        }
+    }
+    fn cr(&mut self) {
+        if !self.output.is_empty() && !self.output.ends_with('\n') {
+            self.output.push('\n');
+        }
+    }
+    fn class(&self, names: &[&str]) -> String {
+        names
+            .iter()
+            .map(|name| format!("{}{name}", self.options.class_prefix))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+    fn blocks(&mut self, nodes: &[Node], tight: bool) {
+        for node in nodes {
+            match node {
+                // HTML (flow) sits on lines of its own.
+                Node::Html(_) => {
+                    self.cr();
+                    self.node(node, tight);
+                    self.cr();
+                }
+                node => self.node(node, tight),
+            }
+        }
+    }
+    fn inlines(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.node(node, false);
+        }
+    }
+    /// `tight` is set for the children of items in a tight list, where
+    /// paragraphs are rendered without `<p>` tags.
+    fn node(&mut self, node: &Node, tight: bool) {
+        match node {
+            Node::Text(Text { value, .. }) => {
+                self.output.push_str(&escape(value));
+            }
+            Node::Newline(_) => {
+                self.output.push_str("<br />\n");
+            }
+            Node::Emphasis(Emphasis { children, .. }) => {
+                self.output.push_str("<em>");
+                self.inlines(children);
+                self.output.push_str("</em>");
+            }
+            Node::Strong(Strong { children, .. }) => {
+                self.output.push_str("<strong>");
+                self.inlines(children);
+                self.output.push_str("</strong>");
+            }
+            Node::Strikethrough(Strikethrough { children, .. }) => {
+                self.output.push_str("<del>");
+                self.inlines(children);
+                self.output.push_str("</del>");
+            }
+            Node::Image(Image { alt, url, title, .. }) => {
+                self.image(url, alt, title.as_deref());
+            }
+            Node::ImageReference(reference) => {
+                let identifier = normalize_identifier(&reference.identifier);
+                match self.definitions.get(&identifier).copied() {
+                    Some(definition) => self.image(&definition.url, &reference.alt, definition.title.as_deref()),
+                    None => self.output.push_str(&escape(&reference.alt)),
+                }
+            }
+            Node::Link(Link { children, url, title, .. }) => {
+                self.link_start(url, title.as_deref());
+                self.inlines(children);
+                self.output.push_str("</a>");
+            }
+            Node::LinkReference(reference) => {
+                let identifier = normalize_identifier(&reference.identifier);
+                match self.definitions.get(&identifier).copied() {
+                    Some(definition) => {
+                        self.link_start(&definition.url, definition.title.as_deref());
+                        self.inlines(&reference.children);
+                        self.output.push_str("</a>");
+                    }
+                    None => self.inlines(&reference.children),
+                }
+            }
+            // Unresolved wiki links are text, like references without definition.
+            Node::WikiLink(link) => match link.url.as_deref() {
+                Some(url) => {
+                    self.link_start(url, None);
+                    self.output.push_str(&escape(&link.text()));
+                    self.output.push_str("</a>");
+                }
+                None => self.output.push_str(&escape(&link.text())),
+            },
+            Node::Embed(embed) => match embed.url.as_deref() {
+                Some(url) => self.image(url, &embed.alt(), None),
+                None => self.output.push_str(&escape(&embed.alt())),
+            },
+            Node::CodeBlock(CodeBlock { value, lang, .. }) => {
+                self.cr();
+                match lang {
+                    Some(lang) => {
+                        let class = self.class(&[&format!("language-{lang}")]);
+                        self.output.push_str(&format!("<pre><code class=\"{}\">", escape(&class)));
+                    }
+                    None => self.output.push_str("<pre><code>"),
+                }
+                self.code_value(value);
+                self.output.push_str("</code></pre>");
+                self.cr();
+            }
+            Node::InlineCode(InlineCode { value, .. }) => {
+                self.output.push_str("<code>");
+                self.output.push_str(&escape(value));
+                self.output.push_str("</code>");
+            }
+            Node::DisplayMath(DisplayMath { value, .. }) => {
+                self.cr();
+                let class = self.class(&["language-math", "math-display"]);
+                self.output.push_str(&format!("<pre><code class=\"{}\">", escape(&class)));
+                self.code_value(value);
+                self.output.push_str("</code></pre>");
+                self.cr();
+            }
+            Node::InlineMath(InlineMath { value, .. }) => {
+                let class = self.class(&["language-math", "math-inline"]);
+                self.output.push_str(&format!("<code class=\"{}\">", escape(&class)));
+                self.output.push_str(&escape(value));
+                self.output.push_str("</code>");
+            }
+            Node::List(list) => self.list(list),
+            Node::ListItem(item) => self.list_item(item, !item.spread),
+            Node::Heading(Heading { level, children, .. }) => {
+                let level = heading_level(level);
+                self.cr();
+                self.output.push_str(&format!("<h{level}>"));
+                self.inlines(children);
+                self.output.push_str(&format!("</h{level}>"));
+                self.cr();
+            }
+            Node::Table(table) => self.table(table),
+            Node::TableRow(row) => self.table_row(row, "td", &[]),
+            Node::TableCell(TableCell { children, .. }) => {
+                self.output.push_str("<td>");
+                self.inlines(children);
+                self.output.push_str("</td>");
+            }
+            Node::HorizontalDivider(_) => {
+                self.cr();
+                self.output.push_str("<hr />");
+                self.cr();
+            }
+            Node::Definition(_) => {}
+            Node::Paragraph(Paragraph { children, .. }) => {
+                if tight {
+                    self.inlines(children);
+                } else {
+                    self.cr();
+                    self.output.push_str("<p>");
+                    self.inlines(children);
+                    self.output.push_str("</p>");
+                    self.cr();
+                }
+            }
+            Node::Blockquote(Blockquote { children, .. }) => {
+                self.cr();
+                self.output.push_str("<blockquote>");
+                self.cr();
+                self.blocks(children, false);
+                self.cr();
+                self.output.push_str("</blockquote>");
+                self.cr();
+            }
+            Node::Admonition(Admonition { kind, title, children, .. }) => {
+                // Like GitHub’s alerts.
+                let class = self.class(&["markdown-alert", &format!("markdown-alert-{kind}")]);
+                let title_class = self.class(&["markdown-alert-title"]);
+                let title = match title {
+                    Some(title) => title.clone(),
+                    None => kind.chars().take(1).flat_map(char::to_uppercase).chain(kind.chars().skip(1)).collect(),
+                };
+                self.cr();
+                self.output.push_str(&format!("<div class=\"{}\">\n", escape(&class)));
+                self.output.push_str(&format!("<p class=\"{}\">{}</p>\n", escape(&title_class), escape(&title)));
+                self.blocks(children, false);
+                self.cr();
+                self.output.push_str("</div>");
+                self.cr();
+            }
+            Node::DefinitionList(list) => {
+                self.cr();
+                self.output.push_str("<dl>");
+                self.cr();
+                for child in list.children.iter() {
+                    match child {
+                        Node::DefinitionDescription(description) => self.definition_description(description, !list.spread),
+                        node => self.node(node, false),
+                    }
+                }
+                self.cr();
+                self.output.push_str("</dl>");
+                self.cr();
+            }
+            Node::DefinitionTerm(DefinitionTerm { children, .. }) => {
+                self.cr();
+                self.output.push_str("<dt>");
+                self.inlines(children);
+                self.output.push_str("</dt>");
+                self.cr();
+            }
+            Node::DefinitionDescription(description) => self.definition_description(description, tight),
+            Node::FootnoteReference(reference) => self.footnote_reference(reference),
+            // Rendered in the footnotes section.
+            Node::FootnoteDefinition(_) => {}
+            Node::Toml(_) => {}
+            Node::Yaml(_) => {}
+            Node::Html(Html { value, .. }) => {
+                if !self.options.safe {
+                    self.output.push_str(value);
+                }
+            }
+        }
+    }
+    fn code_value(&mut self, value: &str) {
+        self.output.push_str(&escape(value));
+        if !value.is_empty() {
+            self.output.push('\n');
+        }
+    }
+    fn link_start(&mut self, url: &str, title: Option<&str>) {
+        let url = self.url(url, false);
+        self.output.push_str(&format!("<a href=\"{}\"", escape(&url)));
+        if let Some(title) = title {
+            self.output.push_str(&format!(" title=\"{}\"", escape(title)));
+        }
+        self.output.push('>');
+    }
+    fn image(&mut self, url: &str, alt: &str, title: Option<&str>) {
+        let url = self.url(url, true);
+        self.output.push_str(&format!("<img src=\"{}\" alt=\"{}\"", escape(&url), escape(alt)));
+        if let Some(title) = title {
+            self.output.push_str(&format!(" title=\"{}\"", escape(title)));
+        }
+        self.output.push_str(" />");
+    }
+    fn url(&self, url: &str, image: bool) -> String {
+        if self.options.safe && !is_safe_url(url, image) {
+            return String::default()
+        }
+        encode_url(url)
+    }
+    fn list(&mut self, list: &List) {
+        let tight = !list.spread && list.children.iter().all(|child| match child {
+            Node::ListItem(item) => !item.spread,
+            _ => true,
+        });
+        let has_task_items = list.children.iter().any(|child| match child {
+            Node::ListItem(item) => item.checked.is_some(),
+            _ => false,
+        });
+        let tag = if list.ordered { "ol" } else { "ul" };
+        self.cr();
+        self.output.push_str(&format!("<{tag}"));
+        if list.ordered {
+            if let Some(start) = list.start.filter(|start| *start != 1) {
+                self.output.push_str(&format!(" start=\"{start}\""));
+            }
+        }
+        if has_task_items {
+            let class = self.class(&["contains-task-list"]);
+            self.output.push_str(&format!(" class=\"{}\"", escape(&class)));
+        }
+        self.output.push('>');
+        self.cr();
+        for child in list.children.iter() {
+            match child {
+                Node::ListItem(item) => self.list_item(item, tight),
+                node => self.node(node, tight),
+            }
+        }
+        self.cr();
+        self.output.push_str(&format!("</{tag}>"));
+        self.cr();
+    }
+    fn list_item(&mut self, item: &ListItem, tight: bool) {
+        self.cr();
+        match item.checked {
+            Some(checked) => {
+                let class = self.class(&["task-list-item"]);
+                self.output.push_str(&format!("<li class=\"{}\">", escape(&class)));
+                if !tight {
+                    self.cr();
+                }
+                let checkbox = if checked {
+                    "<input type=\"checkbox\" disabled=\"\" checked=\"\" /> "
+                } else {
+                    "<input type=\"checkbox\" disabled=\"\" /> "
+                };
+                // Keep the checkbox inside the first paragraph of loose items.
+                match item.children.split_first() {
+                    Some((Node::Paragraph(paragraph), rest)) if !tight => {
+                        self.output.push_str("<p>");
+                        self.output.push_str(checkbox);
+                        self.inlines(&paragraph.children);
+                        self.output.push_str("</p>");
+                        self.cr();
+                        self.blocks(rest, tight);
+                    }
+                    _ => {
+                        self.output.push_str(checkbox);
+                        self.blocks(&item.children, tight);
+                    }
+                }
+            }
+            None => {
+                self.output.push_str("<li>");
+                self.blocks(&item.children, tight);
+            }
+        }
+        if !tight {
+            self.cr();
+        }
+        self.output.push_str("</li>");
+        self.cr();
+    }
+    /// `tight` is set in definition lists without blank lines, like in
+    /// [`Self::list_item`].
+    fn definition_description(&mut self, description: &DefinitionDescription, tight: bool) {
+        self.cr();
+        self.output.push_str("<dd>");
+        self.blocks(&description.children, tight);
+        if !tight {
+            self.cr();
+        }
+        self.output.push_str("</dd>");
+        self.cr();
+    }
+    fn table(&mut self, table: &Table) {
+        self.cr();
+        self.output.push_str("<table>\n");
+        let mut rows = table.children.iter();
+        if let Some(Node::TableRow(head)) = rows.next() {
+            self.output.push_str("<thead>\n");
+            self.table_row(head, "th", &table.alignment);
+            self.output.push_str("</thead>\n");
+        }
+        let body = rows.collect::<Vec<_>>();
+        if !body.is_empty() {
+            self.output.push_str("<tbody>\n");
+            for row in body {
+                match row {
+                    Node::TableRow(row) => self.table_row(row, "td", &table.alignment),
+                    node => self.node(node, false),
+                }
+            }
+            self.output.push_str("</tbody>\n");
+        }
+        self.output.push_str("</table>");
+        self.cr();
+    }
+    fn table_row(&mut self, row: &TableRow, cell_tag: &str, alignment: &[AlignKind]) {
+        self.output.push_str("<tr>\n");
+        for (index, cell) in row.children.iter().enumerate() {
+            let align = match alignment.get(index) {
+                Some(AlignKind::Left) => " align=\"left\"",
+                Some(AlignKind::Right) => " align=\"right\"",
+                Some(AlignKind::Center) => " align=\"center\"",
+                Some(AlignKind::None) | None => "",
+            };
+            self.output.push_str(&format!("<{cell_tag}{align}>"));
+            self.inlines(cell.children());
+            self.output.push_str(&format!("</{cell_tag}>\n"));
+        }
+        self.output.push_str("</tr>\n");
+    }
+    fn footnote_reference(&mut self, reference: &FootnoteReference) {
+        let identifier = normalize_identifier(&reference.identifier);
+        let Some(index) = self.footnote_order.iter().position(|x| x == &identifier) else {
+            return
+        };
+        let count = self.footnote_reference_counts.entry(identifier.clone()).or_default();
+        *count += 1;
+        let id = encode_url(&identifier);
+        let suffix = if *count > 1 { format!("-{count}") } else { String::default() };
+        let prefix = escape(&self.options.id_prefix);
+        self.output.push_str(&format!(
+            "<sup><a href=\"#{prefix}fn-{id}\" id=\"{prefix}fnref-{id}{suffix}\" data-footnote-ref=\"\" aria-describedby=\"footnote-label\">{}</a></sup>",
+            index + 1,
+        ));
+    }
+    fn footnote_section(&mut self) {
+        let footnotes = self.footnote_order
+            .iter()
+            .filter_map(|identifier| {
+                let definition = self.footnote_definitions.get(identifier).copied()?;
+                Some((identifier.clone(), definition))
+            })
+            .collect::<Vec<_>>();
+        if footnotes.is_empty() {
+            return
+        }
+        let prefix = escape(&self.options.id_prefix);
+        let class = self.class(&["footnotes"]);
+        let label_class = self.class(&["sr-only"]);
+        self.cr();
+        self.output.push_str(&format!(
+            "<section data-footnotes=\"\" class=\"{}\"><h2 id=\"footnote-label\" class=\"{}\">{}</h2>\n<ol>\n",
+            escape(&class),
+            escape(&label_class),
+            escape(&self.options.footnote_label),
+        ));
+        for (identifier, definition) in footnotes {
+            let id = encode_url(&identifier);
+            self.output.push_str(&format!("<li id=\"{prefix}fn-{id}\">\n"));
+            self.blocks(&definition.children, false);
+            let count = self.footnote_reference_counts.get(&identifier).copied().unwrap_or(1).max(1);
+            let class = self.class(&["data-footnote-backref"]);
+            let backrefs = (1..=count)
+                .map(|reference| {
+                    let (suffix, label) = match reference {
+                        1 => (String::default(), String::default()),
+                        _ => (format!("-{reference}"), format!("<sup>{reference}</sup>")),
+                    };
+                    format!(
+                        "<a href=\"#{prefix}fnref-{id}{suffix}\" data-footnote-backref=\"\" aria-label=\"Back to content\" class=\"{}\">↩{label}</a>",
+                        escape(&class),
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            // GitHub places the back references inside the last paragraph.
+            if self.output.ends_with("</p>\n") {
+                self.output.truncate(self.output.len() - "</p>\n".len());
+                self.output.push_str(&format!(" {backrefs}</p>\n"));
+            } else {
+                self.cr();
+                self.output.push_str(&backrefs);
+                self.cr();
+            }
+            self.output.push_str("</li>\n");
+        }
+        self.output.push_str("</ol>\n</section>\n");
+    }
+}
+
+fn heading_level(level: &HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
+
+/// Collapses markdown whitespace, trims and case-folds a label so it can be
+/// matched against other labels.
+pub(crate) fn normalize_identifier(value: &str) -> String {
+    value
+        .split([' ', '\t', '\r', '\n'])
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Escapes text for use in HTML content or attribute values.
+pub(crate) fn escape(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    for char in value.chars() {
+        match char {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            char => output.push(char),
+        }
+    }
+    output
+}
+
+/// Percent-encodes the characters that are not allowed in URLs, leaving
+/// existing escapes alone.
+fn encode_url(value: &str) -> String {
+    const SAFE: &str = "!#$%&'()*+,-./:;=?@[]_~";
+    let mut output = String::with_capacity(value.len());
+    for char in value.chars() {
+        if char.is_ascii_alphanumeric() || SAFE.contains(char) {
+            output.push(char);
+        } else {
+            let mut buffer = [0; 4];
+            for byte in char.encode_utf8(&mut buffer).bytes() {
+                output.push_str(&format!("%{byte:02X}"));
+            }
+        }
+    }
+    output
+}
+
+fn is_safe_url(url: &str, image: bool) -> bool {
+    const LINK_PROTOCOLS: &[&str] = &["http", "https", "mailto", "irc", "ircs", "xmpp"];
+    const IMAGE_PROTOCOLS: &[&str] = &["http", "https"];
+    let protocols = if image { IMAGE_PROTOCOLS } else { LINK_PROTOCOLS };
+    let end = url.find(['?', '#', '/']).unwrap_or(url.len());
+    match url[..end].find(':') {
+        Some(colon) => protocols
+            .iter()
+            .any(|protocol| url[..colon].eq_ignore_ascii_case(protocol)),
+        // Relative.
+        None => true,
+    }
+}
@@ -0,0 +1,411 @@
+//! Incremental reparsing of top-level blocks.
+//!
+//! A [`ParsedDocument`] remembers its source so that a [`TextEdit`] only needs
+//! to reparse the top-level blocks around the edited range. Blocks before the
+//! region are reused as is, blocks after it are reused with their positions
+//! shifted.
+//!
+//! ```
+//! use markdown_format::incremental::{ParsedDocument, TextEdit};
+//! let document = ParsedDocument::parse("# A\n\nb\n\nc\n", Default::default()).unwrap();
+//! let reparsed = document.apply(&TextEdit::new(5..6, "B")).unwrap();
+//! assert_eq!(reparsed.document.source(), "# A\n\nB\n\nc\n");
+//! assert_eq!(reparsed.changed, vec![1]);
+//! ```
+use std::ops::Range;
+use crate::{Error, Node, ParseConfig, ParseLimits};
+use crate::offsets::{add_unicode_offsets_from, Counts};
+use crate::text::{line_endings, line_start};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A parsed document along with the source and config it was parsed from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedDocument {
+    source: String,
+    config: ParseConfig,
+    nodes: Vec<Node>,
+}
+
+/// Replaces the given byte range of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Byte range in the previous source, both ends must be on `char` boundaries.
+    pub range: Range<usize>,
+    pub replacement: String,
+}
+
+/// Result of [`ParsedDocument::apply`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reparsed {
+    pub document: ParsedDocument,
+    /// Indices of the top-level blocks of the new document that are not
+    /// equal to a block of the previous document, in ascending order.
+    pub changed: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEditError {
+    pub range: Range<usize>,
+    pub source_length: usize,
+}
+
+impl std::fmt::Display for InvalidEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "edit range {}..{} is not a valid char range of a {} byte source",
+            self.range.start,
+            self.range.end,
+            self.source_length,
+        )
+    }
+}
+impl std::error::Error for InvalidEditError {}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl TextEdit {
+    pub fn new(range: Range<usize>, replacement: impl Into<String>) -> Self {
+        Self { range, replacement: replacement.into() }
+    }
+}
+
+impl ParsedDocument {
+    pub fn parse(source: impl Into<String>, config: ParseConfig) -> Result<Self, Error> {
+        let source = source.into();
+        let nodes = crate::parse_with(&source, &config)?;
+        Ok(Self { source, config, nodes })
+    }
+    pub fn source(&self) -> &str { &self.source }
+    pub fn config(&self) -> &ParseConfig { &self.config }
+    pub fn nodes(&self) -> &[Node] { &self.nodes }
+    pub fn into_nodes(self) -> Vec<Node> { self.nodes }
+    /// Applies the edit, the resulting tree is equal to parsing the edited
+    /// source from scratch.
+    pub fn apply(&self, edit: &TextEdit) -> Result<Reparsed, Error> {
+        let TextEdit { range, replacement } = edit;
+        let is_valid = range.start <= range.end
+            && self.source.is_char_boundary(range.start)
+            && self.source.is_char_boundary(range.end);
+        if !is_valid {
+            let source_length = self.source.len();
+            return Err(InvalidEditError { range: range.clone(), source_length }.into())
+        }
+        let mut source = String::with_capacity(self.source.len() + replacement.len());
+        source.push_str(&self.source[..range.start]);
+        source.push_str(replacement);
+        source.push_str(&self.source[range.end..]);
+        crate::parser::check_input_size(source.len(), &self.config.limits)?;
+        let blocks = match self.nodes.iter().map(block_span).collect::<Option<Vec<_>>>() {
+            Some(blocks) => blocks,
+            None => return self.reparse_everything(source),
+        };
+        let mut region = Region {
+            first: blocks
+                .iter()
+                .position(|block| block.end >= range.start)
+                .unwrap_or(blocks.len())
+                .saturating_sub(1),
+            end: blocks
+                .iter()
+                .take_while(|block| block.start <= range.end)
+                .count()
+                .saturating_add(1)
+                .min(blocks.len()),
+        };
+        // A `---` on the first line may open frontmatter that ends anywhere.
+        let first_line_end = self.source.find(['\n', '\r']).unwrap_or(self.source.len());
+        if self.config.frontmatter && range.start <= first_line_end {
+            region = Region { first: 0, end: blocks.len() };
+        }
+        let mut step = 1;
+        loop {
+            let attempt = Attempt::new(self, &source, edit, &blocks, &region);
+            let Some(attempt) = attempt.run()? else {
+                region = Region { first: 0, end: blocks.len() };
+                continue
+            };
+            let (before, after) = attempt.matching_boundaries();
+            let stable_before = before > 0 || region.first == 0;
+            let stable_after = after > 0 || region.end == blocks.len();
+            if stable_before && stable_after {
+                let reparsed = attempt.finish(before, after);
+                crate::parser::check_node_count(&reparsed.document.nodes, &self.config.limits)?;
+                return Ok(reparsed)
+            }
+            // The edit affects the parse of blocks outside the region (e.g. an
+            // unclosed fence), grow it on the unstable sides.
+            if !stable_before {
+                region.first = region.first.saturating_sub(step);
+            }
+            if !stable_after {
+                region.end = region.end.saturating_add(step).min(blocks.len());
+            }
+            step *= 2;
+        }
+    }
+    fn reparse_everything(&self, source: String) -> Result<Reparsed, Error> {
+        let document = Self::parse(source, self.config.clone())?;
+        let changed = (0..document.nodes.len()).collect();
+        Ok(Reparsed { document, changed })
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Difference between the positions of a node in two sources.
+struct Shift {
+    offset: isize,
+    lines: isize,
+    utf16: isize,
+    scalars: isize,
+}
+
+/// Byte offsets of a top-level block.
+struct BlockSpan {
+    start: usize,
+    end: usize,
+    line: usize,
+}
+
+/// Range of top-level blocks of the previous document that get reparsed.
+struct Region {
+    first: usize,
+    end: usize,
+}
+
+struct Attempt<'a> {
+    previous: &'a ParsedDocument,
+    source: &'a str,
+    region: Range<usize>,
+    /// Byte range of the region in the previous and in the new source.
+    old_bytes: Range<usize>,
+    new_bytes: Range<usize>,
+    /// Line of the region start, same in both sources.
+    line: usize,
+    nodes: Vec<Node>,
+}
+
+impl<'a> Attempt<'a> {
+    fn new(
+        previous: &'a ParsedDocument,
+        source: &'a str,
+        edit: &TextEdit,
+        blocks: &[BlockSpan],
+        region: &Region,
+    ) -> Self {
+        let (start, line) = match blocks.get(region.first) {
+            Some(block) if region.first > 0 => (line_start(&previous.source, block.start), block.line),
+            _ => (0, 1),
+        };
+        let old_end = match blocks.get(region.end) {
+            Some(block) => line_start(&previous.source, block.start),
+            None => previous.source.len(),
+        };
+        let new_end = old_end + edit.replacement.len() + edit.range.start - edit.range.end;
+        Attempt {
+            previous,
+            source,
+            region: region.first..region.end,
+            old_bytes: start..old_end,
+            new_bytes: start..new_end,
+            line,
+            nodes: Vec::new(),
+        }
+    }
+    /// Parses the region, returns `None` if it has to be the whole document.
+    fn run(mut self) -> Result<Option<Self>, Error> {
+        let whole_document = self.region.start == 0 && self.region.end == self.previous.nodes.len();
+        let config = &self.previous.config;
+        let text = &self.source[self.new_bytes.clone()];
+        if whole_document {
+            self.nodes = crate::parse_with(text, config)?;
+            return Ok(Some(self))
+        }
+        let has_definitions = config.definition || config.gfm_footnote_definition;
+        let reparsed = &self.previous.nodes[self.region.clone()];
+        if has_definitions && reparsed.iter().any(contains_definition) {
+            return Ok(None)
+        }
+        let outside = self.previous.nodes[..self.region.start]
+            .iter()
+            .chain(&self.previous.nodes[self.region.end..]);
+        let source = &self.previous.source;
+        let nodes = parse_region(config, source, outside, text, self.new_bytes.start, self.line)?;
+        let Some(nodes) = nodes else { return Ok(None) };
+        if nodes.iter().any(contains_definition) && has_definitions {
+            return Ok(None)
+        }
+        self.nodes = nodes;
+        Ok(Some(self))
+    }
+    /// Number of leading and trailing reparsed blocks that equal the previous
+    /// ones.
+    fn matching_boundaries(&self) -> (usize, usize) {
+        let previous = &self.previous.nodes[self.region.clone()];
+        let before = self.nodes
+            .iter()
+            .zip(previous)
+            .take_while(|(new, old)| new == old)
+            .count();
+        let tail_shift = self.tail_shift();
+        let after = self.nodes[before..]
+            .iter()
+            .rev()
+            .zip(previous[before..].iter().rev())
+            .take_while(|(new, old)| {
+                let mut old = (*old).clone();
+                shift(&mut old, &tail_shift);
+                *new == &old
+            })
+            .count();
+        (before, after)
+    }
+    /// Shift of the positions after the region.
+    fn tail_shift(&self) -> Shift {
+        let new = &self.source[self.new_bytes.clone()];
+        let old = &self.previous.source[self.old_bytes.clone()];
+        let (utf16, scalars) = match self.previous.config.unicode_offsets {
+            true => {
+                let (new, old) = (Counts::of(new), Counts::of(old));
+                (new.utf16 as isize - old.utf16 as isize, new.scalars as isize - old.scalars as isize)
+            }
+            false => (0, 0),
+        };
+        Shift {
+            offset: new.len() as isize - old.len() as isize,
+            lines: line_endings(new) as isize - line_endings(old) as isize,
+            utf16,
+            scalars,
+        }
+    }
+    fn finish(self, before: usize, after: usize) -> Reparsed {
+        let tail_shift = self.tail_shift();
+        let changed = self.region.start + before..self.region.start + self.nodes.len() - after;
+        let previous = &self.previous.nodes;
+        let mut nodes = Vec::with_capacity(previous.len() - self.region.len() + self.nodes.len());
+        nodes.extend_from_slice(&previous[..self.region.start]);
+        nodes.extend(self.nodes);
+        nodes.extend(previous[self.region.end..].iter().cloned().map(|mut node| {
+            shift(&mut node, &tail_shift);
+            node
+        }));
+        let document = ParsedDocument {
+            source: self.source.to_owned(),
+            config: self.previous.config.clone(),
+            nodes,
+        };
+        Reparsed { document, changed: changed.collect() }
+    }
+}
+
+/// Parses `text`, which starts at the beginning of line `line` (byte `offset`)
+/// of a document whose other top-level blocks are `outside`, with positions
+/// relative to the whole document.
+///
+/// References only resolve against defined labels, so the definitions among
+/// `outside` (sliced from their `source`) are parsed along with the text.
+/// Returns `None` if that can't be done faithfully.
+pub(crate) fn parse_region<'a>(
+    config: &ParseConfig,
+    source: &str,
+    outside: impl IntoIterator<Item = &'a Node>,
+    text: &str,
+    offset: usize,
+    line: usize,
+) -> Result<Option<Vec<Node>>, Error> {
+    let has_definitions = config.definition || config.gfm_footnote_definition;
+    let mut prelude = String::new();
+    let mut prelude_length = 0;
+    if has_definitions {
+        for node in outside {
+            match node {
+                Node::Definition(_) | Node::FootnoteDefinition(_) => {
+                    let Some(span) = block_span(node) else { return Ok(None) };
+                    let start = line_start(source, span.start);
+                    prelude.push_str(&source[start..span.end]);
+                    prelude.push_str("\n\n");
+                    prelude_length += 1;
+                }
+                node if contains_definition(node) => return Ok(None),
+                _ => (),
+            }
+        }
+    }
+    if prelude_length > 0 {
+        // Frontmatter is only recognized at the very start.
+        if offset == 0 && config.frontmatter {
+            return Ok(None)
+        }
+        // A paragraph ends any footnote definition in the prelude.
+        prelude.push_str("x\n\n");
+    }
+    let region_config = ParseConfig {
+        frontmatter: config.frontmatter && offset == 0,
+        unicode_offsets: false,
+        // Checked on the whole document by the callers.
+        limits: ParseLimits { max_input_bytes: None, max_nodes: None, ..config.limits },
+        ..config.clone()
+    };
+    let mut nodes = crate::parse_with(format!("{prelude}{text}"), &region_config)?;
+    if prelude_length > 0 {
+        let is_prelude = nodes.len() > prelude_length
+            && nodes[..prelude_length]
+                .iter()
+                .all(|node| matches!(node, Node::Definition(_) | Node::FootnoteDefinition(_)))
+            && matches!(nodes[prelude_length], Node::Paragraph(_));
+        if !is_prelude {
+            return Ok(None)
+        }
+        nodes.drain(..=prelude_length);
+    }
+    let region_shift = Shift {
+        offset: offset as isize - prelude.len() as isize,
+        lines: line as isize - 1 - line_endings(&prelude) as isize,
+        utf16: 0,
+        scalars: 0,
+    };
+    for node in nodes.iter_mut() {
+        shift(node, &region_shift);
+    }
+    if config.unicode_offsets {
+        add_unicode_offsets_from(text, offset, Counts::of(&source[..offset]), &mut nodes);
+    }
+    Ok(Some(nodes))
+}
+
+fn block_span(node: &Node) -> Option<BlockSpan> {
+    let position = node.position()?;
+    Some(BlockSpan {
+        start: position.start.offset,
+        end: position.end.offset,
+        line: position.start.line,
+    })
+}
+
+pub(crate) fn contains_definition(node: &Node) -> bool {
+    match node {
+        Node::Definition(_) | Node::FootnoteDefinition(_) => true,
+        node => node.children().iter().any(contains_definition),
+    }
+}
+
+fn shift(node: &mut Node, shift_by: &Shift) {
+    if let Some(position) = node.position_mut() {
+        for point in [&mut position.start, &mut position.end] {
+            point.offset = point.offset.wrapping_add_signed(shift_by.offset);
+            point.line = point.line.wrapping_add_signed(shift_by.lines);
+            point.utf16_offset = point.utf16_offset.map(|x| x.wrapping_add_signed(shift_by.utf16));
+            point.scalar_offset = point.scalar_offset.map(|x| x.wrapping_add_signed(shift_by.scalars));
+        }
+    }
+    if let Some(children) = node.children_mut() {
+        for child in children {
+            shift(child, shift_by);
+        }
+    }
+}
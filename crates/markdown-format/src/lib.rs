@@ -1,6 +1,31 @@
 mod data;
+mod config;
 mod parser;
 mod debug;
+mod wire;
+mod error;
+mod text;
+pub mod binary;
+pub mod pretty_tree;
+pub mod html;
+pub mod printer;
+pub mod incremental;
+pub mod stream;
+pub mod outline;
+pub mod resolve;
+pub mod frontmatter;
+pub mod visit;
+pub mod plain_text;
+pub mod offsets;
+pub mod admonition;
+pub mod definition_list;
+pub mod wikilink;
+pub mod lint;
+#[cfg(feature = "highlight")]
+pub mod highlight;
 
 pub use data::*;
-pub use parser::*;
\ No newline at end of file
+pub use config::*;
+pub use parser::*;
+pub use wire::*;
+pub use error::*;
\ No newline at end of file
@@ -0,0 +1,567 @@
+//! Lint rules over the AST, for inline editor warnings.
+//!
+//! A [`Linter`] runs a set of [`Rule`]s over a parsed document and reports
+//! [`Diagnostic`]s with the source range of the offending construct. The
+//! built-in rules are on by default, teams can add their own by implementing
+//! [`Rule`].
+//!
+//! ```
+//! use markdown_format::lint::{self, Severity};
+//! let source = "# Title\n\n### Details\n\nSee https://example.com\n";
+//! let nodes = markdown_format::parse(source).unwrap();
+//! let diagnostics = lint::lint(source, &nodes);
+//! let rules = diagnostics.iter().map(|x| x.rule.as_str()).collect::<Vec<_>>();
+//! assert_eq!(rules, ["heading-increment", "bare-url"]);
+//! assert_eq!(diagnostics[0].severity, Severity::Warning);
+//! assert_eq!(diagnostics[0].position.as_ref().unwrap().start.line, 3);
+//! ```
+use std::collections::HashMap;
+use std::ops::Range;
+use crate::{List, Node, Point, SourceRange, Text};
+use crate::text::{line_endings, line_start};
+use crate::offsets::Counts;
+use crate::outline::{level, plain_text, slugify};
+use crate::resolve::DiagnosticKind;
+use crate::visit::{self, Visit};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Diagnostic {
+    /// Name of the rule, such as `heading-increment`.
+    pub rule: String,
+    pub severity: Severity,
+    pub message: String,
+    pub position: Option<SourceRange>,
+}
+
+/// What a [`Rule`] reports, the [`Linter`] adds the rule name and severity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub message: String,
+    pub position: Option<SourceRange>,
+}
+
+/// The document being linted.
+#[derive(Debug, Clone, Copy)]
+pub struct Context<'a> {
+    /// The source the nodes were parsed from.
+    pub source: &'a str,
+    pub nodes: &'a [Node],
+}
+
+/// A check over a whole document.
+///
+/// ```
+/// use markdown_format::Node;
+/// use markdown_format::lint::{Context, Finding, Linter, Rule};
+///
+/// struct NoHtml;
+///
+/// impl Rule for NoHtml {
+///     fn name(&self) -> &str {
+///         "no-html"
+///     }
+///     fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+///         markdown_format::visit::depth_first(context.nodes)
+///             .filter(|x| matches!(x, Node::Html(_)))
+///             .map(|x| Finding { message: "raw HTML".to_owned(), position: x.position().cloned() })
+///             .collect()
+///     }
+/// }
+///
+/// let source = "a <b>c</b>\n";
+/// let mut linter = Linter::default();
+/// linter.add(NoHtml);
+/// let diagnostics = linter.lint(source, &markdown_format::parse(source).unwrap());
+/// assert_eq!(diagnostics.len(), 2);
+/// ```
+pub trait Rule {
+    /// Unique, in kebab case.
+    fn name(&self) -> &str;
+    fn check(&self, context: &Context<'_>) -> Vec<Finding>;
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+/// Runs a set of rules, [`Linter::default`] has all the built-in ones.
+pub struct Linter {
+    rules: Vec<(Box<dyn Rule>, Severity)>,
+}
+
+/// Levels of rules by name, as in `{"bare-url": "off", "heading-increment":
+/// "error"}`. Rules not mentioned keep their level, unknown names are ignored.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct LintConfig {
+    pub rules: HashMap<String, RuleLevel>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleLevel {
+    Off,
+    Warning,
+    Error,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// ## BUILT-IN RULES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// `heading-increment`: headings only go one level deeper at a time.
+#[derive(Debug, Clone, Default)]
+pub struct HeadingIncrement;
+
+/// `duplicate-heading-slug`: headings get distinct anchors without the `-1`,
+/// `-2`, … suffixes of [`crate::outline::Slugger`].
+#[derive(Debug, Clone, Default)]
+pub struct DuplicateHeadingSlug;
+
+/// `empty-link`: links have text and a destination.
+#[derive(Debug, Clone, Default)]
+pub struct EmptyLink;
+
+/// `image-alt`: images have alternative text.
+#[derive(Debug, Clone, Default)]
+pub struct ImageAlt;
+
+/// `bare-url`: URLs are written as autolinks (`<https://…>`) or links.
+#[derive(Debug, Clone, Default)]
+pub struct BareUrl;
+
+/// `list-marker-style`: bullet lists use the same marker.
+#[derive(Debug, Clone, Default)]
+pub struct ListMarkerStyle {
+    /// `-`, `*` or `+`, the first marker of the document by default.
+    pub marker: Option<char>,
+}
+
+/// `hard-break-spaces`: hard breaks use a backslash rather than invisible
+/// trailing spaces.
+#[derive(Debug, Clone, Default)]
+pub struct HardBreakSpaces;
+
+/// `undefined-reference`: link, image and footnote references have a
+/// definition.
+#[derive(Debug, Clone, Default)]
+pub struct UndefinedReference;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Lints with the built-in rules.
+pub fn lint(source: &str, nodes: &[Node]) -> Vec<Diagnostic> {
+    Linter::default().lint(source, nodes)
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        let mut linter = Linter::empty();
+        linter.add(HeadingIncrement);
+        linter.add(DuplicateHeadingSlug);
+        linter.add(EmptyLink);
+        linter.add(ImageAlt);
+        linter.add(BareUrl);
+        linter.add(ListMarkerStyle::default());
+        linter.add(HardBreakSpaces);
+        linter.add(UndefinedReference);
+        linter
+    }
+}
+
+impl Linter {
+    /// A linter without rules.
+    pub fn empty() -> Self {
+        Linter { rules: Vec::new() }
+    }
+    /// Adds the rule at its default severity, replacing the rule with the
+    /// same name if any.
+    pub fn add(&mut self, rule: impl Rule + 'static) {
+        let severity = rule.default_severity();
+        match self.rules.iter_mut().find(|(x, _)| x.name() == rule.name()) {
+            Some(entry) => *entry = (Box::new(rule), severity),
+            None => self.rules.push((Box::new(rule), severity)),
+        }
+    }
+    pub fn remove(&mut self, name: &str) {
+        self.rules.retain(|(x, _)| x.name() != name);
+    }
+    pub fn set_severity(&mut self, name: &str, severity: Severity) {
+        for (_, x) in self.rules.iter_mut().filter(|(x, _)| x.name() == name) {
+            *x = severity;
+        }
+    }
+    pub fn configure(&mut self, config: &LintConfig) {
+        for (name, level) in &config.rules {
+            match level {
+                RuleLevel::Off => self.remove(name),
+                RuleLevel::Warning => self.set_severity(name, Severity::Warning),
+                RuleLevel::Error => self.set_severity(name, Severity::Error),
+            }
+        }
+    }
+    /// Names of the rules, in order.
+    pub fn rules(&self) -> impl Iterator<Item = &str> {
+        self.rules.iter().map(|(x, _)| x.name())
+    }
+    /// Diagnostics of all rules in source order, those without position last.
+    pub fn lint(&self, source: &str, nodes: &[Node]) -> Vec<Diagnostic> {
+        let context = Context { source, nodes };
+        let mut diagnostics = Vec::new();
+        for (rule, severity) in &self.rules {
+            diagnostics.extend(rule.check(&context).into_iter().map(|x| Diagnostic {
+                rule: rule.name().to_owned(),
+                severity: *severity,
+                message: x.message,
+                position: x.position,
+            }));
+        }
+        diagnostics.sort_by_key(|x| x.position.as_ref().map_or(usize::MAX, |x| x.start.offset));
+        diagnostics
+    }
+}
+
+impl<'a> Context<'a> {
+    /// The source of a node, `None` without (valid) position.
+    pub fn source_of(&self, node: &Node) -> Option<&'a str> {
+        let position = node.position()?;
+        self.source.get(position.start.offset..position.end.offset)
+    }
+    /// The source range of a byte range of the source, `None` if it's out of
+    /// bounds or not on character boundaries. It has Unicode offsets if the
+    /// positions of the nodes have them.
+    pub fn range(&self, range: Range<usize>) -> Option<SourceRange> {
+        let unicode = self.nodes.iter().find_map(Node::position).is_some_and(|x| x.start.utf16_offset.is_some());
+        let start = Point {
+            line: 1,
+            column: 1,
+            offset: 0,
+            utf16_offset: unicode.then_some(0),
+            scalar_offset: unicode.then_some(0),
+        };
+        self.range_from(&start, range)
+    }
+    /// Like [`Self::range`], counting lines and Unicode offsets from an
+    /// earlier point.
+    fn range_from(&self, from: &Point, range: Range<usize>) -> Option<SourceRange> {
+        let start = self.point_from(from, range.start)?;
+        let end = self.point_from(&start, range.end)?;
+        Some(SourceRange { start, end })
+    }
+    fn point_from(&self, from: &Point, offset: usize) -> Option<Point> {
+        let between = self.source.get(from.offset..offset)?;
+        let counts = Counts::of(between);
+        Some(Point {
+            line: from.line + line_endings(between),
+            column: offset - line_start(self.source, offset) + 1,
+            offset,
+            utf16_offset: from.utf16_offset.map(|x| x + counts.utf16),
+            scalar_offset: from.scalar_offset.map(|x| x + counts.scalars),
+        })
+    }
+    /// Where `value` (a piece of the text) is in the source of the text, if
+    /// it's there verbatim, searching from byte `after` of the source on.
+    fn find_in(&self, text: &Text, value: &str, after: usize) -> Option<SourceRange> {
+        let position = text.position.as_ref()?;
+        let start = position.start.offset.max(after);
+        let index = self.source.get(start..position.end.offset)?.find(value)?;
+        self.range_from(&position.start, start + index..start + index + value.len())
+    }
+}
+
+impl Finding {
+    fn new(message: impl Into<String>, position: Option<&SourceRange>) -> Self {
+        Finding { message: message.into(), position: position.cloned() }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({})", self.message, self.rule)?;
+        if let Some(position) = self.position.as_ref() {
+            write!(f, " at {}:{}", position.start.line, position.start.column)?;
+        }
+        Ok(())
+    }
+}
+
+impl Rule for HeadingIncrement {
+    fn name(&self) -> &str {
+        "heading-increment"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut previous = None;
+        for heading in headings(context.nodes) {
+            let level = level(&heading.level);
+            if let Some(previous) = previous.filter(|x| level > x + 1) {
+                let message = format!("heading level {level} after level {previous}, expected {}", previous + 1);
+                findings.push(Finding::new(message, heading.position.as_ref()));
+            }
+            previous = Some(level);
+        }
+        findings
+    }
+}
+
+impl Rule for DuplicateHeadingSlug {
+    fn name(&self) -> &str {
+        "duplicate-heading-slug"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut first = HashMap::new();
+        for heading in headings(context.nodes) {
+            let slug = slugify(&plain_text(&heading.children));
+            match first.get(&slug) {
+                Some(line) => {
+                    let message = format!("heading slug `{slug}` is already used by the heading at line {line}");
+                    findings.push(Finding::new(message, heading.position.as_ref()));
+                }
+                None => {
+                    first.insert(slug, heading.position.as_ref().map_or(0, |x| x.start.line));
+                }
+            }
+        }
+        findings
+    }
+}
+
+impl Rule for EmptyLink {
+    fn name(&self) -> &str {
+        "empty-link"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        for node in visit::depth_first(context.nodes) {
+            let (children, url, position) = match node {
+                Node::Link(x) => (&x.children, Some(&x.url), &x.position),
+                Node::LinkReference(x) => (&x.children, x.url.as_ref(), &x.position),
+                _ => continue,
+            };
+            if plain_text(children).trim().is_empty() && !children.iter().any(|x| matches!(x, Node::Image(_) | Node::ImageReference(_))) {
+                findings.push(Finding::new("link without text", position.as_ref()));
+            }
+            if url.is_some_and(|x| x.is_empty() || x == "#") {
+                findings.push(Finding::new("link without destination", position.as_ref()));
+            }
+        }
+        findings
+    }
+}
+
+impl Rule for ImageAlt {
+    fn name(&self) -> &str {
+        "image-alt"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        visit::depth_first(context.nodes)
+            .filter_map(|node| match node {
+                Node::Image(x) if x.alt.trim().is_empty() => Some(&x.position),
+                Node::ImageReference(x) if x.alt.trim().is_empty() => Some(&x.position),
+                _ => None,
+            })
+            .map(|x| Finding::new("image without alternative text", x.as_ref()))
+            .collect()
+    }
+}
+
+impl Rule for BareUrl {
+    fn name(&self) -> &str {
+        "bare-url"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        let mut visitor = BareUrls { context, findings: Vec::new() };
+        visitor.visit_nodes(context.nodes);
+        visitor.findings
+    }
+}
+
+impl Rule for ListMarkerStyle {
+    fn name(&self) -> &str {
+        "list-marker-style"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        let mut findings = Vec::new();
+        let mut expected = self.marker;
+        let lists = visit::depth_first(context.nodes).filter_map(|x| match x {
+            Node::List(list) if !list.ordered => Some(list),
+            _ => None,
+        });
+        for list in lists {
+            let Some(marker) = marker(context, list) else { continue };
+            match expected {
+                Some(expected) if marker != expected => {
+                    let message = format!("list marker `{marker}`, expected `{expected}`");
+                    findings.push(Finding::new(message, list.position.as_ref()));
+                }
+                Some(_) => (),
+                None => expected = Some(marker),
+            }
+        }
+        findings
+    }
+}
+
+impl Rule for HardBreakSpaces {
+    fn name(&self) -> &str {
+        "hard-break-spaces"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        visit::depth_first(context.nodes)
+            .filter(|x| matches!(x, Node::Newline(_)))
+            .filter(|x| context.source_of(x).is_some_and(|x| x.starts_with([' ', '\t'])))
+            .map(|x| Finding::new("hard break made of trailing spaces, use a backslash", x.position()))
+            .collect()
+    }
+}
+
+impl Rule for UndefinedReference {
+    fn name(&self) -> &str {
+        "undefined-reference"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        // The parser turns references to undefined definitions into text,
+        // edited trees may still have reference nodes without definition.
+        let mut nodes = context.nodes.to_vec();
+        let resolution = crate::resolve::resolve(&mut nodes);
+        let mut findings = resolution.diagnostics
+            .iter()
+            .filter_map(|x| match x.kind {
+                DiagnosticKind::UndefinedReference => Some(("reference", x)),
+                DiagnosticKind::UndefinedFootnote => Some(("footnote", x)),
+                _ => None,
+            })
+            .map(|(kind, x)| Finding::new(format!("{kind} `{}` is not defined", x.identifier), x.position.as_ref()))
+            .collect::<Vec<_>>();
+        let mut visitor = UndefinedReferences { context, findings: Vec::new() };
+        visitor.visit_nodes(context.nodes);
+        findings.append(&mut visitor.findings);
+        findings
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+fn headings(nodes: &[Node]) -> impl Iterator<Item = &crate::Heading> {
+    visit::depth_first(nodes).filter_map(|x| match x {
+        Node::Heading(heading) => Some(heading),
+        _ => None,
+    })
+}
+
+/// The marker of the first item, items of a list share theirs.
+fn marker(context: &Context<'_>, list: &List) -> Option<char> {
+    let item = list.children.first()?;
+    context.source_of(item)?.chars().next().filter(|x| matches!(x, '-' | '*' | '+'))
+}
+
+/// URLs in text, links made of a literal URL (GFM autolink literals).
+struct BareUrls<'a, 'b> {
+    context: &'b Context<'a>,
+    findings: Vec<Finding>,
+}
+
+impl Visit for BareUrls<'_, '_> {
+    fn visit_text(&mut self, text: &Text) {
+        let mut after = 0;
+        for url in urls(&text.value) {
+            let found = self.context.find_in(text, url, after);
+            if let Some(found) = found.as_ref() {
+                after = found.end.offset;
+            }
+            let position = found.or_else(|| text.position.clone());
+            self.findings.push(Finding::new(format!("bare URL `{url}`, use `<{url}>`"), position.as_ref()));
+        }
+    }
+    fn visit_link(&mut self, link: &crate::Link) {
+        let node_source = link.position.as_ref().and_then(|x| self.context.source.get(x.start.offset..x.end.offset));
+        if let Some(value) = node_source.filter(|x| !x.starts_with(['[', '<'])) {
+            self.findings.push(Finding::new(format!("bare URL `{value}`, use `<{value}>`"), link.position.as_ref()));
+        }
+    }
+    fn visit_link_reference(&mut self, _: &crate::LinkReference) {}
+    fn visit_wiki_link(&mut self, _: &crate::WikiLink) {}
+}
+
+fn urls(value: &str) -> Vec<&str> {
+    let mut urls = Vec::new();
+    let mut start = 0;
+    while let Some(index) = value[start..].find("http") {
+        let begin = start + index;
+        let candidate = &value[begin..];
+        let end = candidate.find(|x: char| x.is_whitespace() || matches!(x, '<' | '>')).unwrap_or(candidate.len());
+        let url = candidate[..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '\'', '"']);
+        let has_scheme = ["http://", "https://"].iter().any(|x| url.len() > x.len() && url.starts_with(x));
+        if has_scheme && !value[..begin].ends_with(char::is_alphanumeric) {
+            urls.push(url);
+            start = begin + url.len();
+        } else {
+            start = begin + 4;
+        }
+    }
+    urls
+}
+
+/// References written verbatim, which the parser leaves as text when the
+/// definition doesn't exist: `[text][label]`, `[label][]` and `[^label]`.
+struct UndefinedReferences<'a, 'b> {
+    context: &'b Context<'a>,
+    findings: Vec<Finding>,
+}
+
+impl Visit for UndefinedReferences<'_, '_> {
+    fn visit_text(&mut self, text: &Text) {
+        let mut after = 0;
+        for (raw, kind, label) in references(&text.value) {
+            // Escaped brackets are text as well.
+            let Some(position) = self.context.find_in(text, raw, after) else { continue };
+            after = position.end.offset;
+            let message = format!("{kind} `{label}` is not defined");
+            self.findings.push(Finding::new(message, Some(&position)));
+        }
+    }
+}
+
+/// `(raw, kind, label)` of the verbatim references of a text.
+fn references(value: &str) -> Vec<(&str, &'static str, &str)> {
+    let mut references = Vec::new();
+    let mut start = 0;
+    while let Some(index) = value[start..].find('[') {
+        let open = start + index;
+        let Some(label) = bracketed(&value[open..]).filter(|x| !x.trim().is_empty()) else {
+            start = open + 1;
+            continue
+        };
+        let after = open + label.len() + 2;
+        if let Some(second) = bracketed(&value[after..]) {
+            let identifier = if second.trim().is_empty() { label } else { second };
+            let end = after + second.len() + 2;
+            references.push((&value[open..end], "reference", identifier.trim()));
+            start = end;
+        } else if let Some(footnote) = label.strip_prefix('^').filter(|x| !x.trim().is_empty()) {
+            references.push((&value[open..after], "footnote", footnote.trim()));
+            start = after;
+        } else {
+            start = open + 1;
+        }
+    }
+    references
+}
+
+/// The content of `[…]` at the start of `value`, without nested brackets.
+fn bracketed(value: &str) -> Option<&str> {
+    let content = value.strip_prefix('[')?;
+    let end = content.find([']', '['])?;
+    content[end..].starts_with(']').then(|| &content[..end])
+}
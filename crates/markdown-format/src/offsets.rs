@@ -0,0 +1,84 @@
+//! UTF-16 and Unicode scalar offsets alongside the byte offsets of [`Point`].
+//!
+//! ```
+//! let config = markdown_format::ParseConfig { unicode_offsets: true, ..Default::default() };
+//! let nodes = markdown_format::parse_with("😀 *a*", &config).unwrap();
+//! let markdown_format::Node::Paragraph(paragraph) = &nodes[0] else { panic!() };
+//! let start = &paragraph.children[1].position().unwrap().start;
+//! assert_eq!((start.offset, start.utf16_offset, start.scalar_offset), (5, Some(3), Some(2)));
+//! ```
+use crate::{Node, Point};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Sets [`Point::utf16_offset`] and [`Point::scalar_offset`] of every position
+/// in the tree from its byte offset into `source`.
+pub fn add_unicode_offsets(source: &str, nodes: &mut [Node]) {
+    add_unicode_offsets_from(source, 0, Counts::default(), nodes)
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Length of a text in UTF-16 code units and in Unicode scalar values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct Counts {
+    pub(crate) utf16: usize,
+    pub(crate) scalars: usize,
+}
+
+impl Counts {
+    pub(crate) fn of(text: &str) -> Self {
+        text.chars().fold(Counts::default(), |counts, char| Counts {
+            utf16: counts.utf16 + char.len_utf16(),
+            scalars: counts.scalars + 1,
+        })
+    }
+    pub(crate) fn set(self, point: &mut Point) {
+        point.utf16_offset = Some(self.utf16);
+        point.scalar_offset = Some(self.scalars);
+    }
+}
+
+/// Like [`add_unicode_offsets`] for nodes whose byte offsets are relative to
+/// a document in which `text` starts at byte `offset`, preceded by `before`.
+pub(crate) fn add_unicode_offsets_from(text: &str, offset: usize, before: Counts, nodes: &mut [Node]) {
+    let mut offsets = Vec::new();
+    for node in crate::visit::depth_first(nodes) {
+        if let Some(position) = node.position() {
+            offsets.extend([position.start.offset, position.end.offset]);
+        }
+    }
+    offsets.sort_unstable();
+    offsets.dedup();
+    // One pass over the text for all offsets, in order.
+    let mut table = Vec::with_capacity(offsets.len());
+    let mut chars = text.char_indices().peekable();
+    let mut counts = before;
+    for target in offsets {
+        let relative = target.saturating_sub(offset);
+        while let Some((_, char)) = chars.next_if(|(index, _)| *index < relative) {
+            counts.utf16 += char.len_utf16();
+            counts.scalars += 1;
+        }
+        table.push((target, counts));
+    }
+    set_counts(nodes, &table);
+}
+
+fn set_counts(nodes: &mut [Node], table: &[(usize, Counts)]) {
+    let lookup = |offset: usize| {
+        let index = table.partition_point(|(x, _)| *x < offset);
+        table[index].1
+    };
+    for node in nodes {
+        if let Some(position) = node.position_mut() {
+            lookup(position.start.offset).set(&mut position.start);
+            lookup(position.end.offset).set(&mut position.end);
+        }
+        if let Some(children) = node.children_mut() {
+            set_counts(children, table);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Document outline (table of contents) extraction.
+//!
+//! ```
+//! let nodes = markdown_format::parse("# Intro\n\n## Setup\n\n## Setup\n\n# Usage\n").unwrap();
+//! let sections = markdown_format::outline::outline(&nodes);
+//! assert_eq!(sections.len(), 2);
+//! assert_eq!(sections[0].children[1].slug, "setup-1");
+//! ```
+use std::collections::HashMap;
+use crate::{HeadingLevel, Node, SourceRange};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// A heading along with the headings of a deeper level that follow it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Section {
+    /// `1` to `6`.
+    pub level: u8,
+    /// The heading’s plain text content.
+    pub title: String,
+    /// GitHub-style anchor, unique within the document.
+    pub slug: String,
+    pub position: Option<SourceRange>,
+    pub children: Vec<Section>,
+}
+
+/// Generates GitHub-style slugs, de-duplicating repeated ones with a `-1`,
+/// `-2`, … suffix (like `github-slugger`).
+#[derive(Debug, Clone, Default)]
+pub struct Slugger {
+    occurrences: HashMap<String, usize>,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The sections of the document, headings nested in containers (block
+/// quotes, list items, admonitions, …) included.
+pub fn outline(nodes: &[Node]) -> Vec<Section> {
+    let mut slugger = Slugger::default();
+    let mut headings = Vec::new();
+    collect_headings(nodes, &mut slugger, &mut headings);
+    nest(&mut headings.into_iter().peekable(), 0)
+}
+
+impl Slugger {
+    pub fn slug(&mut self, value: &str) -> String {
+        let original = slugify(value);
+        let mut slug = original.clone();
+        while self.occurrences.contains_key(&slug) {
+            let count = self.occurrences.entry(original.clone()).or_default();
+            *count += 1;
+            slug = format!("{original}-{count}");
+        }
+        self.occurrences.insert(slug.clone(), 0);
+        slug
+    }
+}
+
+/// Lowercases, drops punctuation and symbols and turns spaces into dashes.
+pub fn slugify(value: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        .filter(|x| x.is_alphanumeric() || matches!(x, ' ' | '-' | '_'))
+        .map(|x| if x == ' ' { '-' } else { x })
+        .collect()
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+fn collect_headings(nodes: &[Node], slugger: &mut Slugger, headings: &mut Vec<Section>) {
+    for node in nodes {
+        match node {
+            Node::Heading(heading) => {
+                let title = plain_text(&heading.children);
+                headings.push(Section {
+                    level: level(&heading.level),
+                    slug: slugger.slug(&title),
+                    title,
+                    position: heading.position.clone(),
+                    children: Vec::new(),
+                });
+            }
+            Node::Blockquote(x) => collect_headings(&x.children, slugger, headings),
+            Node::List(x) => collect_headings(&x.children, slugger, headings),
+            Node::ListItem(x) => collect_headings(&x.children, slugger, headings),
+            Node::FootnoteDefinition(x) => collect_headings(&x.children, slugger, headings),
+            Node::Admonition(x) => collect_headings(&x.children, slugger, headings),
+            _ => (),
+        }
+    }
+}
+
+/// Consumes the headings deeper than `parent_level`.
+fn nest(
+    headings: &mut std::iter::Peekable<std::vec::IntoIter<Section>>,
+    parent_level: u8,
+) -> Vec<Section> {
+    let mut sections = Vec::new();
+    while let Some(mut section) = headings.next_if(|x| x.level > parent_level) {
+        section.children = nest(headings, section.level);
+        sections.push(section);
+    }
+    sections
+}
+
+pub(crate) fn plain_text(nodes: &[Node]) -> String {
+    let mut text = String::new();
+    push_plain_text(nodes, &mut text);
+    text
+}
+
+fn push_plain_text(nodes: &[Node], text: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(x) => text.push_str(&x.value),
+            Node::InlineCode(x) => text.push_str(&x.value),
+            Node::InlineMath(x) => text.push_str(&x.value),
+            Node::Image(x) => text.push_str(&x.alt),
+            Node::ImageReference(x) => text.push_str(&x.alt),
+            Node::Embed(x) => text.push_str(&x.alt()),
+            Node::WikiLink(x) => text.push_str(&x.text()),
+            Node::Newline(_) => text.push(' '),
+            Node::Emphasis(x) => push_plain_text(&x.children, text),
+            Node::Strong(x) => push_plain_text(&x.children, text),
+            Node::Strikethrough(x) => push_plain_text(&x.children, text),
+            Node::Link(x) => push_plain_text(&x.children, text),
+            Node::LinkReference(x) => push_plain_text(&x.children, text),
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn level(level: &HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
@@ -1,63 +1,216 @@
 use itertools::Itertools;
 use markdown::mdast;
 use crate as ast;
+use crate::{Error, ParseConfig, ParseLimits};
 
-pub fn parse(source: impl AsRef<str>) -> Result<Vec<ast::Node>, Box<dyn std::error::Error>> {
+/// Parse with the default construct set (see [`ParseConfig::default`]).
+pub fn parse(source: impl AsRef<str>) -> Result<Vec<ast::Node>, Error> {
+    parse_with(source, &ParseConfig::default())
+}
+
+/// Parse with the constructs selected by the given config.
+pub fn parse_with(source: impl AsRef<str>, config: &ParseConfig) -> Result<Vec<ast::Node>, Error> {
     let source = source.as_ref();
-    let options = ::markdown::ParseOptions {
-        constructs: ::markdown::Constructs {
-            attention: true,
-            autolink: true,
-            block_quote: true,
-            character_escape: true,
-            character_reference: true,
-            code_indented: true,
-            code_fenced: true,
-            code_text: true,
-            definition: false,
-            gfm_strikethrough: true,
-            gfm_table: true,
-            gfm_task_list_item: false,
-            hard_break_escape: true,
-            hard_break_trailing: true,
-            heading_atx: true,
-            heading_setext: true,
-            html_flow: true,
-            html_text: true,
-            label_start_image: true,
-            label_start_link: true,
-            label_end: true,
-            list_item: true,
-            math_flow: true,
-            math_text: true,
-            thematic_break: true,
-            ..Default::default()
-        },
-        ..Default::default()
-    };
-    let node: ::markdown::mdast::Node = ::markdown::to_mdast(source, &options).map_err(ParserError)?;
-    Ok(convert_node(&node))
+    check_input_size(source.len(), &config.limits)?;
+    check_quote_depth(source, config)?;
+    let options = ::markdown::ParseOptions::from(config);
+    let node: ::markdown::mdast::Node = to_mdast(source, &options)?;
+    let nodes = convert(&node, &config.limits);
+    drop_tree(node);
+    let mut nodes = nodes?;
+    if config.admonition {
+        crate::admonition::admonitions(source, &mut nodes);
+    }
+    if config.definition_list {
+        crate::definition_list::definition_lists(source, &mut nodes);
+    }
+    if config.wikilink {
+        crate::wikilink::wikilinks(source, &mut nodes);
+    }
+    #[cfg(feature = "highlight")]
+    if config.highlight {
+        crate::highlight::add_highlights(&mut nodes);
+    }
+    if config.unicode_offsets {
+        crate::offsets::add_unicode_offsets(source, &mut nodes);
+    }
+    check_node_count(&nodes, &config.limits)?;
+    Ok(nodes)
 }
 
-#[derive(Debug)]
-pub struct ParserError(::markdown::message::Message);
+/// Runs the `markdown` crate, which panics on some inputs instead of failing.
+fn to_mdast(source: &str, options: &::markdown::ParseOptions) -> Result<mdast::Node, Error> {
+    // Only the options are shared with the closure and they are never mutated.
+    let parse = std::panic::AssertUnwindSafe(|| ::markdown::to_mdast(source, options));
+    let result = std::panic::catch_unwind(parse);
+    match result {
+        Ok(result) => result.map_err(syntax_error),
+        Err(payload) => {
+            let reason = payload
+                .downcast_ref::<&str>()
+                .map(|x| x.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("unknown panic"));
+            Err(Error::Internal { reason })
+        }
+    }
+}
 
-impl std::fmt::Display for ParserError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { self.0.fmt(f) }
+fn syntax_error(message: ::markdown::message::Message) -> Error {
+    let position = message.place.map(|place| match *place {
+        ::markdown::message::Place::Position(position) => position.start.into(),
+        ::markdown::message::Place::Point(point) => point.into(),
+    });
+    Error::Syntax { reason: message.reason, position }
 }
-impl std::error::Error for ParserError {}
 
-fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
-    match node {
-        mdast::Node::Root(root) => {
-            root.children.iter().flat_map(convert_node).collect_vec()
+/// A document beyond one of the [`ParseLimits`] of its config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitError {
+    pub limit: Limit,
+    /// The configured bound.
+    pub maximum: usize,
+    /// Start of the first node past the limit, `None` for the limits on the
+    /// whole document ([`Limit::InputBytes`] and [`Limit::Nodes`]).
+    pub position: Option<ast::Point>,
+}
+
+/// The field of [`ParseLimits`] a [`LimitError`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    InputBytes,
+    Depth,
+    Nodes,
+    TableCells,
+}
+
+impl Limit {
+    pub(crate) fn check(self, maximum: Option<usize>, value: usize, node: Option<&mdast::Node>) -> Result<(), LimitError> {
+        match maximum {
+            Some(maximum) if value > maximum => Err(LimitError {
+                limit: self,
+                maximum,
+                position: node.and_then(mdast::Node::position).map(|x| x.start.clone().into()),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Display for LimitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let maximum = self.maximum;
+        match self.limit {
+            Limit::InputBytes => write!(f, "input exceeds the limit of {maximum} bytes")?,
+            Limit::Depth => write!(f, "nodes nest deeper than the limit of {maximum}")?,
+            Limit::Nodes => write!(f, "document exceeds the limit of {maximum} nodes")?,
+            Limit::TableCells => write!(f, "table exceeds the limit of {maximum} cells")?,
+        }
+        match &self.position {
+            Some(position) => write!(f, " at {}:{}", position.line, position.column),
+            None => Ok(()),
+        }
+    }
+}
+impl std::error::Error for LimitError {}
+
+/// The limits on the whole document, checked separately by the parsers of
+/// regions of it.
+pub(crate) fn check_input_size(length: usize, limits: &ParseLimits) -> Result<(), LimitError> {
+    Limit::InputBytes.check(limits.max_input_bytes, length, None)
+}
+pub(crate) fn check_node_count(nodes: &[ast::Node], limits: &ParseLimits) -> Result<(), LimitError> {
+    match limits.max_nodes {
+        Some(_) => Limit::Nodes.check(limits.max_nodes, node_count(nodes), None),
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn node_count(nodes: &[ast::Node]) -> usize {
+    crate::visit::depth_first(nodes).count()
+}
+
+/// Rejects lines nesting block quotes deeper than the limit before parsing,
+/// which takes time quadratic in their depth. Stops at the first line that
+/// may be part of a block whose content isn't Markdown (code, math, HTML,
+/// frontmatter), where `>` is just text.
+fn check_quote_depth(source: &str, config: &ParseConfig) -> Result<(), LimitError> {
+    let Some(maximum) = config.limits.max_depth else { return Ok(()) };
+    if config.frontmatter && (source.starts_with("---") || source.starts_with("+++")) {
+        return Ok(())
+    }
+    let mut offset = 0;
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        let content = line.trim_end_matches('\n').trim_end_matches('\r');
+        if content.contains(['`', '~', '$', '<', '\t', '\r']) {
+            return Ok(())
+        }
+        // `>` and an optional space, each one opening a block quote.
+        let bytes = content.as_bytes();
+        let (mut column, mut depth) = (0, 0);
+        while bytes.get(column) == Some(&b'>') {
+            depth += 1;
+            if depth > maximum {
+                let point = ast::Point { line: index + 1, column: column + 1, offset: offset + column, utf16_offset: None, scalar_offset: None };
+                return Err(LimitError { limit: Limit::Depth, maximum, position: Some(point) })
+            }
+            column += if bytes.get(column + 1) == Some(&b' ') { 2 } else { 1 };
+        }
+        offset += line.len();
+    }
+    Ok(())
+}
+
+/// Drops the tree with an explicit stack, it's as deep as the source nests
+/// (beyond the depth limit, which is only checked while converting it) and
+/// its recursive drop would overflow the call stack.
+fn drop_tree(root: mdast::Node) {
+    let mut stack = vec![root];
+    while let Some(mut node) = stack.pop() {
+        if let Some(children) = node.children_mut() {
+            stack.append(children);
         }
-        mdast::Node::Blockquote(mdast::Blockquote {position, children}) => {
+    }
+}
+
+/// Converts the tree bottom-up with an explicit stack, so that deeply nested
+/// documents can't overflow the call stack here, checking the limits on the
+/// way down.
+fn convert(root: &mdast::Node, limits: &ParseLimits) -> Result<Vec<ast::Node>, LimitError> {
+    struct Frame<'a> {
+        node: &'a mdast::Node,
+        /// Index of the next child to descend into.
+        next: usize,
+        children: Vec<ast::Node>,
+    }
+    let mut stack = vec![Frame { node: root, next: 0, children: Vec::new() }];
+    loop {
+        let frame = stack.last_mut().unwrap();
+        if let Some(child) = frame.node.children().and_then(|x| x.get(frame.next)) {
+            frame.next += 1;
+            Limit::Depth.check(limits.max_depth, stack.len(), Some(child))?;
+            if let mdast::Node::Table(table) = child {
+                let cells = table.children.iter().map(|row| row.children().map_or(0, Vec::len)).sum();
+                Limit::TableCells.check(limits.max_table_cells, cells, Some(child))?;
+            }
+            stack.push(Frame { node: child, next: 0, children: Vec::new() });
+            continue
+        }
+        let frame = stack.pop().unwrap();
+        let nodes = convert_node(frame.node, frame.children);
+        match stack.last_mut() {
+            Some(parent) => parent.children.extend(nodes),
+            None => return Ok(nodes),
+        }
+    }
+}
+
+/// Converts a single node, given its converted children.
+fn convert_node(node: &mdast::Node, children: Vec<ast::Node>) -> Vec<ast::Node> {
+    match node {
+        mdast::Node::Root(_) => children,
+        mdast::Node::Blockquote(mdast::Blockquote {position, ..}) => {
             let position = position.to_owned().map(Into::into);
-            let children = children
-                .iter()
-                .flat_map(convert_node)
-                .collect_vec();
             vec![
                 ast::Node::Blockquote(ast::Blockquote {
                     position,
@@ -66,10 +219,6 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
             ]
         }
         mdast::Node::FootnoteDefinition(node) => {
-            let children = node.children
-                .iter()
-                .flat_map(convert_node)
-                .collect_vec();
             let position = node.position.clone().map(Into::into);
             let identifier = node.identifier.clone();
             let label = node.label.clone();
@@ -79,18 +228,15 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
                     position,
                     identifier,
                     label,
+                    number: None,
                 })
             ]
         }
         mdast::Node::List(node) => {
-            let children = node.children
-                .iter()
-                .flat_map(convert_node)
-                .collect_vec();
             let position = node.position.clone().map(Into::into);
-            let ordered = node.ordered.clone();
-            let start = node.start.clone();
-            let spread = node.spread.clone();
+            let ordered = node.ordered;
+            let start = node.start;
+            let spread = node.spread;
             vec![
                 ast::Node::List(ast::List {
                     children,
@@ -139,21 +285,19 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
             let value = node.value.clone();
             let position = node.position.clone().map(Into::into);
             vec![
-                ast::Node::InlineCode(ast::InlineCode { value, position })
+                ast::Node::InlineMath(ast::InlineMath { value, position })
             ]
         }
         mdast::Node::Delete(node) => {
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             let position = node.position.clone().map(Into::into);
             vec![
                 ast::Node::Strikethrough(ast::Strikethrough { children, position })
             ]
         }
         mdast::Node::Emphasis(node) => {
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             let position = node.position.clone().map(Into::into);
             vec![
-                ast::Node::Strikethrough(ast::Strikethrough { children, position })
+                ast::Node::Emphasis(ast::Emphasis { children, position })
             ]
         }
         mdast::Node::FootnoteReference(node) => {
@@ -165,6 +309,7 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
                     position,
                     identifier,
                     label,
+                    number: None,
                 })
             ]
         }
@@ -185,7 +330,7 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
         mdast::Node::ImageReference(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
             let alt = node.alt.clone();
-            let reference_kind = node.reference_kind.clone().into();
+            let reference_kind = node.reference_kind.into();
             let identifier = node.identifier.clone();
             let label = node.label.clone();
             vec![
@@ -195,12 +340,13 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
                     reference_kind,
                     identifier,
                     label,
+                    url: None,
+                    title: None,
                 })
             ]
         }
         mdast::Node::Link(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             let url = node.url.clone();
             let title = node.title.clone();
             vec![
@@ -214,7 +360,6 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
         }
         mdast::Node::LinkReference(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             let reference_kind = node.reference_kind.into();
             let identifier = node.identifier.clone();
             let label = node.label.clone();
@@ -225,12 +370,13 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
                     reference_kind,
                     identifier,
                     label,
+                    url: None,
+                    title: None,
                 })
             ]
         }
         mdast::Node::Strong(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             vec![
                 ast::Node::Strong(ast::Strong { position, children })
             ]
@@ -256,6 +402,7 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
                     value,
                     lang,
                     meta,
+                    highlights: None,
                 })
             ]
         }
@@ -273,7 +420,6 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
         }
         mdast::Node::Heading(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             let level = match node.depth {
                 1 => crate::data::HeadingLevel::H1,
                 2 => crate::data::HeadingLevel::H2,
@@ -293,7 +439,6 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
         }
         mdast::Node::Table(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             let alignment = node.align.clone().into_iter().map(Into::into).collect_vec();
             vec![
                 ast::Node::Table(ast::Table {
@@ -311,7 +456,6 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
         }
         mdast::Node::TableRow(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             vec![
                 ast::Node::TableRow(ast::TableRow {
                     position,
@@ -321,7 +465,6 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
         }
         mdast::Node::TableCell(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             vec![
                 ast::Node::TableCell(ast::TableCell {
                     position,
@@ -331,9 +474,8 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
         }
         mdast::Node::ListItem(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
-            let spread = node.spread.clone();
-            let checked = node.checked.clone();
+            let spread = node.spread;
+            let checked = node.checked;
             vec![
                 ast::Node::ListItem(ast::ListItem { children, position, spread, checked })
             ]
@@ -356,7 +498,6 @@ fn convert_node(node: &mdast::Node) -> Vec<ast::Node> {
         }
         mdast::Node::Paragraph(node) => {
             let position = node.position.clone().map(Into::<ast::SourceRange>::into);
-            let children = node.children.iter().flat_map(convert_node).collect_vec();
             vec![
                 ast::Node::Paragraph(ast::Paragraph { position, children })
             ]
@@ -383,6 +524,8 @@ impl From<::markdown::unist::Point> for ast::Point {
             line,
             column,
             offset,
+            utf16_offset: None,
+            scalar_offset: None,
         }
     }
 }
@@ -404,4 +547,43 @@ impl From<::markdown::mdast::AlignKind> for crate::data::AlignKind {
             ::markdown::mdast::AlignKind::None => crate::data::AlignKind::None,
         }
     }
+}
+impl From<&ParseConfig> for ::markdown::ParseOptions {
+    fn from(config: &ParseConfig) -> Self {
+        ::markdown::ParseOptions {
+            constructs: ::markdown::Constructs {
+                attention: config.attention,
+                autolink: config.autolink,
+                block_quote: config.block_quote,
+                character_escape: config.character_escape,
+                character_reference: config.character_reference,
+                code_indented: config.code_indented,
+                code_fenced: config.code_fenced,
+                code_text: config.code_text,
+                definition: config.definition,
+                frontmatter: config.frontmatter,
+                gfm_autolink_literal: config.gfm_autolink_literal,
+                gfm_footnote_definition: config.gfm_footnote_definition,
+                gfm_label_start_footnote: config.gfm_label_start_footnote,
+                gfm_strikethrough: config.gfm_strikethrough,
+                gfm_table: config.gfm_table,
+                gfm_task_list_item: config.gfm_task_list_item,
+                hard_break_escape: config.hard_break_escape,
+                hard_break_trailing: config.hard_break_trailing,
+                heading_atx: config.heading_atx,
+                heading_setext: config.heading_setext,
+                html_flow: config.html_flow,
+                html_text: config.html_text,
+                label_start_image: config.label_start_image,
+                label_start_link: config.label_start_link,
+                label_end: config.label_end,
+                list_item: config.list_item,
+                math_flow: config.math_flow,
+                math_text: config.math_text,
+                thematic_break: config.thematic_break,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,230 @@
+//! Plain text extraction with a mapping back to the source.
+//!
+//! ```
+//! use markdown_format::plain_text::{to_plain_text, PlainTextOptions};
+//! let source = "# Hello *world*\n\nSee `code`.\n";
+//! let nodes = markdown_format::parse(source).unwrap();
+//! let plain = to_plain_text(&nodes, &PlainTextOptions::default());
+//! assert_eq!(plain.text, "Hello world\n\nSee code.");
+//!
+//! let hit = plain.text.find("world").unwrap();
+//! let range = plain.source_range(hit..hit + 5).unwrap();
+//! assert_eq!(&source[range.start.offset..range.end.offset], "world");
+//! ```
+use std::ops::Range;
+use crate::{Node, Point, SourceRange};
+use crate::text::line_endings;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// What to do with content that may not read well as plain text.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Handling {
+    /// Its text: code and math as is, the alt text of images, table cells
+    /// separated by tabs and rows by line endings.
+    #[default]
+    Include,
+    /// Nothing.
+    Omit,
+    /// The given text instead, for example `"image"` for screen readers.
+    Replace(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PlainTextOptions {
+    pub code_blocks: Handling,
+    pub inline_code: Handling,
+    /// Both display and inline math.
+    pub math: Handling,
+    /// Images, image references and embeds.
+    pub images: Handling,
+    pub tables: Handling,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct PlainText {
+    pub text: String,
+    /// Ordered by `range`, separators between blocks are not mapped.
+    pub mappings: Vec<Mapping>,
+}
+
+/// A range of the plain text produced by a single node.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Mapping {
+    /// Byte range in [`PlainText::text`].
+    pub range: Range<usize>,
+    /// Of the node, which for text whose source differs from its value (such
+    /// as escapes or code spans) covers more than the text itself.
+    pub source: SourceRange,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The readable text of the document: blocks are separated by blank lines,
+/// list items by line endings. Frontmatter, definitions, HTML and footnote
+/// references are left out.
+pub fn to_plain_text(nodes: &[Node], options: &PlainTextOptions) -> PlainText {
+    let mut writer = Writer {
+        options,
+        output: PlainText { text: String::new(), mappings: Vec::new() },
+        separator: "",
+    };
+    writer.blocks(nodes, "\n\n");
+    writer.output
+}
+
+impl PlainText {
+    /// The source of a byte range of the text, `None` if it covers no mapped
+    /// text.
+    ///
+    /// Exact where the text is a verbatim copy of the source, otherwise it
+    /// widens to the nodes the range touches.
+    pub fn source_range(&self, range: Range<usize>) -> Option<SourceRange> {
+        let first = self.mappings.iter().position(|x| x.range.end > range.start)?;
+        let overlapping = self.mappings[first..]
+            .iter()
+            .take_while(|x| x.range.start < range.end.max(range.start + 1));
+        let last = overlapping.clone().last()?;
+        let first = overlapping.clone().next()?;
+        let start = match self.is_verbatim(first) {
+            true => self.point(first, range.start.max(first.range.start)),
+            false => first.source.start.clone(),
+        };
+        let end = match self.is_verbatim(last) {
+            true => self.point(last, range.end.min(last.range.end)),
+            false => last.source.end.clone(),
+        };
+        Some(SourceRange { start, end })
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+struct Writer<'a> {
+    options: &'a PlainTextOptions,
+    output: PlainText,
+    /// Written before the next text, the longest one requested wins.
+    separator: &'static str,
+}
+
+impl Writer<'_> {
+    fn blocks(&mut self, nodes: &[Node], separator: &'static str) {
+        for node in nodes {
+            self.separate(separator);
+            self.block(node);
+        }
+    }
+    fn block(&mut self, node: &Node) {
+        let options = self.options;
+        match node {
+            Node::Paragraph(x) => self.inlines(&x.children),
+            Node::Heading(x) => self.inlines(&x.children),
+            Node::Blockquote(x) => self.blocks(&x.children, "\n\n"),
+            Node::FootnoteDefinition(x) => self.blocks(&x.children, "\n\n"),
+            Node::Admonition(x) => {
+                if let Some(title) = x.title.as_ref() {
+                    self.push(title, &x.position);
+                }
+                self.blocks(&x.children, "\n\n");
+            }
+            Node::List(x) => self.blocks(&x.children, "\n"),
+            Node::ListItem(x) => self.blocks(&x.children, if x.spread { "\n\n" } else { "\n" }),
+            Node::DefinitionList(x) => self.blocks(&x.children, if x.spread { "\n\n" } else { "\n" }),
+            Node::DefinitionTerm(x) => self.inlines(&x.children),
+            Node::DefinitionDescription(x) => self.blocks(&x.children, "\n"),
+            Node::CodeBlock(x) => self.handle(&options.code_blocks, &x.value, &x.position),
+            Node::DisplayMath(x) => self.handle(&options.math, &x.value, &x.position),
+            Node::Table(x) => match &options.tables {
+                Handling::Include => {
+                    for row in &x.children {
+                        self.separate("\n");
+                        for cell in row.children() {
+                            self.separate("\t");
+                            self.inlines(cell.children());
+                        }
+                    }
+                }
+                handling => self.handle(handling, "", &x.position),
+            },
+            node => self.inline(node),
+        }
+    }
+    fn inlines(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.inline(node);
+        }
+    }
+    fn inline(&mut self, node: &Node) {
+        let options = self.options;
+        match node {
+            Node::Text(x) => self.push(&x.value, &x.position),
+            Node::Newline(x) => self.push("\n", &x.position),
+            Node::InlineCode(x) => self.handle(&options.inline_code, &x.value, &x.position),
+            Node::InlineMath(x) => self.handle(&options.math, &x.value, &x.position),
+            Node::Image(x) => self.handle(&options.images, &x.alt, &x.position),
+            Node::ImageReference(x) => self.handle(&options.images, &x.alt, &x.position),
+            Node::Embed(x) => self.handle(&options.images, &x.alt(), &x.position),
+            Node::WikiLink(x) => self.push(&x.text(), &x.position),
+            Node::Emphasis(x) => self.inlines(&x.children),
+            Node::Strong(x) => self.inlines(&x.children),
+            Node::Strikethrough(x) => self.inlines(&x.children),
+            Node::Link(x) => self.inlines(&x.children),
+            Node::LinkReference(x) => self.inlines(&x.children),
+            _ => (),
+        }
+    }
+    fn handle(&mut self, handling: &Handling, value: &str, position: &Option<SourceRange>) {
+        match handling {
+            Handling::Include => self.push(value, position),
+            Handling::Omit => (),
+            Handling::Replace(value) => self.push(value, position),
+        }
+    }
+    fn separate(&mut self, separator: &'static str) {
+        if separator.len() > self.separator.len() {
+            self.separator = separator;
+        }
+    }
+    fn push(&mut self, value: &str, position: &Option<SourceRange>) {
+        if value.is_empty() {
+            return
+        }
+        let text = &mut self.output.text;
+        if !text.is_empty() {
+            text.push_str(self.separator);
+        }
+        self.separator = "";
+        let start = text.len();
+        text.push_str(value);
+        if let Some(source) = position.clone() {
+            self.output.mappings.push(Mapping { range: start..text.len(), source });
+        }
+    }
+}
+
+impl PlainText {
+    /// Whether the source of the mapping is exactly its text.
+    fn is_verbatim(&self, mapping: &Mapping) -> bool {
+        let source = mapping.source.end.offset.saturating_sub(mapping.source.start.offset);
+        source == mapping.range.len()
+    }
+    /// The source point of a text index within a verbatim mapping.
+    fn point(&self, mapping: &Mapping, index: usize) -> Point {
+        let before = &self.text[mapping.range.start..index];
+        let start = &mapping.source.start;
+        let line_start = before.rfind(['\n', '\r']).map(|x| x + 1);
+        Point {
+            line: start.line + line_endings(before),
+            column: match line_start {
+                Some(line_start) => before.len() - line_start + 1,
+                None => start.column + before.len(),
+            },
+            offset: start.offset + before.len(),
+            utf16_offset: start.utf16_offset.map(|x| x + before.encode_utf16().count()),
+            scalar_offset: start.scalar_offset.map(|x| x + before.chars().count()),
+        }
+    }
+}
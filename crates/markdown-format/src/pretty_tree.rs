@@ -0,0 +1,93 @@
+//! Labelled trees for debug output of the AST, as printed by `mdfmt parse
+//! --format tree` and stored in the golden snapshots.
+//!
+//! ```
+//! use markdown_format::pretty_tree::ToPrettyTree;
+//! let nodes = markdown_format::parse("*a*").unwrap();
+//! let tree = markdown_format::pretty_tree::branch_of("nodes", &nodes);
+//! assert!(tree.to_string().starts_with("nodes\n  Paragraph\n"));
+//! ```
+use std::fmt::{self, Display, Formatter};
+
+/// Printed one label per line, indented by two spaces per level.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PrettyTree {
+    Value(String),
+    Branch(String, Vec<PrettyTree>),
+}
+
+pub trait ToPrettyTree {
+    fn to_pretty_tree(&self) -> PrettyTree;
+}
+
+impl PrettyTree {
+    pub fn branch_of<T: ToPrettyTree>(label: impl ToString, children: impl IntoIterator<Item = T>) -> PrettyTree {
+        PrettyTree::Branch(label.to_string(), children.into_iter().map(|x| x.to_pretty_tree()).collect())
+    }
+}
+
+pub fn value(value: impl ToString) -> PrettyTree {
+    PrettyTree::Value(value.to_string())
+}
+
+pub fn branch_of<T: ToPrettyTree>(label: impl ToString, children: impl IntoIterator<Item = T>) -> PrettyTree {
+    PrettyTree::branch_of(label, children)
+}
+
+pub fn field<T: ToPrettyTree + ?Sized>(label: impl ToString, value: &T) -> PrettyTree {
+    PrettyTree::Branch(label.to_string(), vec![value.to_pretty_tree()])
+}
+
+impl Display for PrettyTree {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut stack = vec![(0, self)];
+        while let Some((depth, tree)) = stack.pop() {
+            let (label, children) = match tree {
+                PrettyTree::Value(value) => (value, &[][..]),
+                PrettyTree::Branch(label, children) => (label, &children[..]),
+            };
+            writeln!(f, "{:indent$}{label}", "", indent = 2 * depth)?;
+            stack.extend(children.iter().rev().map(|x| (depth + 1, x)));
+        }
+        Ok(())
+    }
+}
+
+impl ToPrettyTree for PrettyTree {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        self.clone()
+    }
+}
+
+impl<T: ToPrettyTree + ?Sized> ToPrettyTree for &T {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        (**self).to_pretty_tree()
+    }
+}
+
+impl<T: ToPrettyTree> ToPrettyTree for Option<T> {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        match self {
+            Some(x) => x.to_pretty_tree(),
+            None => value("None"),
+        }
+    }
+}
+
+impl<T: ToPrettyTree> ToPrettyTree for Vec<T> {
+    fn to_pretty_tree(&self) -> PrettyTree {
+        branch_of("Vec", self)
+    }
+}
+
+macro_rules! debug_value {
+    ($($type:ty),*) => {
+        $(impl ToPrettyTree for $type {
+            fn to_pretty_tree(&self) -> PrettyTree {
+                value(format!("{self:?}"))
+            }
+        })*
+    };
+}
+
+debug_value!(String, str, bool, char, u8, u16, u32, u64, usize, i64, f64);
@@ -0,0 +1,636 @@
+//! Markdown printer.
+//!
+//! Prints the AST back to normalized CommonMark (plus the GFM extensions),
+//! such that parsing the output yields the same AST modulo positions.
+use crate::data::*;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// OPTIONS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrinterOptions {
+    /// Marker of unordered list items.
+    pub bullet: Bullet,
+    /// Marker of emphasis, strong uses it twice.
+    pub emphasis: EmphasisMarker,
+    /// Fence of code blocks.
+    pub fence: Fence,
+    /// Pads table cells so the columns line up.
+    pub table_padding: bool,
+}
+
+impl Default for PrinterOptions {
+    fn default() -> Self {
+        Self {
+            bullet: Bullet::Dash,
+            emphasis: EmphasisMarker::Asterisk,
+            fence: Fence::Backtick,
+            table_padding: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bullet {
+    /// `-`
+    Dash,
+    /// `*`
+    Asterisk,
+    /// `+`
+    Plus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmphasisMarker {
+    /// `*a*` and `**a**`.
+    Asterisk,
+    /// `_a_` and `__a__`.
+    Underscore,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fence {
+    /// ```` ``` ````
+    Backtick,
+    /// `~~~`
+    Tilde,
+}
+
+impl Bullet {
+    fn char(self) -> char {
+        match self {
+            Self::Dash => '-',
+            Self::Asterisk => '*',
+            Self::Plus => '+',
+        }
+    }
+    /// Used for a list that directly follows another unordered list, which
+    /// would otherwise be merged into it.
+    fn alternate(self) -> Self {
+        match self {
+            Self::Dash => Self::Asterisk,
+            Self::Asterisk | Self::Plus => Self::Dash,
+        }
+    }
+}
+
+impl EmphasisMarker {
+    fn char(self) -> char {
+        match self {
+            Self::Asterisk => '*',
+            Self::Underscore => '_',
+        }
+    }
+    fn alternate(self) -> Self {
+        match self {
+            Self::Asterisk => Self::Underscore,
+            Self::Underscore => Self::Asterisk,
+        }
+    }
+}
+
+impl Fence {
+    fn char(self) -> char {
+        match self {
+            Self::Backtick => '`',
+            Self::Tilde => '~',
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Prints a document as Markdown.
+pub fn print(nodes: &[Node], options: &PrinterOptions) -> String {
+    let printer = Printer { options };
+    let mut output = printer.blocks(nodes, false);
+    if !output.is_empty() {
+        output.push('\n');
+    }
+    output
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// BLOCKS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+struct Printer<'a> {
+    options: &'a PrinterOptions,
+}
+
+impl<'a> Printer<'a> {
+    /// Blocks are separated by a blank line, or a single line break in tight
+    /// list items.
+    fn blocks(&self, nodes: &[Node], tight: bool) -> String {
+        let mut output = String::default();
+        let mut previous: Option<&Node> = None;
+        for node in nodes {
+            let block = self.block(node, previous);
+            if previous.is_some() {
+                output.push_str(if tight { "\n" } else { "\n\n" });
+            }
+            output.push_str(&block);
+            previous = Some(node);
+        }
+        output
+    }
+    fn block(&self, node: &Node, previous: Option<&Node>) -> String {
+        match node {
+            Node::Paragraph(Paragraph { children, .. }) => self.inlines(children, Context::Paragraph),
+            Node::Heading(Heading { level, children, .. }) => {
+                let level = match level {
+                    HeadingLevel::H1 => 1,
+                    HeadingLevel::H2 => 2,
+                    HeadingLevel::H3 => 3,
+                    HeadingLevel::H4 => 4,
+                    HeadingLevel::H5 => 5,
+                    HeadingLevel::H6 => 6,
+                };
+                let content = self.inlines(children, Context::Heading);
+                match level {
+                    _ if content.is_empty() => "#".repeat(level),
+                    // ATX headings end at the line break, setext ones can span lines.
+                    1 if content.contains('\n') => format!("{content}\n==="),
+                    2 if content.contains('\n') => format!("{content}\n---"),
+                    _ => format!("{} {}", "#".repeat(level), content.replace('\n', " ")),
+                }
+            }
+            Node::Blockquote(Blockquote { children, .. }) => {
+                let content = self.blocks(children, false);
+                prefix_lines(&content, "> ", "> ")
+            }
+            Node::Admonition(admonition) => self.admonition(admonition),
+            Node::List(list) => {
+                let follows_list = matches!(previous, Some(Node::List(x)) if x.ordered == list.ordered);
+                self.list(list, follows_list)
+            }
+            Node::ListItem(item) => self.list_item(item, &self.options.bullet.char().to_string()),
+            Node::DefinitionList(list) => self.definition_list(list),
+            Node::DefinitionTerm(DefinitionTerm { children, .. }) => self.inlines(children, Context::Paragraph),
+            Node::DefinitionDescription(description) => self.definition_description(description),
+            Node::CodeBlock(CodeBlock { value, lang, meta, .. }) => {
+                let info = match (lang, meta) {
+                    (Some(lang), Some(meta)) => format!("{lang} {meta}"),
+                    (Some(lang), None) => lang.clone(),
+                    (None, _) => String::default(),
+                };
+                // Backtick fences can't have backticks in their info string.
+                let fence = if info.contains('`') { Fence::Tilde } else { self.options.fence };
+                fenced(value, fence.char(), 3, &info)
+            }
+            Node::DisplayMath(DisplayMath { value, meta, .. }) => {
+                fenced(value, '$', 2, meta.as_deref().unwrap_or_default())
+            }
+            Node::HorizontalDivider(_) => String::from("***"),
+            Node::Table(table) => self.table(table),
+            Node::Definition(Definition { url, title, identifier, label, .. }) => {
+                let label = label.as_deref().unwrap_or(identifier);
+                let mut output = format!("[{}]: {}", escape_label(label), destination(url));
+                if let Some(title) = title {
+                    output.push_str(&format!(" {}", link_title(title)));
+                }
+                output
+            }
+            Node::FootnoteDefinition(FootnoteDefinition { children, identifier, label, .. }) => {
+                let label = label.as_deref().unwrap_or(identifier);
+                let content = self.blocks(children, false);
+                let marker = format!("[^{}]: ", escape_label(label));
+                prefix_lines(&content, &marker, "    ")
+            }
+            Node::Html(Html { value, .. }) => value.clone(),
+            Node::Toml(Toml { value, .. }) => format!("+++\n{value}\n+++"),
+            Node::Yaml(Yaml { value, .. }) => format!("---\n{value}\n---"),
+            node => self.inline(node, Context::Paragraph, ""),
+        }
+    }
+    /// As a callout where the kind allows, as a `:::` container otherwise.
+    fn admonition(&self, Admonition { kind, title, children, .. }: &Admonition) -> String {
+        let content = self.blocks(children, false);
+        let title = title.as_ref().map(|x| format!(" {x}")).unwrap_or_default();
+        if kind.chars().all(|x| x.is_ascii_alphabetic()) {
+            let marker = format!("[!{}]{title}", kind.to_ascii_uppercase());
+            let content = match content.is_empty() {
+                true => marker,
+                false => format!("{marker}\n{content}"),
+            };
+            return prefix_lines(&content, "> ", "> ")
+        }
+        // Outer fences are longer than the ones they contain.
+        let fence = ":".repeat(3 + admonition_depth(children));
+        match content.is_empty() {
+            true => format!("{fence}{kind}{title}\n{fence}"),
+            false => format!("{fence}{kind}{title}\n\n{content}\n\n{fence}"),
+        }
+    }
+    fn list(&self, list: &List, follows_list: bool) -> String {
+        let bullet = if follows_list { self.options.bullet.alternate() } else { self.options.bullet };
+        let delimiter = if follows_list { ')' } else { '.' };
+        let start = list.start.unwrap_or(1);
+        let items = list.children
+            .iter()
+            .enumerate()
+            .map(|(index, child)| {
+                let marker = if list.ordered {
+                    format!("{}{delimiter}", start as usize + index)
+                } else {
+                    bullet.char().to_string()
+                };
+                match child {
+                    Node::ListItem(item) => self.list_item(item, &marker),
+                    node => self.block(node, None),
+                }
+            })
+            .collect::<Vec<_>>();
+        items.join(if list.spread { "\n\n" } else { "\n" })
+    }
+    fn list_item(&self, item: &ListItem, marker: &str) -> String {
+        let mut content = self.blocks(&item.children, !item.spread);
+        match item.checked {
+            Some(true) => content.insert_str(0, "[x] "),
+            Some(false) => content.insert_str(0, "[ ] "),
+            None => {}
+        }
+        if content.is_empty() {
+            return marker.to_owned()
+        }
+        let indent = " ".repeat(marker.len() + 1);
+        prefix_lines(&content, &format!("{marker} "), &indent)
+    }
+    /// Terms on lines of their own, a blank line before each further term.
+    fn definition_list(&self, list: &DefinitionList) -> String {
+        let mut output = String::default();
+        let mut previous: Option<&Node> = None;
+        for child in list.children.iter() {
+            let separator = match (previous, child) {
+                (None, _) => "",
+                (Some(Node::DefinitionTerm(_)), Node::DefinitionTerm(_)) => "\n",
+                (Some(_), Node::DefinitionTerm(_)) => "\n\n",
+                _ if list.spread => "\n\n",
+                _ => "\n",
+            };
+            output.push_str(separator);
+            output.push_str(&self.block(child, None));
+            previous = Some(child);
+        }
+        output
+    }
+    fn definition_description(&self, description: &DefinitionDescription) -> String {
+        let content = self.blocks(&description.children, false);
+        if content.is_empty() {
+            return String::from(":")
+        }
+        prefix_lines(&content, ": ", "    ")
+    }
+    fn table(&self, table: &Table) -> String {
+        let rows = table.children
+            .iter()
+            .map(|row| {
+                row.children()
+                    .iter()
+                    .map(|cell| self.inlines(cell.children(), Context::TableCell))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let columns = table.alignment.len().max(rows.iter().map(Vec::len).max().unwrap_or(0));
+        let widths = (0..columns)
+            .map(|column| {
+                let content = rows
+                    .iter()
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| cell.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                if self.options.table_padding { content.max(3) } else { 0 }
+            })
+            .collect::<Vec<_>>();
+        let format_row = |cells: Vec<String>| {
+            let cells = (0..columns)
+                .map(|column| {
+                    let cell = cells.get(column).map(String::as_str).unwrap_or_default();
+                    let padding = widths[column].saturating_sub(cell.chars().count());
+                    format!("{cell}{}", " ".repeat(padding))
+                })
+                .collect::<Vec<_>>();
+            format!("| {} |", cells.join(" | "))
+        };
+        let delimiter = (0..columns)
+            .map(|column| {
+                let width = widths[column].max(3);
+                match table.alignment.get(column) {
+                    Some(AlignKind::Left) => format!(":{}", "-".repeat(width - 1)),
+                    Some(AlignKind::Right) => format!("{}:", "-".repeat(width - 1)),
+                    Some(AlignKind::Center) => format!(":{}:", "-".repeat(width - 2)),
+                    Some(AlignKind::None) | None => "-".repeat(width),
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut lines = Vec::with_capacity(rows.len() + 1);
+        let mut rows = rows.into_iter();
+        lines.push(format_row(rows.next().unwrap_or_default()));
+        lines.push(format!("| {} |", delimiter.join(" | ")));
+        lines.extend(rows.map(format_row));
+        lines.join("\n")
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// INLINES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    Paragraph,
+    Heading,
+    TableCell,
+    /// Content of a link, where GFM autolink literals must not form.
+    Link,
+}
+
+impl<'a> Printer<'a> {
+    fn inlines(&self, nodes: &[Node], context: Context) -> String {
+        let mut output = String::default();
+        self.inlines_into(nodes, context, &mut output);
+        if context == Context::Heading && output.ends_with('#') && !output.ends_with("\\#") {
+            // Would be taken for a closing sequence.
+            output.insert(output.len() - 1, '\\');
+        }
+        output
+    }
+    fn inlines_into(&self, nodes: &[Node], context: Context, output: &mut String) {
+        for node in nodes {
+            let printed = self.inline(node, context, output);
+            output.push_str(&printed);
+        }
+    }
+    /// `output` is the content printed so far on the current line, needed to
+    /// decide what must be escaped.
+    fn inline(&self, node: &Node, context: Context, output: &str) -> String {
+        match node {
+            Node::Text(Text { value, .. }) => escape_text(value, output, context),
+            Node::Newline(_) => String::from("\\\n"),
+            Node::Emphasis(Emphasis { children, .. }) => {
+                let marker = self.emphasis_marker(output, children);
+                let content = self.nested(children, context);
+                format!("{marker}{content}{marker}")
+            }
+            Node::Strong(Strong { children, .. }) => {
+                let marker = self.emphasis_marker(output, children).to_string().repeat(2);
+                let content = self.nested(children, context);
+                format!("{marker}{content}{marker}")
+            }
+            Node::Strikethrough(Strikethrough { children, .. }) => {
+                let content = self.nested(children, context);
+                format!("~~{content}~~")
+            }
+            // The table cell delimiter still applies inside code.
+            Node::InlineCode(InlineCode { value, .. }) if context == Context::TableCell => {
+                code_span(&value.replace('|', "\\|"), '`')
+            }
+            Node::InlineCode(InlineCode { value, .. }) => code_span(value, '`'),
+            Node::InlineMath(InlineMath { value, .. }) => code_span(value, '$'),
+            Node::Link(Link { children, url, title, .. }) => {
+                let content = self.nested(children, Context::Link);
+                let mut output = format!("[{content}]({}", destination(url));
+                if let Some(title) = title {
+                    output.push_str(&format!(" {}", link_title(title)));
+                }
+                output.push(')');
+                output
+            }
+            Node::LinkReference(LinkReference { children, reference_kind, identifier, label, .. }) => {
+                let content = self.nested(children, context);
+                let label = escape_label(label.as_deref().unwrap_or(identifier));
+                match reference_kind {
+                    ReferenceKind::Full => format!("[{content}][{label}]"),
+                    ReferenceKind::Collapsed => format!("[{content}][]"),
+                    ReferenceKind::Shortcut => format!("[{content}]"),
+                }
+            }
+            Node::Image(Image { alt, url, title, .. }) => {
+                let mut output = format!("![{}]({}", escape_text(alt, "", Context::Paragraph), destination(url));
+                if let Some(title) = title {
+                    output.push_str(&format!(" {}", link_title(title)));
+                }
+                output.push(')');
+                output
+            }
+            Node::ImageReference(ImageReference { alt, reference_kind, identifier, label, .. }) => {
+                let alt = escape_text(alt, "", Context::Paragraph);
+                let label = escape_label(label.as_deref().unwrap_or(identifier));
+                match reference_kind {
+                    ReferenceKind::Full => format!("![{alt}][{label}]"),
+                    ReferenceKind::Collapsed => format!("![{alt}][]"),
+                    ReferenceKind::Shortcut => format!("![{alt}]"),
+                }
+            }
+            Node::WikiLink(WikiLink { target, alias, heading_anchor, .. }) => {
+                wikilink(target, heading_anchor.as_deref(), alias.as_deref(), context)
+            }
+            Node::Embed(Embed { target, alias, heading_anchor, .. }) => {
+                format!("!{}", wikilink(target, heading_anchor.as_deref(), alias.as_deref(), context))
+            }
+            Node::FootnoteReference(FootnoteReference { identifier, label, .. }) => {
+                format!("[^{}]", escape_label(label.as_deref().unwrap_or(identifier)))
+            }
+            Node::Html(Html { value, .. }) => value.clone(),
+            // Block nodes in inline position have no Markdown representation.
+            node => self.block(node, None),
+        }
+    }
+    fn nested(&self, children: &[Node], context: Context) -> String {
+        let mut output = String::default();
+        // Not at the start of a line.
+        output.push(' ');
+        self.inlines_into(children, context, &mut output);
+        output.remove(0);
+        output
+    }
+    /// Switches to the alternate marker when the content starts with emphasis
+    /// itself (`***a***` would be ambiguous) or the marker would be glued to the
+    /// preceding emphasis run.
+    fn emphasis_marker(&self, output: &str, children: &[Node]) -> char {
+        let marker = self.options.emphasis;
+        let starts_with_emphasis = matches!(children.first(), Some(Node::Emphasis(_) | Node::Strong(_)))
+            || matches!(children.last(), Some(Node::Emphasis(_) | Node::Strong(_)));
+        let glued = output.ends_with(marker.char());
+        let marker = if starts_with_emphasis || glued { marker.alternate() } else { marker };
+        // Intraword underscores are not emphasis.
+        let intraword = output.chars().last().is_some_and(char::is_alphanumeric);
+        if marker == EmphasisMarker::Underscore && intraword {
+            return EmphasisMarker::Asterisk.char()
+        }
+        marker.char()
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// HELPERS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+fn admonition_depth(nodes: &[Node]) -> usize {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Admonition(x) => 1 + admonition_depth(&x.children),
+            node => admonition_depth(node.children()),
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// Prefixes the first line with `first` and the following ones with `rest`,
+/// blank lines only get the non-whitespace part of the prefix.
+fn prefix_lines(content: &str, first: &str, rest: &str) -> String {
+    content
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| {
+            let prefix = if index == 0 { first } else { rest };
+            if line.is_empty() {
+                prefix.trim_end().to_owned()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn fenced(value: &str, fence_char: char, min_length: usize, info: &str) -> String {
+    let longest_run = value
+        .lines()
+        .map(|line| line.trim_start().chars().take_while(|x| *x == fence_char).count())
+        .max()
+        .unwrap_or(0);
+    let fence = fence_char.to_string().repeat(min_length.max(longest_run + 1));
+    if value.is_empty() {
+        format!("{fence}{info}\n{fence}")
+    } else {
+        format!("{fence}{info}\n{value}\n{fence}")
+    }
+}
+
+fn code_span(value: &str, fence_char: char) -> String {
+    let mut longest_run = 0;
+    let mut run = 0;
+    for char in value.chars() {
+        run = if char == fence_char { run + 1 } else { 0 };
+        longest_run = longest_run.max(run);
+    }
+    let fence = fence_char.to_string().repeat(longest_run + 1);
+    let padded = value.starts_with(fence_char)
+        || value.ends_with(fence_char)
+        || (value.starts_with(' ') && value.ends_with(' ') && !value.trim().is_empty());
+    if padded {
+        format!("{fence} {value} {fence}")
+    } else {
+        format!("{fence}{value}{fence}")
+    }
+}
+
+fn destination(url: &str) -> String {
+    let needs_brackets = url.is_empty() || url.chars().any(|x| x.is_whitespace() || x.is_control());
+    if needs_brackets {
+        let url = url.replace('\\', "\\\\").replace('<', "\\<").replace('>', "\\>");
+        return format!("<{url}>")
+    }
+    let mut output = String::with_capacity(url.len());
+    let chars = url.chars().collect::<Vec<_>>();
+    for (index, char) in chars.iter().copied().enumerate() {
+        let next = chars.get(index + 1).copied();
+        let escape = match char {
+            '\\' | '(' | ')' | '<' => true,
+            '&' => next.is_some_and(|x| x.is_alphanumeric() || x == '#'),
+            _ => false,
+        };
+        if escape {
+            output.push('\\');
+        }
+        output.push(char);
+    }
+    output
+}
+
+fn link_title(title: &str) -> String {
+    format!("\"{}\"", title.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn wikilink(target: &str, heading_anchor: Option<&str>, alias: Option<&str>, context: Context) -> String {
+    let mut output = format!("[[{target}");
+    if let Some(heading_anchor) = heading_anchor {
+        output.push_str(&format!("#{heading_anchor}"));
+    }
+    if let Some(alias) = alias {
+        // The table cell delimiter still applies inside wiki links.
+        output.push_str(if context == Context::TableCell { "\\|" } else { "|" });
+        output.push_str(alias);
+    }
+    output.push_str("]]");
+    output
+}
+
+fn escape_label(label: &str) -> String {
+    let mut output = String::with_capacity(label.len());
+    for char in label.chars() {
+        if matches!(char, '\\' | '[' | ']') {
+            output.push('\\');
+        }
+        output.push(char);
+    }
+    output
+}
+
+/// Escapes everything in text that could be taken for markup. `preceding` is
+/// what was printed before on the same block.
+fn escape_text(value: &str, preceding: &str, context: Context) -> String {
+    let chars = value.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(value.len());
+    let mut line_start = preceding.is_empty() || preceding.ends_with('\n');
+    for (index, char) in chars.iter().copied().enumerate() {
+        let previous = if index == 0 { preceding.chars().last() } else { Some(chars[index - 1]) };
+        let next = chars.get(index + 1).copied();
+        let escape = match char {
+            '\\' | '*' | '[' | ']' | '`' | '<' | '~' | '$' | '|' => true,
+            '_' => {
+                let intraword = previous.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric);
+                !intraword
+            }
+            '&' => next.is_some_and(|x| x.is_alphanumeric() || x == '#'),
+            '!' => next == Some('['),
+            // Would complete a shortcut reference into a link or definition.
+            '(' => previous == Some(']'),
+            '#' | '>' | '+' | '-' | '=' => line_start,
+            // Autolink literals: `https://`, `www.` and emails, or the
+            // marker of a definition description.
+            ':' => {
+                previous == Some(']')
+                    || line_start && matches!(next, Some(' ' | '\t'))
+                    || context == Context::Link && chars[index + 1..].starts_with(&['/', '/'])
+            }
+            '.' if context == Context::Link && index >= 3 && chars[index - 3..index].iter().all(|x| x.eq_ignore_ascii_case(&'w')) => true,
+            '@' => context == Context::Link,
+            '.' | ')' => {
+                // Ordered list item markers: digits at the start of a line.
+                let digits = chars[..index]
+                    .iter()
+                    .rev()
+                    .take_while(|x| x.is_ascii_digit())
+                    .count();
+                let starts_line = index == digits && (preceding.is_empty() || preceding.ends_with('\n'))
+                    || index > digits && chars[index - digits - 1] == '\n';
+                digits > 0 && starts_line
+            }
+            _ => false,
+        };
+        if line_start && (char == ' ' || char == '\t') && context != Context::TableCell {
+            // Leading whitespace would be stripped (or indent code).
+            output.push_str(if char == ' ' { "&#32;" } else { "&#9;" });
+            continue
+        }
+        if escape {
+            output.push('\\');
+        }
+        output.push(char);
+        line_start = char == '\n';
+    }
+    output
+}
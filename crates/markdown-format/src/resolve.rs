@@ -0,0 +1,228 @@
+//! Resolution of references against their definitions.
+//!
+//! ```
+//! use markdown_format::Node;
+//! let mut nodes = markdown_format::parse("[a][x]\n\n[x]: /url \"Title\"\n").unwrap();
+//! let resolution = markdown_format::resolve::resolve(&mut nodes);
+//! assert!(resolution.diagnostics.is_empty());
+//! let Node::Paragraph(paragraph) = &nodes[0] else { panic!() };
+//! let Node::LinkReference(reference) = &paragraph.children[0] else { panic!() };
+//! assert_eq!(reference.url.as_deref(), Some("/url"));
+//! ```
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use crate::{Definition, Node, SourceRange};
+use crate::html::normalize_identifier;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Resolution {
+    /// Link and image definitions by normalized identifier, the first
+    /// definition of an identifier wins.
+    pub definitions: HashMap<String, Definition>,
+    /// Normalized identifiers of the referenced footnote definitions, footnote
+    /// `n` is at index `n - 1`.
+    pub footnotes: Vec<String>,
+    /// In source order.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    /// Normalized identifier.
+    pub identifier: String,
+    /// Of the offending reference or definition.
+    pub position: Option<SourceRange>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    /// A link or image reference without definition.
+    UndefinedReference,
+    /// A footnote reference without footnote definition.
+    UndefinedFootnote,
+    /// A definition no reference uses.
+    UnusedDefinition,
+    /// A footnote definition no reference uses.
+    UnusedFootnoteDefinition,
+    /// A definition shadowed by an earlier one with the same identifier.
+    DuplicateDefinition,
+    /// A footnote definition shadowed by an earlier one with the same identifier.
+    DuplicateFootnoteDefinition,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self.kind {
+            DiagnosticKind::UndefinedReference => "reference to undefined",
+            DiagnosticKind::UndefinedFootnote => "reference to undefined footnote",
+            DiagnosticKind::UnusedDefinition => "unused definition",
+            DiagnosticKind::UnusedFootnoteDefinition => "unused footnote definition",
+            DiagnosticKind::DuplicateDefinition => "duplicate definition",
+            DiagnosticKind::DuplicateFootnoteDefinition => "duplicate footnote definition",
+        };
+        write!(f, "{message} `{}`", self.identifier)?;
+        if let Some(position) = self.position.as_ref() {
+            write!(f, " at {}:{}", position.start.line, position.start.column)?;
+        }
+        Ok(())
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Sets `url`/`title` of link and image references and numbers footnote
+/// references and definitions in order of first reference.
+///
+/// References without definition get `None`, the parser only produces
+/// references to existing definitions but edited trees may not.
+pub fn resolve(nodes: &mut [Node]) -> Resolution {
+    let mut resolver = Resolver::default();
+    resolver.collect(nodes);
+    resolver.apply(nodes);
+    let Resolver { definitions, footnotes, mut used, mut diagnostics, .. } = resolver;
+    for (identifier, definition) in &definitions {
+        if !used.remove(identifier) {
+            diagnostics.push(Diagnostic {
+                kind: DiagnosticKind::UnusedDefinition,
+                identifier: identifier.clone(),
+                position: definition.position.clone(),
+            });
+        }
+    }
+    diagnostics.sort_by_key(|x| x.position.as_ref().map_or(usize::MAX, |x| x.start.offset));
+    Resolution { definitions, footnotes, diagnostics }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Default)]
+struct Resolver {
+    definitions: HashMap<String, Definition>,
+    /// Positions of the footnote definitions by normalized identifier.
+    footnote_definitions: HashMap<String, Option<SourceRange>>,
+    footnotes: Vec<String>,
+    /// Normalized identifiers of the referenced definitions.
+    used: std::collections::HashSet<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Resolver {
+    fn collect(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            match node {
+                Node::Definition(definition) => {
+                    match self.definitions.entry(normalize_identifier(&definition.identifier)) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(definition.clone());
+                        }
+                        Entry::Occupied(entry) => self.diagnostics.push(Diagnostic {
+                            kind: DiagnosticKind::DuplicateDefinition,
+                            identifier: entry.key().clone(),
+                            position: definition.position.clone(),
+                        }),
+                    }
+                }
+                Node::FootnoteDefinition(definition) => {
+                    match self.footnote_definitions.entry(normalize_identifier(&definition.identifier)) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(definition.position.clone());
+                        }
+                        Entry::Occupied(entry) => self.diagnostics.push(Diagnostic {
+                            kind: DiagnosticKind::DuplicateFootnoteDefinition,
+                            identifier: entry.key().clone(),
+                            position: definition.position.clone(),
+                        }),
+                    }
+                }
+                _ => (),
+            }
+            self.collect(node.children());
+        }
+    }
+    fn apply(&mut self, nodes: &mut [Node]) {
+        // Footnotes are numbered first so that definitions preceding their
+        // first reference get a number too.
+        self.number_footnotes(nodes);
+        self.apply_resolution(nodes);
+        let mut unused = self.footnote_definitions
+            .iter()
+            .filter(|(identifier, _)| !self.footnotes.contains(identifier))
+            .map(|(identifier, position)| (identifier.clone(), position.clone()))
+            .collect::<Vec<_>>();
+        unused.sort_by(|a, b| a.0.cmp(&b.0));
+        for (identifier, position) in unused {
+            self.report(DiagnosticKind::UnusedFootnoteDefinition, identifier, &position);
+        }
+    }
+    fn number_footnotes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            if let Node::FootnoteReference(reference) = node {
+                let identifier = normalize_identifier(&reference.identifier);
+                let is_defined = self.footnote_definitions.contains_key(&identifier);
+                if is_defined && !self.footnotes.contains(&identifier) {
+                    self.footnotes.push(identifier);
+                }
+            }
+            self.number_footnotes(node.children());
+        }
+    }
+    fn apply_resolution(&mut self, nodes: &mut [Node]) {
+        for node in nodes.iter_mut() {
+            match node {
+                Node::LinkReference(reference) => {
+                    let (url, title) = self.lookup(&reference.identifier, &reference.position);
+                    reference.url = url;
+                    reference.title = title;
+                }
+                Node::ImageReference(reference) => {
+                    let (url, title) = self.lookup(&reference.identifier, &reference.position);
+                    reference.url = url;
+                    reference.title = title;
+                }
+                Node::FootnoteReference(reference) => {
+                    reference.number = self.footnote_number(&reference.identifier);
+                    if reference.number.is_none() {
+                        let identifier = normalize_identifier(&reference.identifier);
+                        self.report(DiagnosticKind::UndefinedFootnote, identifier, &reference.position);
+                    }
+                }
+                Node::FootnoteDefinition(definition) => {
+                    definition.number = self.footnote_number(&definition.identifier);
+                }
+                _ => (),
+            }
+            if let Some(children) = node.children_mut() {
+                self.apply_resolution(children);
+            }
+        }
+    }
+    fn lookup(&mut self, identifier: &str, position: &Option<SourceRange>) -> (Option<String>, Option<String>) {
+        let identifier = normalize_identifier(identifier);
+        match self.definitions.get(&identifier) {
+            Some(definition) => {
+                let resolved = (Some(definition.url.clone()), definition.title.clone());
+                self.used.insert(identifier);
+                resolved
+            }
+            None => {
+                self.report(DiagnosticKind::UndefinedReference, identifier, position);
+                (None, None)
+            }
+        }
+    }
+    fn footnote_number(&self, identifier: &str) -> Option<usize> {
+        let identifier = normalize_identifier(identifier);
+        self.footnotes.iter().position(|x| *x == identifier).map(|x| x + 1)
+    }
+    fn report(&mut self, kind: DiagnosticKind, identifier: String, position: &Option<SourceRange>) {
+        let position = position.clone();
+        self.diagnostics.push(Diagnostic { kind, identifier, position });
+    }
+}
@@ -0,0 +1,406 @@
+//! Parsing of partially received documents, e.g. streamed chat responses.
+//!
+//! Appending text can only change the last top-level block, and the list,
+//! block quote, paragraph or other container before it while the last block
+//! starts on the unfinished last line, which may still turn into a
+//! continuation of it. With [`ParseConfig::definition_list`], a paragraph
+//! after a definition list stays open along with the list, as description
+//! lines may still turn it into terms of the list. Every block before the
+//! open ones is finalized: it
+//! keeps its [`BlockId`] and is never reparsed. The open blocks are rendered
+//! speculatively, as if unclosed constructs (code fences, math, emphasis,
+//! code spans, link destinations, table delimiter rows) were already closed.
+//!
+//! ```
+//! use markdown_format::{Node, ParseConfig};
+//! use markdown_format::stream::StreamingParser;
+//! let mut parser = StreamingParser::new(ParseConfig::default());
+//! parser.push("# Title\n\n```rust\nfn main() {").unwrap();
+//! assert!(matches!(parser.finalized(), [Node::Heading(_)]));
+//! assert!(matches!(parser.open(), [Node::CodeBlock(_)]));
+//! ```
+//!
+//! References to definitions that arrive after the block containing them
+//! was finalized only resolve in [`StreamingParser::finish`].
+use crate::{Error, Limit, LimitError, Node, ParseConfig, Point};
+use crate::incremental::{contains_definition, parse_region};
+use crate::text::{line_endings, line_start};
+use crate::parser::{check_input_size, node_count};
+use crate::offsets::Counts;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+#[derive(Debug, Clone)]
+pub struct StreamingParser {
+    config: ParseConfig,
+    source: String,
+    finalized: Vec<Node>,
+    /// Number of nodes in `finalized`, for [`crate::ParseLimits::max_nodes`].
+    finalized_nodes: usize,
+    open: Vec<Node>,
+    /// Start of the first line that isn't part of a finalized block.
+    open_start: usize,
+    open_line: usize,
+}
+
+/// Identity of a top-level block, the index it has (or will have once
+/// finalized) in [`StreamingParser::finalized`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BlockId(pub usize);
+
+#[derive(Debug, Clone, Copy)]
+pub struct StreamBlock<'a> {
+    pub id: BlockId,
+    pub node: &'a Node,
+    /// Whether the block may still change as more text arrives, open blocks
+    /// are speculative renderings.
+    pub open: bool,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl StreamingParser {
+    pub fn new(config: ParseConfig) -> Self {
+        Self {
+            config,
+            source: String::new(),
+            finalized: Vec::new(),
+            finalized_nodes: 0,
+            open: Vec::new(),
+            open_start: 0,
+            open_line: 1,
+        }
+    }
+    /// Everything received so far.
+    pub fn source(&self) -> &str { &self.source }
+    /// Blocks that won't change anymore, the index of a block is its id.
+    pub fn finalized(&self) -> &[Node] { &self.finalized }
+    /// Speculative rendering of the trailing blocks (usually one node, two
+    /// while the last one starts on an unfinished line, none when only
+    /// whitespace is pending).
+    pub fn open(&self) -> &[Node] { &self.open }
+    /// The finalized blocks followed by the open ones.
+    pub fn blocks(&self) -> impl Iterator<Item = StreamBlock<'_>> {
+        let finalized = self.finalized.iter().map(|node| (node, false));
+        let open = self.open.iter().map(|node| (node, true));
+        finalized
+            .chain(open)
+            .enumerate()
+            .map(|(index, (node, open))| StreamBlock { id: BlockId(index), node, open })
+    }
+    /// Appends a chunk, finalizing every block but the open ones, see the
+    /// module documentation.
+    pub fn push(&mut self, chunk: &str) -> Result<(), Error> {
+        check_input_size(self.source.len() + chunk.len(), &self.config.limits)?;
+        self.source.push_str(chunk);
+        let mut nodes = self.parse_open()?;
+        self.check_node_count(&nodes)?;
+        let frontmatter_pending = self.open_start == 0
+            && self.config.frontmatter
+            && (self.source.starts_with("---") || self.source.starts_with("+++"))
+            && !matches!(nodes.first(), Some(Node::Yaml(_) | Node::Toml(_)));
+        let open_count = self.open_count(&nodes);
+        if nodes.len() > open_count && !frontmatter_pending {
+            let open = nodes.split_off(nodes.len() - open_count);
+            if let Some(start) = open.first().and_then(Node::position).map(|x| x.start.clone()) {
+                self.open_start = line_start(&self.source, start.offset);
+                self.open_line = start.line;
+                self.finalized_nodes += node_count(&nodes);
+                self.finalized.append(&mut nodes);
+                nodes = open;
+            }
+        }
+        self.open = self.speculate(nodes)?;
+        Ok(())
+    }
+    /// Parses the open blocks without speculation, the result equals parsing
+    /// the whole source.
+    pub fn finish(mut self) -> Result<Vec<Node>, Error> {
+        let mut open = self.parse_open()?;
+        self.check_node_count(&open)?;
+        self.finalized.append(&mut open);
+        let has_definitions = self.config.definition || self.config.gfm_footnote_definition;
+        if has_definitions && self.finalized.iter().any(contains_definition) {
+            // Definitions may resolve references in blocks finalized before them.
+            return crate::parse_with(&self.source, &self.config)
+        }
+        Ok(self.finalized)
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl StreamingParser {
+    fn parse_open(&self) -> Result<Vec<Node>, Error> {
+        let text = &self.source[self.open_start..];
+        self.parse_from_open_start(text)
+    }
+    fn parse_from_open_start(&self, text: &str) -> Result<Vec<Node>, Error> {
+        let (start, line) = (self.open_start, self.open_line);
+        let nodes = parse_region(&self.config, &self.source, &self.finalized, text, start, line)?;
+        match nodes {
+            Some(nodes) => Ok(nodes),
+            None => {
+                let mut nodes = crate::parse_with(&self.source, &self.config)?;
+                nodes.retain(|node| node.position().is_some_and(|x| x.start.offset >= start));
+                Ok(nodes)
+            }
+        }
+    }
+    /// Of the finalized blocks followed by the given open ones.
+    fn check_node_count(&self, open: &[Node]) -> Result<(), LimitError> {
+        let limits = &self.config.limits;
+        match limits.max_nodes {
+            Some(_) => Limit::Nodes.check(limits.max_nodes, self.finalized_nodes + node_count(open), None),
+            None => Ok(()),
+        }
+    }
+    /// Number of trailing blocks to keep open. A block starting on the
+    /// unfinished last line may still turn into a continuation of the one
+    /// before it: `2` into the list item `2. b`, `***` into a lazy paragraph
+    /// line `***x`, `:` into the description of a definition list, so both
+    /// stay open.
+    fn open_count(&self, nodes: &[Node]) -> usize {
+        let unfinished_line = line_start(&self.source, self.source.len());
+        let mut open_count = match nodes {
+            [.., previous, last] => match (previous.position(), last.position()) {
+                (Some(previous_position), Some(last_position)) if last_position.start.offset >= unfinished_line => {
+                    let between = &self.source[previous_position.end.offset..last_position.start.offset];
+                    let is_marker = self.config.definition_list
+                        && matches!(previous, Node::Paragraph(_) | Node::DefinitionList(_))
+                        && self.source[unfinished_line..].trim() == ":";
+                    if is_marker || is_continuable(previous, line_endings(between) > 1) { 2 } else { 1 }
+                }
+                _ => 1,
+            },
+            _ => 1,
+        };
+        // Terms followed by descriptions join the definition list before them.
+        let first_open = nodes.len().saturating_sub(open_count - 1);
+        if self.config.definition_list && matches!(nodes[..first_open], [.., Node::DefinitionList(_), Node::Paragraph(_)]) {
+            open_count += 1;
+        }
+        open_count
+    }
+    fn speculate(&self, nodes: Vec<Node>) -> Result<Vec<Node>, Error> {
+        let text = &self.source[self.open_start..];
+        let closed = close_constructs(text, &self.config);
+        if closed == text {
+            return Ok(nodes)
+        }
+        let mut speculative = self.parse_from_open_start(&closed)?;
+        let end = end_point(&self.source, &self.config);
+        for node in speculative.iter_mut() {
+            clamp(node, &end);
+        }
+        Ok(speculative)
+    }
+}
+
+/// Whether lines after the block can still become part of it, depending on
+/// how they end. Only lists and containers continue past a blank line.
+fn is_continuable(node: &Node, after_blank_line: bool) -> bool {
+    match node {
+        Node::List(_) | Node::FootnoteDefinition(_) | Node::Admonition(_) => true,
+        Node::Paragraph(_) | Node::Blockquote(_) | Node::Definition(_) | Node::Table(_) | Node::DefinitionList(_) => {
+            !after_blank_line
+        }
+        _ => false,
+    }
+}
+
+/// Best-effort completion of the constructs left open at the end of `text`.
+fn close_constructs(text: &str, config: &ParseConfig) -> String {
+    let mut lines = text.split_inclusive('\n').collect::<Vec<_>>();
+    // A trailing `-`, `=`, `*` or `1.` may become a setext underline, a
+    // thematic break or a list item, wait for the rest of the line.
+    if let Some(last) = lines.last().filter(|x| !x.ends_with('\n')) {
+        let marker = last.trim();
+        let is_ambiguous = marker.chars().all(|x| matches!(x, '-' | '=' | '*' | '+' | '_'))
+            || (marker.trim_end_matches(['.', ')']).chars().all(|x| x.is_ascii_digit()) && marker.len() <= 10);
+        if is_ambiguous {
+            lines.pop();
+        }
+    }
+    let mut closed = lines.concat();
+    if let Some(fence) = open_fence(&lines, config) {
+        if !closed.ends_with('\n') {
+            closed.push('\n');
+        }
+        closed.push_str(&fence);
+        return closed
+    }
+    if config.gfm_table {
+        if let Some(delimiter) = table_delimiter_row(&lines) {
+            let last = lines.len() - 1;
+            closed = lines[..last].concat();
+            closed.push_str(&delimiter);
+            return closed
+        }
+    }
+    let paragraph_start = lines
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map_or(0, |index| index + 1);
+    let paragraph = lines[paragraph_start..].concat();
+    let indented = paragraph.starts_with("    ") || paragraph.starts_with('\t');
+    if indented || paragraph.trim_start().starts_with('<') {
+        return closed
+    }
+    let closers = inline_closers(&paragraph, config);
+    if !closers.is_empty() {
+        closed.truncate(closed.trim_end().len());
+        closed.push_str(&closers);
+    }
+    closed
+}
+
+/// The closing line of a code or math fence left open, including the
+/// container prefix (`> `, list indent) of the opening line.
+fn open_fence(lines: &[&str], config: &ParseConfig) -> Option<String> {
+    let mut open: Option<(String, char, usize)> = None;
+    for line in lines {
+        let content = line.trim_start_matches(|x: char| x.is_whitespace() || x == '>');
+        let content = match open {
+            None => content.trim_start_matches(|x: char| matches!(x, '-' | '*' | '+') || x.is_ascii_digit()),
+            Some(_) => content,
+        };
+        let content = content.trim_start_matches(['.', ')']).trim_start();
+        let marker = content.chars().next();
+        let length = content.chars().take_while(|x| Some(*x) == marker).count();
+        let is_fence = match marker {
+            Some('`') | Some('~') => length >= 3 && config.code_fenced,
+            Some('$') => length >= 2 && config.math_flow,
+            _ => false,
+        };
+        if !is_fence {
+            continue
+        }
+        let marker = marker.unwrap();
+        match &open {
+            None => {
+                let prefix = line[..line.len() - content.len()]
+                    .chars()
+                    .map(|x| if x == '>' || x.is_whitespace() { x } else { ' ' })
+                    .collect();
+                open = Some((prefix, marker, length));
+            }
+            Some((_, open_marker, open_length)) => {
+                let rest = content.trim_start_matches(marker).trim();
+                if marker == *open_marker && length >= *open_length && rest.is_empty() {
+                    open = None;
+                }
+            }
+        }
+    }
+    let (prefix, marker, length) = open?;
+    Some(format!("{prefix}{}", marker.to_string().repeat(length)))
+}
+
+/// A complete delimiter row replacing a partially received one that follows
+/// a header row.
+fn table_delimiter_row(lines: &[&str]) -> Option<String> {
+    let [.., header, last] = lines else { return None };
+    let last = last.trim();
+    let is_partial_delimiter = !last.is_empty()
+        && last.chars().all(|x| matches!(x, '|' | '-' | ':' | ' '))
+        && last.contains(['|', '-']);
+    if !is_partial_delimiter || !header.contains('|') {
+        return None
+    }
+    let header = header.trim().trim_start_matches('|').trim_end_matches('|');
+    let columns = header.split('|').count();
+    Some(format!("|{}\n", " --- |".repeat(columns)))
+}
+
+/// Closing delimiters for the emphasis, code, math and link destinations left
+/// open in the given paragraph text, innermost first.
+fn inline_closers(text: &str, config: &ParseConfig) -> String {
+    let mut stack: Vec<String> = Vec::new();
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut index = 0;
+    while index < chars.len() {
+        let current = chars[index];
+        let run = chars[index..].iter().take_while(|x| **x == current).count();
+        let previous = index.checked_sub(1).map(|x| chars[x]);
+        let next = chars.get(index + run).copied();
+        let top = stack.last().cloned();
+        let marker = current.to_string().repeat(run);
+        // Code and math spans hide everything until the matching run.
+        let in_code = top.as_deref().is_some_and(|x| x.starts_with(['`', '$']));
+        match current {
+            _ if in_code && top.as_deref() == Some(marker.as_str()) => {
+                stack.pop();
+            }
+            _ if in_code => (),
+            '\\' => {
+                index += 2;
+                continue
+            }
+            '`' if config.code_text => stack.push(marker),
+            '$' if config.math_text => stack.push(marker),
+            '*' | '_' | '~' if top.as_deref() == Some(marker.as_str()) => {
+                stack.pop();
+            }
+            '*' | '_' | '~' => {
+                let opens = next.is_some_and(|x| !x.is_whitespace())
+                    && (current == '*' || !matches!(previous, Some(x) if x.is_alphanumeric()))
+                    && (current != '~' || (config.gfm_strikethrough && run <= 2));
+                if opens {
+                    stack.push(marker);
+                }
+            }
+            '[' => stack.push("]".to_owned()),
+            ']' if top.as_deref() == Some("]") => {
+                stack.pop();
+                if next == Some('(') {
+                    stack.push(")".to_owned());
+                    index += run + 1;
+                    continue
+                }
+            }
+            ')' if top.as_deref() == Some(")") => {
+                stack.pop();
+            }
+            _ => (),
+        }
+        index += run;
+    }
+    // An unclosed `[` is just text.
+    stack.retain(|x| x != "]");
+    stack.into_iter().rev().collect()
+}
+
+fn end_point(source: &str, config: &ParseConfig) -> Point {
+    let start = line_start(source, source.len());
+    let mut point = Point {
+        line: line_endings(source) + 1,
+        column: source.len() - start + 1,
+        offset: source.len(),
+        utf16_offset: None,
+        scalar_offset: None,
+    };
+    if config.unicode_offsets {
+        Counts::of(source).set(&mut point);
+    }
+    point
+}
+
+/// Speculatively added text has no place in the source.
+fn clamp(node: &mut Node, end: &Point) {
+    if let Some(position) = node.position_mut() {
+        for point in [&mut position.start, &mut position.end] {
+            if point.offset > end.offset {
+                *point = end.clone();
+            }
+        }
+    }
+    if let Some(children) = node.children_mut() {
+        for child in children {
+            clamp(child, end);
+        }
+    }
+}
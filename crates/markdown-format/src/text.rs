@@ -0,0 +1,11 @@
+//! Line helpers shared by the passes that work on the source text.
+
+/// Byte offset of the start of the line that contains `offset`.
+pub(crate) fn line_start(source: &str, offset: usize) -> usize {
+    source[..offset].rfind(['\n', '\r']).map_or(0, |index| index + 1)
+}
+
+/// Counts `\n`, `\r\n` and `\r` line endings.
+pub(crate) fn line_endings(value: &str) -> usize {
+    value.matches('\n').count() + value.matches('\r').count() - value.matches("\r\n").count()
+}
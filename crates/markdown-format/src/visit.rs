@@ -0,0 +1,706 @@
+//! Traversal of the AST: node helpers, visitors, folds and iterators.
+//!
+//! ```
+//! use markdown_format::Node;
+//! use markdown_format::visit::{self, Visit, VisitMut};
+//!
+//! #[derive(Default)]
+//! struct Urls(Vec<String>);
+//! impl Visit for Urls {
+//!     fn visit_link(&mut self, link: &markdown_format::Link) {
+//!         self.0.push(link.url.clone());
+//!         self.visit_nodes(&link.children);
+//!     }
+//! }
+//!
+//! struct Shout;
+//! impl VisitMut for Shout {
+//!     fn visit_text_mut(&mut self, text: &mut markdown_format::Text) {
+//!         text.value = text.value.to_uppercase();
+//!     }
+//! }
+//!
+//! let mut nodes = markdown_format::parse("# [Hi](/a)\n\n> [there](/b)\n").unwrap();
+//! let mut urls = Urls::default();
+//! urls.visit_nodes(&nodes);
+//! assert_eq!(urls.0, ["/a", "/b"]);
+//!
+//! Shout.visit_nodes_mut(&mut nodes);
+//! let text = visit::depth_first(&nodes).find_map(|node| match node {
+//!     Node::Text(text) => Some(text.value.as_str()),
+//!     _ => None,
+//! });
+//! assert_eq!(text, Some("HI"));
+//! ```
+use std::collections::VecDeque;
+use crate::*;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The variant of a [`Node`], without its data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum NodeKind {
+    Text,
+    Newline,
+    Emphasis,
+    Strong,
+    Strikethrough,
+    Image,
+    ImageReference,
+    Link,
+    LinkReference,
+    CodeBlock,
+    InlineCode,
+    List,
+    ListItem,
+    Heading,
+    Table,
+    TableRow,
+    TableCell,
+    HorizontalDivider,
+    Definition,
+    Paragraph,
+    Blockquote,
+    FootnoteReference,
+    FootnoteDefinition,
+    DisplayMath,
+    InlineMath,
+    Toml,
+    Yaml,
+    Html,
+    Admonition,
+    WikiLink,
+    Embed,
+    DefinitionList,
+    DefinitionTerm,
+    DefinitionDescription,
+}
+
+/// Pre-order depth-first iterator, see [`depth_first`].
+#[derive(Debug, Clone)]
+pub struct DepthFirst<'a> {
+    stack: Vec<&'a Node>,
+}
+
+/// Level-order iterator, see [`breadth_first`].
+#[derive(Debug, Clone)]
+pub struct BreadthFirst<'a> {
+    queue: VecDeque<&'a Node>,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # NODE HELPERS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+impl Node {
+    pub fn kind(&self) -> NodeKind {
+        match self {
+            Node::Text(_) => NodeKind::Text,
+            Node::Newline(_) => NodeKind::Newline,
+            Node::Emphasis(_) => NodeKind::Emphasis,
+            Node::Strong(_) => NodeKind::Strong,
+            Node::Strikethrough(_) => NodeKind::Strikethrough,
+            Node::Image(_) => NodeKind::Image,
+            Node::ImageReference(_) => NodeKind::ImageReference,
+            Node::Link(_) => NodeKind::Link,
+            Node::LinkReference(_) => NodeKind::LinkReference,
+            Node::CodeBlock(_) => NodeKind::CodeBlock,
+            Node::InlineCode(_) => NodeKind::InlineCode,
+            Node::List(_) => NodeKind::List,
+            Node::ListItem(_) => NodeKind::ListItem,
+            Node::Heading(_) => NodeKind::Heading,
+            Node::Table(_) => NodeKind::Table,
+            Node::TableRow(_) => NodeKind::TableRow,
+            Node::TableCell(_) => NodeKind::TableCell,
+            Node::HorizontalDivider(_) => NodeKind::HorizontalDivider,
+            Node::Definition(_) => NodeKind::Definition,
+            Node::Paragraph(_) => NodeKind::Paragraph,
+            Node::Blockquote(_) => NodeKind::Blockquote,
+            Node::FootnoteReference(_) => NodeKind::FootnoteReference,
+            Node::FootnoteDefinition(_) => NodeKind::FootnoteDefinition,
+            Node::DisplayMath(_) => NodeKind::DisplayMath,
+            Node::InlineMath(_) => NodeKind::InlineMath,
+            Node::Toml(_) => NodeKind::Toml,
+            Node::Yaml(_) => NodeKind::Yaml,
+            Node::Html(_) => NodeKind::Html,
+            Node::Admonition(_) => NodeKind::Admonition,
+            Node::WikiLink(_) => NodeKind::WikiLink,
+            Node::Embed(_) => NodeKind::Embed,
+            Node::DefinitionList(_) => NodeKind::DefinitionList,
+            Node::DefinitionTerm(_) => NodeKind::DefinitionTerm,
+            Node::DefinitionDescription(_) => NodeKind::DefinitionDescription,
+        }
+    }
+    pub fn position(&self) -> Option<&SourceRange> {
+        match self {
+            Node::Text(x) => x.position.as_ref(),
+            Node::Newline(x) => x.position.as_ref(),
+            Node::Emphasis(x) => x.position.as_ref(),
+            Node::Strong(x) => x.position.as_ref(),
+            Node::Strikethrough(x) => x.position.as_ref(),
+            Node::Image(x) => x.position.as_ref(),
+            Node::ImageReference(x) => x.position.as_ref(),
+            Node::Link(x) => x.position.as_ref(),
+            Node::LinkReference(x) => x.position.as_ref(),
+            Node::CodeBlock(x) => x.position.as_ref(),
+            Node::InlineCode(x) => x.position.as_ref(),
+            Node::List(x) => x.position.as_ref(),
+            Node::ListItem(x) => x.position.as_ref(),
+            Node::Heading(x) => x.position.as_ref(),
+            Node::Table(x) => x.position.as_ref(),
+            Node::TableRow(x) => x.position.as_ref(),
+            Node::TableCell(x) => x.position.as_ref(),
+            Node::HorizontalDivider(x) => x.position.as_ref(),
+            Node::Definition(x) => x.position.as_ref(),
+            Node::Paragraph(x) => x.position.as_ref(),
+            Node::Blockquote(x) => x.position.as_ref(),
+            Node::FootnoteReference(x) => x.position.as_ref(),
+            Node::FootnoteDefinition(x) => x.position.as_ref(),
+            Node::DisplayMath(x) => x.position.as_ref(),
+            Node::InlineMath(x) => x.position.as_ref(),
+            Node::Toml(x) => x.position.as_ref(),
+            Node::Yaml(x) => x.position.as_ref(),
+            Node::Html(x) => x.position.as_ref(),
+            Node::Admonition(x) => x.position.as_ref(),
+            Node::WikiLink(x) => x.position.as_ref(),
+            Node::Embed(x) => x.position.as_ref(),
+            Node::DefinitionList(x) => x.position.as_ref(),
+            Node::DefinitionTerm(x) => x.position.as_ref(),
+            Node::DefinitionDescription(x) => x.position.as_ref(),
+        }
+    }
+    pub fn position_mut(&mut self) -> Option<&mut SourceRange> {
+        match self {
+            Node::Text(x) => x.position.as_mut(),
+            Node::Newline(x) => x.position.as_mut(),
+            Node::Emphasis(x) => x.position.as_mut(),
+            Node::Strong(x) => x.position.as_mut(),
+            Node::Strikethrough(x) => x.position.as_mut(),
+            Node::Image(x) => x.position.as_mut(),
+            Node::ImageReference(x) => x.position.as_mut(),
+            Node::Link(x) => x.position.as_mut(),
+            Node::LinkReference(x) => x.position.as_mut(),
+            Node::CodeBlock(x) => x.position.as_mut(),
+            Node::InlineCode(x) => x.position.as_mut(),
+            Node::List(x) => x.position.as_mut(),
+            Node::ListItem(x) => x.position.as_mut(),
+            Node::Heading(x) => x.position.as_mut(),
+            Node::Table(x) => x.position.as_mut(),
+            Node::TableRow(x) => x.position.as_mut(),
+            Node::TableCell(x) => x.position.as_mut(),
+            Node::HorizontalDivider(x) => x.position.as_mut(),
+            Node::Definition(x) => x.position.as_mut(),
+            Node::Paragraph(x) => x.position.as_mut(),
+            Node::Blockquote(x) => x.position.as_mut(),
+            Node::FootnoteReference(x) => x.position.as_mut(),
+            Node::FootnoteDefinition(x) => x.position.as_mut(),
+            Node::DisplayMath(x) => x.position.as_mut(),
+            Node::InlineMath(x) => x.position.as_mut(),
+            Node::Toml(x) => x.position.as_mut(),
+            Node::Yaml(x) => x.position.as_mut(),
+            Node::Html(x) => x.position.as_mut(),
+            Node::Admonition(x) => x.position.as_mut(),
+            Node::WikiLink(x) => x.position.as_mut(),
+            Node::Embed(x) => x.position.as_mut(),
+            Node::DefinitionList(x) => x.position.as_mut(),
+            Node::DefinitionTerm(x) => x.position.as_mut(),
+            Node::DefinitionDescription(x) => x.position.as_mut(),
+        }
+    }
+    /// Empty for nodes that cannot have children.
+    pub fn children(&self) -> &[Node] {
+        match self {
+            Node::Emphasis(x) => &x.children,
+            Node::Strong(x) => &x.children,
+            Node::Strikethrough(x) => &x.children,
+            Node::Link(x) => &x.children,
+            Node::LinkReference(x) => &x.children,
+            Node::List(x) => &x.children,
+            Node::ListItem(x) => &x.children,
+            Node::Heading(x) => &x.children,
+            Node::Table(x) => &x.children,
+            Node::TableRow(x) => &x.children,
+            Node::TableCell(x) => &x.children,
+            Node::Paragraph(x) => &x.children,
+            Node::Blockquote(x) => &x.children,
+            Node::FootnoteDefinition(x) => &x.children,
+            Node::Admonition(x) => &x.children,
+            Node::DefinitionList(x) => &x.children,
+            Node::DefinitionTerm(x) => &x.children,
+            Node::DefinitionDescription(x) => &x.children,
+            _ => &[],
+        }
+    }
+    /// `None` for nodes that cannot have children.
+    pub fn children_mut(&mut self) -> Option<&mut Vec<Node>> {
+        match self {
+            Node::Emphasis(x) => Some(&mut x.children),
+            Node::Strong(x) => Some(&mut x.children),
+            Node::Strikethrough(x) => Some(&mut x.children),
+            Node::Link(x) => Some(&mut x.children),
+            Node::LinkReference(x) => Some(&mut x.children),
+            Node::List(x) => Some(&mut x.children),
+            Node::ListItem(x) => Some(&mut x.children),
+            Node::Heading(x) => Some(&mut x.children),
+            Node::Table(x) => Some(&mut x.children),
+            Node::TableRow(x) => Some(&mut x.children),
+            Node::TableCell(x) => Some(&mut x.children),
+            Node::Paragraph(x) => Some(&mut x.children),
+            Node::Blockquote(x) => Some(&mut x.children),
+            Node::FootnoteDefinition(x) => Some(&mut x.children),
+            Node::Admonition(x) => Some(&mut x.children),
+            Node::DefinitionList(x) => Some(&mut x.children),
+            Node::DefinitionTerm(x) => Some(&mut x.children),
+            Node::DefinitionDescription(x) => Some(&mut x.children),
+            _ => None,
+        }
+    }
+    /// This node followed by its descendants, depth-first.
+    pub fn descendants(&self) -> DepthFirst<'_> {
+        DepthFirst { stack: vec![self] }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # ITERATORS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// The nodes and their descendants in document order: every node comes before
+/// its children.
+pub fn depth_first(nodes: &[Node]) -> DepthFirst<'_> {
+    DepthFirst { stack: nodes.iter().rev().collect() }
+}
+
+/// The nodes and their descendants level by level: the given nodes first,
+/// then all of their children, then all of their grandchildren, ….
+pub fn breadth_first(nodes: &[Node]) -> BreadthFirst<'_> {
+    BreadthFirst { queue: nodes.iter().collect() }
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = &'a Node;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.stack.extend(node.children().iter().rev());
+        Some(node)
+    }
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+    type Item = &'a Node;
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.queue.pop_front()?;
+        self.queue.extend(node.children());
+        Some(node)
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # VISIT
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Read-only traversal. Every method defaults to visiting the children, so an
+/// implementation only overrides the node types it cares about; an override
+/// that wants to keep descending calls `self.visit_nodes(&node.children)`.
+pub trait Visit {
+    fn visit_nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.visit_node(node);
+        }
+    }
+    fn visit_node(&mut self, node: &Node) {
+        walk_node(self, node)
+    }
+    fn visit_text(&mut self, _: &Text) {}
+    fn visit_newline(&mut self, _: &Newline) {}
+    fn visit_emphasis(&mut self, node: &Emphasis) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_strong(&mut self, node: &Strong) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_strikethrough(&mut self, node: &Strikethrough) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_image(&mut self, _: &Image) {}
+    fn visit_image_reference(&mut self, _: &ImageReference) {}
+    fn visit_link(&mut self, node: &Link) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_link_reference(&mut self, node: &LinkReference) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_code_block(&mut self, _: &CodeBlock) {}
+    fn visit_inline_code(&mut self, _: &InlineCode) {}
+    fn visit_list(&mut self, node: &List) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_list_item(&mut self, node: &ListItem) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_heading(&mut self, node: &Heading) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_table(&mut self, node: &Table) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_table_row(&mut self, node: &TableRow) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_table_cell(&mut self, node: &TableCell) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_horizontal_divider(&mut self, _: &HorizontalDivider) {}
+    fn visit_definition(&mut self, _: &Definition) {}
+    fn visit_paragraph(&mut self, node: &Paragraph) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_blockquote(&mut self, node: &Blockquote) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_footnote_reference(&mut self, _: &FootnoteReference) {}
+    fn visit_footnote_definition(&mut self, node: &FootnoteDefinition) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_display_math(&mut self, _: &DisplayMath) {}
+    fn visit_inline_math(&mut self, _: &InlineMath) {}
+    fn visit_toml(&mut self, _: &Toml) {}
+    fn visit_yaml(&mut self, _: &Yaml) {}
+    fn visit_html(&mut self, _: &Html) {}
+    fn visit_admonition(&mut self, node: &Admonition) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_wiki_link(&mut self, _: &WikiLink) {}
+    fn visit_embed(&mut self, _: &Embed) {}
+    fn visit_definition_list(&mut self, node: &DefinitionList) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_definition_term(&mut self, node: &DefinitionTerm) {
+        self.visit_nodes(&node.children)
+    }
+    fn visit_definition_description(&mut self, node: &DefinitionDescription) {
+        self.visit_nodes(&node.children)
+    }
+}
+
+/// Dispatches to the [`Visit`] method of the node’s type.
+pub fn walk_node<V: Visit + ?Sized>(visitor: &mut V, node: &Node) {
+    match node {
+        Node::Text(x) => visitor.visit_text(x),
+        Node::Newline(x) => visitor.visit_newline(x),
+        Node::Emphasis(x) => visitor.visit_emphasis(x),
+        Node::Strong(x) => visitor.visit_strong(x),
+        Node::Strikethrough(x) => visitor.visit_strikethrough(x),
+        Node::Image(x) => visitor.visit_image(x),
+        Node::ImageReference(x) => visitor.visit_image_reference(x),
+        Node::Link(x) => visitor.visit_link(x),
+        Node::LinkReference(x) => visitor.visit_link_reference(x),
+        Node::CodeBlock(x) => visitor.visit_code_block(x),
+        Node::InlineCode(x) => visitor.visit_inline_code(x),
+        Node::List(x) => visitor.visit_list(x),
+        Node::ListItem(x) => visitor.visit_list_item(x),
+        Node::Heading(x) => visitor.visit_heading(x),
+        Node::Table(x) => visitor.visit_table(x),
+        Node::TableRow(x) => visitor.visit_table_row(x),
+        Node::TableCell(x) => visitor.visit_table_cell(x),
+        Node::HorizontalDivider(x) => visitor.visit_horizontal_divider(x),
+        Node::Definition(x) => visitor.visit_definition(x),
+        Node::Paragraph(x) => visitor.visit_paragraph(x),
+        Node::Blockquote(x) => visitor.visit_blockquote(x),
+        Node::FootnoteReference(x) => visitor.visit_footnote_reference(x),
+        Node::FootnoteDefinition(x) => visitor.visit_footnote_definition(x),
+        Node::DisplayMath(x) => visitor.visit_display_math(x),
+        Node::InlineMath(x) => visitor.visit_inline_math(x),
+        Node::Toml(x) => visitor.visit_toml(x),
+        Node::Yaml(x) => visitor.visit_yaml(x),
+        Node::Html(x) => visitor.visit_html(x),
+        Node::Admonition(x) => visitor.visit_admonition(x),
+        Node::WikiLink(x) => visitor.visit_wiki_link(x),
+        Node::Embed(x) => visitor.visit_embed(x),
+        Node::DefinitionList(x) => visitor.visit_definition_list(x),
+        Node::DefinitionTerm(x) => visitor.visit_definition_term(x),
+        Node::DefinitionDescription(x) => visitor.visit_definition_description(x),
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # VISIT MUT
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// In-place traversal, like [`Visit`]. Children are passed as a `Vec` so that
+/// nodes can be inserted or removed.
+pub trait VisitMut {
+    fn visit_nodes_mut(&mut self, nodes: &mut Vec<Node>) {
+        for node in nodes {
+            self.visit_node_mut(node);
+        }
+    }
+    fn visit_node_mut(&mut self, node: &mut Node) {
+        walk_node_mut(self, node)
+    }
+    fn visit_text_mut(&mut self, _: &mut Text) {}
+    fn visit_newline_mut(&mut self, _: &mut Newline) {}
+    fn visit_emphasis_mut(&mut self, node: &mut Emphasis) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_strong_mut(&mut self, node: &mut Strong) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_strikethrough_mut(&mut self, node: &mut Strikethrough) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_image_mut(&mut self, _: &mut Image) {}
+    fn visit_image_reference_mut(&mut self, _: &mut ImageReference) {}
+    fn visit_link_mut(&mut self, node: &mut Link) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_link_reference_mut(&mut self, node: &mut LinkReference) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_code_block_mut(&mut self, _: &mut CodeBlock) {}
+    fn visit_inline_code_mut(&mut self, _: &mut InlineCode) {}
+    fn visit_list_mut(&mut self, node: &mut List) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_list_item_mut(&mut self, node: &mut ListItem) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_heading_mut(&mut self, node: &mut Heading) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_table_mut(&mut self, node: &mut Table) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_table_row_mut(&mut self, node: &mut TableRow) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_table_cell_mut(&mut self, node: &mut TableCell) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_horizontal_divider_mut(&mut self, _: &mut HorizontalDivider) {}
+    fn visit_definition_mut(&mut self, _: &mut Definition) {}
+    fn visit_paragraph_mut(&mut self, node: &mut Paragraph) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_blockquote_mut(&mut self, node: &mut Blockquote) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_footnote_reference_mut(&mut self, _: &mut FootnoteReference) {}
+    fn visit_footnote_definition_mut(&mut self, node: &mut FootnoteDefinition) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_display_math_mut(&mut self, _: &mut DisplayMath) {}
+    fn visit_inline_math_mut(&mut self, _: &mut InlineMath) {}
+    fn visit_toml_mut(&mut self, _: &mut Toml) {}
+    fn visit_yaml_mut(&mut self, _: &mut Yaml) {}
+    fn visit_html_mut(&mut self, _: &mut Html) {}
+    fn visit_admonition_mut(&mut self, node: &mut Admonition) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_wiki_link_mut(&mut self, _: &mut WikiLink) {}
+    fn visit_embed_mut(&mut self, _: &mut Embed) {}
+    fn visit_definition_list_mut(&mut self, node: &mut DefinitionList) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_definition_term_mut(&mut self, node: &mut DefinitionTerm) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+    fn visit_definition_description_mut(&mut self, node: &mut DefinitionDescription) {
+        self.visit_nodes_mut(&mut node.children)
+    }
+}
+
+/// Dispatches to the [`VisitMut`] method of the node’s type.
+pub fn walk_node_mut<V: VisitMut + ?Sized>(visitor: &mut V, node: &mut Node) {
+    match node {
+        Node::Text(x) => visitor.visit_text_mut(x),
+        Node::Newline(x) => visitor.visit_newline_mut(x),
+        Node::Emphasis(x) => visitor.visit_emphasis_mut(x),
+        Node::Strong(x) => visitor.visit_strong_mut(x),
+        Node::Strikethrough(x) => visitor.visit_strikethrough_mut(x),
+        Node::Image(x) => visitor.visit_image_mut(x),
+        Node::ImageReference(x) => visitor.visit_image_reference_mut(x),
+        Node::Link(x) => visitor.visit_link_mut(x),
+        Node::LinkReference(x) => visitor.visit_link_reference_mut(x),
+        Node::CodeBlock(x) => visitor.visit_code_block_mut(x),
+        Node::InlineCode(x) => visitor.visit_inline_code_mut(x),
+        Node::List(x) => visitor.visit_list_mut(x),
+        Node::ListItem(x) => visitor.visit_list_item_mut(x),
+        Node::Heading(x) => visitor.visit_heading_mut(x),
+        Node::Table(x) => visitor.visit_table_mut(x),
+        Node::TableRow(x) => visitor.visit_table_row_mut(x),
+        Node::TableCell(x) => visitor.visit_table_cell_mut(x),
+        Node::HorizontalDivider(x) => visitor.visit_horizontal_divider_mut(x),
+        Node::Definition(x) => visitor.visit_definition_mut(x),
+        Node::Paragraph(x) => visitor.visit_paragraph_mut(x),
+        Node::Blockquote(x) => visitor.visit_blockquote_mut(x),
+        Node::FootnoteReference(x) => visitor.visit_footnote_reference_mut(x),
+        Node::FootnoteDefinition(x) => visitor.visit_footnote_definition_mut(x),
+        Node::DisplayMath(x) => visitor.visit_display_math_mut(x),
+        Node::InlineMath(x) => visitor.visit_inline_math_mut(x),
+        Node::Toml(x) => visitor.visit_toml_mut(x),
+        Node::Yaml(x) => visitor.visit_yaml_mut(x),
+        Node::Html(x) => visitor.visit_html_mut(x),
+        Node::Admonition(x) => visitor.visit_admonition_mut(x),
+        Node::WikiLink(x) => visitor.visit_wiki_link_mut(x),
+        Node::Embed(x) => visitor.visit_embed_mut(x),
+        Node::DefinitionList(x) => visitor.visit_definition_list_mut(x),
+        Node::DefinitionTerm(x) => visitor.visit_definition_term_mut(x),
+        Node::DefinitionDescription(x) => visitor.visit_definition_description_mut(x),
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # FOLD
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Owned traversal that rebuilds the tree bottom-up. Override `fold_node` to
+/// replace a node by one of another type and `fold_nodes` to drop or splice
+/// nodes.
+pub trait Fold {
+    fn fold_nodes(&mut self, nodes: Vec<Node>) -> Vec<Node> {
+        nodes.into_iter().map(|node| self.fold_node(node)).collect()
+    }
+    fn fold_node(&mut self, node: Node) -> Node {
+        fold_node(self, node)
+    }
+    fn fold_text(&mut self, node: Text) -> Text {
+        node
+    }
+    fn fold_newline(&mut self, node: Newline) -> Newline {
+        node
+    }
+    fn fold_emphasis(&mut self, node: Emphasis) -> Emphasis {
+        Emphasis { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_strong(&mut self, node: Strong) -> Strong {
+        Strong { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_strikethrough(&mut self, node: Strikethrough) -> Strikethrough {
+        Strikethrough { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_image(&mut self, node: Image) -> Image {
+        node
+    }
+    fn fold_image_reference(&mut self, node: ImageReference) -> ImageReference {
+        node
+    }
+    fn fold_link(&mut self, node: Link) -> Link {
+        Link { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_link_reference(&mut self, node: LinkReference) -> LinkReference {
+        LinkReference { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_code_block(&mut self, node: CodeBlock) -> CodeBlock {
+        node
+    }
+    fn fold_inline_code(&mut self, node: InlineCode) -> InlineCode {
+        node
+    }
+    fn fold_list(&mut self, node: List) -> List {
+        List { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_list_item(&mut self, node: ListItem) -> ListItem {
+        ListItem { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_heading(&mut self, node: Heading) -> Heading {
+        Heading { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_table(&mut self, node: Table) -> Table {
+        Table { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_table_row(&mut self, node: TableRow) -> TableRow {
+        TableRow { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_table_cell(&mut self, node: TableCell) -> TableCell {
+        TableCell { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_horizontal_divider(&mut self, node: HorizontalDivider) -> HorizontalDivider {
+        node
+    }
+    fn fold_definition(&mut self, node: Definition) -> Definition {
+        node
+    }
+    fn fold_paragraph(&mut self, node: Paragraph) -> Paragraph {
+        Paragraph { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_blockquote(&mut self, node: Blockquote) -> Blockquote {
+        Blockquote { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_footnote_reference(&mut self, node: FootnoteReference) -> FootnoteReference {
+        node
+    }
+    fn fold_footnote_definition(&mut self, node: FootnoteDefinition) -> FootnoteDefinition {
+        FootnoteDefinition { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_display_math(&mut self, node: DisplayMath) -> DisplayMath {
+        node
+    }
+    fn fold_inline_math(&mut self, node: InlineMath) -> InlineMath {
+        node
+    }
+    fn fold_toml(&mut self, node: Toml) -> Toml {
+        node
+    }
+    fn fold_yaml(&mut self, node: Yaml) -> Yaml {
+        node
+    }
+    fn fold_html(&mut self, node: Html) -> Html {
+        node
+    }
+    fn fold_admonition(&mut self, node: Admonition) -> Admonition {
+        Admonition { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_wiki_link(&mut self, node: WikiLink) -> WikiLink {
+        node
+    }
+    fn fold_embed(&mut self, node: Embed) -> Embed {
+        node
+    }
+    fn fold_definition_list(&mut self, node: DefinitionList) -> DefinitionList {
+        DefinitionList { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_definition_term(&mut self, node: DefinitionTerm) -> DefinitionTerm {
+        DefinitionTerm { children: self.fold_nodes(node.children), ..node }
+    }
+    fn fold_definition_description(&mut self, node: DefinitionDescription) -> DefinitionDescription {
+        DefinitionDescription { children: self.fold_nodes(node.children), ..node }
+    }
+}
+
+/// Dispatches to the [`Fold`] method of the node’s type.
+pub fn fold_node<F: Fold + ?Sized>(folder: &mut F, node: Node) -> Node {
+    match node {
+        Node::Text(x) => Node::Text(folder.fold_text(x)),
+        Node::Newline(x) => Node::Newline(folder.fold_newline(x)),
+        Node::Emphasis(x) => Node::Emphasis(folder.fold_emphasis(x)),
+        Node::Strong(x) => Node::Strong(folder.fold_strong(x)),
+        Node::Strikethrough(x) => Node::Strikethrough(folder.fold_strikethrough(x)),
+        Node::Image(x) => Node::Image(folder.fold_image(x)),
+        Node::ImageReference(x) => Node::ImageReference(folder.fold_image_reference(x)),
+        Node::Link(x) => Node::Link(folder.fold_link(x)),
+        Node::LinkReference(x) => Node::LinkReference(folder.fold_link_reference(x)),
+        Node::CodeBlock(x) => Node::CodeBlock(folder.fold_code_block(x)),
+        Node::InlineCode(x) => Node::InlineCode(folder.fold_inline_code(x)),
+        Node::List(x) => Node::List(folder.fold_list(x)),
+        Node::ListItem(x) => Node::ListItem(folder.fold_list_item(x)),
+        Node::Heading(x) => Node::Heading(folder.fold_heading(x)),
+        Node::Table(x) => Node::Table(folder.fold_table(x)),
+        Node::TableRow(x) => Node::TableRow(folder.fold_table_row(x)),
+        Node::TableCell(x) => Node::TableCell(folder.fold_table_cell(x)),
+        Node::HorizontalDivider(x) => Node::HorizontalDivider(folder.fold_horizontal_divider(x)),
+        Node::Definition(x) => Node::Definition(folder.fold_definition(x)),
+        Node::Paragraph(x) => Node::Paragraph(folder.fold_paragraph(x)),
+        Node::Blockquote(x) => Node::Blockquote(folder.fold_blockquote(x)),
+        Node::FootnoteReference(x) => Node::FootnoteReference(folder.fold_footnote_reference(x)),
+        Node::FootnoteDefinition(x) => Node::FootnoteDefinition(folder.fold_footnote_definition(x)),
+        Node::DisplayMath(x) => Node::DisplayMath(folder.fold_display_math(x)),
+        Node::InlineMath(x) => Node::InlineMath(folder.fold_inline_math(x)),
+        Node::Toml(x) => Node::Toml(folder.fold_toml(x)),
+        Node::Yaml(x) => Node::Yaml(folder.fold_yaml(x)),
+        Node::Html(x) => Node::Html(folder.fold_html(x)),
+        Node::Admonition(x) => Node::Admonition(folder.fold_admonition(x)),
+        Node::WikiLink(x) => Node::WikiLink(folder.fold_wiki_link(x)),
+        Node::Embed(x) => Node::Embed(folder.fold_embed(x)),
+        Node::DefinitionList(x) => Node::DefinitionList(folder.fold_definition_list(x)),
+        Node::DefinitionTerm(x) => Node::DefinitionTerm(folder.fold_definition_term(x)),
+        Node::DefinitionDescription(x) => Node::DefinitionDescription(folder.fold_definition_description(x)),
+    }
+}
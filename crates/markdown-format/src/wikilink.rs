@@ -0,0 +1,245 @@
+//! Wiki links and embeds: `[[Page name#Heading|alias]]` and `![[image.png]]`.
+//!
+//! They are recognized after parsing, in text that came through verbatim:
+//! escaped brackets and wiki links split by other markup stay text, as do
+//! the ones inside link text. Hosts map targets to URLs with [`resolve`].
+//!
+//! ```
+//! use markdown_format::{Node, ParseConfig};
+//! use markdown_format::wikilink::{self, Target};
+//! let config = ParseConfig { wikilink: true, ..ParseConfig::gfm() };
+//! let mut nodes = markdown_format::parse_with("See [[Setup#Linux|setup]].", &config).unwrap();
+//! wikilink::resolve(&mut nodes, &mut |x: Target| Some(format!("/wiki/{}", x.target)));
+//! let Node::Paragraph(paragraph) = &nodes[0] else { panic!() };
+//! let Node::WikiLink(link) = &paragraph.children[1] else { panic!() };
+//! assert_eq!((link.target.as_str(), link.heading_anchor.as_deref()), ("Setup", Some("Linux")));
+//! assert_eq!(link.url.as_deref(), Some("/wiki/Setup"));
+//! ```
+use std::ops::Range;
+use crate::{Embed, Link, LinkReference, Node, Point, SourceRange, Text, WikiLink};
+use crate::text::{line_endings, line_start};
+use crate::visit::VisitMut;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # DATA TYPES
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// What a wiki link or embed points to, as given to a [`Resolver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target<'a> {
+    pub target: &'a str,
+    pub heading_anchor: Option<&'a str>,
+    pub is_embed: bool,
+}
+
+/// Maps wiki link and embed targets to URLs, implemented for closures taking
+/// a [`Target`].
+pub trait Resolver {
+    /// `None` leaves the link unresolved.
+    fn resolve(&mut self, target: Target<'_>) -> Option<String>;
+}
+
+impl<F: FnMut(Target<'_>) -> Option<String>> Resolver for F {
+    fn resolve(&mut self, target: Target<'_>) -> Option<String> {
+        self(target)
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Turns wiki links and embeds in text into [`WikiLink`] and [`Embed`]
+/// nodes, at any depth. `source` is the document the positions of `nodes`
+/// point into.
+pub fn wikilinks(source: &str, nodes: &mut Vec<Node>) {
+    Pass { source }.visit_nodes_mut(nodes);
+}
+
+/// Sets the `url` of every wiki link and embed.
+pub fn resolve(nodes: &mut Vec<Node>, resolver: &mut impl Resolver) {
+    Resolve { resolver }.visit_nodes_mut(nodes);
+}
+
+impl WikiLink {
+    /// The text to show: the alias, otherwise the target and heading as
+    /// written.
+    pub fn text(&self) -> String {
+        match self.alias.as_ref() {
+            Some(alias) => alias.clone(),
+            None => link_text(&self.target, self.heading_anchor.as_deref()),
+        }
+    }
+}
+
+impl Embed {
+    /// Alternative text: the alias unless it is a size such as `200x100`,
+    /// otherwise the target.
+    pub fn alt(&self) -> String {
+        let is_size = |alias: &&String| {
+            let (width, height) = alias.split_once('x').unwrap_or((alias, "0"));
+            [width, height].iter().all(|x| !x.is_empty() && x.bytes().all(|x| x.is_ascii_digit()))
+        };
+        match self.alias.as_ref().filter(|x| !is_size(x)) {
+            Some(alias) => alias.clone(),
+            None => link_text(&self.target, self.heading_anchor.as_deref()),
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+struct Pass<'a> {
+    source: &'a str,
+}
+
+/// A wiki link found in a text value.
+struct Found {
+    range: Range<usize>,
+    is_embed: bool,
+    target: String,
+    alias: Option<String>,
+    heading_anchor: Option<String>,
+}
+
+impl VisitMut for Pass<'_> {
+    fn visit_nodes_mut(&mut self, nodes: &mut Vec<Node>) {
+        if nodes.iter().any(|x| matches!(x, Node::Text(text) if text.value.contains("[["))) {
+            let mut output = Vec::with_capacity(nodes.len());
+            for node in std::mem::take(nodes) {
+                match node {
+                    Node::Text(text) => self.text(text, &mut output),
+                    node => output.push(node),
+                }
+            }
+            *nodes = output;
+        }
+        for node in nodes {
+            self.visit_node_mut(node);
+        }
+    }
+    // Links can't contain links.
+    fn visit_link_mut(&mut self, _: &mut Link) {}
+    fn visit_link_reference_mut(&mut self, _: &mut LinkReference) {}
+}
+
+impl Pass<'_> {
+    /// Splits the text around its wiki links.
+    fn text(&self, text: Text, output: &mut Vec<Node>) {
+        let Some(position) = text.position.clone() else {
+            output.push(Node::Text(text));
+            return
+        };
+        // Value and source positions of the text not yet output.
+        let mut rest = 0;
+        let mut rest_start = position.start.clone();
+        for found in find(&text.value) {
+            let raw = &text.value[found.range.clone()];
+            let searched = self.source.get(rest_start.offset..position.end.offset).unwrap_or_default();
+            // In table cells the alias is separated by an escaped `|`.
+            let escaped = raw.replace('|', "\\|");
+            let found_at = [raw, escaped.as_str()]
+                .into_iter()
+                .find_map(|raw| Some((searched.find(raw)?, raw.len())));
+            let Some((index, length)) = found_at else { continue };
+            let start = self.point(&position.start, rest_start.offset + index);
+            let end = self.point(&position.start, start.offset + length);
+            if found.range.start > rest {
+                let value = text.value[rest..found.range.start].to_owned();
+                let position = SourceRange { start: rest_start, end: start.clone() };
+                output.push(Node::Text(Text { value, position: Some(position) }));
+            }
+            let Found { is_embed, target, alias, heading_anchor, .. } = found;
+            let position = Some(SourceRange { start, end: end.clone() });
+            output.push(match is_embed {
+                true => Node::Embed(Embed { position, target, alias, heading_anchor, url: None }),
+                false => Node::WikiLink(WikiLink { position, target, alias, heading_anchor, url: None }),
+            });
+            rest = found.range.end;
+            rest_start = end;
+        }
+        match rest {
+            0 => output.push(Node::Text(text)),
+            rest if rest < text.value.len() => {
+                let value = text.value[rest..].to_owned();
+                let position = SourceRange { start: rest_start, end: position.end };
+                output.push(Node::Text(Text { value, position: Some(position) }));
+            }
+            _ => (),
+        }
+    }
+    /// The point at `offset`, which is after `start` in the same text.
+    fn point(&self, start: &Point, offset: usize) -> Point {
+        Point {
+            line: start.line + line_endings(&self.source[start.offset..offset]),
+            column: offset - line_start(self.source, offset) + 1,
+            offset,
+            utf16_offset: None,
+            scalar_offset: None,
+        }
+    }
+}
+
+struct Resolve<'a, R> {
+    resolver: &'a mut R,
+}
+
+impl<R: Resolver> VisitMut for Resolve<'_, R> {
+    fn visit_wiki_link_mut(&mut self, node: &mut WikiLink) {
+        let heading_anchor = node.heading_anchor.as_deref();
+        node.url = self.resolver.resolve(Target { target: &node.target, heading_anchor, is_embed: false });
+    }
+    fn visit_embed_mut(&mut self, node: &mut Embed) {
+        let heading_anchor = node.heading_anchor.as_deref();
+        node.url = self.resolver.resolve(Target { target: &node.target, heading_anchor, is_embed: true });
+    }
+}
+
+/// The wiki links of a text value, in order. Their content is a single line
+/// without brackets.
+fn find(value: &str) -> Vec<Found> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(index) = value[from..].find("[[") {
+        let open = from + index;
+        let content_start = open + 2;
+        let Some(length) = value[content_start..].find("]]") else { break };
+        let content = &value[content_start..content_start + length];
+        let parts = Some(content).filter(|x| !x.contains(['[', ']', '\n', '\r'])).and_then(parts);
+        let Some((target, heading_anchor, alias)) = parts else {
+            from = open + 1;
+            continue
+        };
+        let is_embed = value[..open].ends_with('!');
+        let start = if is_embed { open - 1 } else { open };
+        let end = content_start + length + 2;
+        found.push(Found { range: start..end, is_embed, target, alias, heading_anchor });
+        from = end;
+    }
+    found
+}
+
+/// `target#heading|alias`, where only one of target and heading is required.
+fn parts(content: &str) -> Option<(String, Option<String>, Option<String>)> {
+    let (link, alias) = match content.split_once('|') {
+        Some((link, alias)) => (link, Some(alias.trim())),
+        None => (content, None),
+    };
+    let (target, heading_anchor) = match link.split_once('#') {
+        Some((target, heading_anchor)) => (target.trim(), Some(heading_anchor.trim())),
+        None => (link.trim(), None),
+    };
+    let heading_anchor = heading_anchor.filter(|x| !x.is_empty()).map(str::to_owned);
+    if target.is_empty() && heading_anchor.is_none() {
+        return None
+    }
+    let alias = alias.filter(|x| !x.is_empty()).map(str::to_owned);
+    Some((target.to_owned(), heading_anchor, alias))
+}
+
+fn link_text(target: &str, heading_anchor: Option<&str>) -> String {
+    match (target, heading_anchor) {
+        (target, None) => target.to_owned(),
+        ("", Some(heading_anchor)) => heading_anchor.to_owned(),
+        (target, Some(heading_anchor)) => format!("{target}#{heading_anchor}"),
+    }
+}
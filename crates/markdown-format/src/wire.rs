@@ -0,0 +1,74 @@
+//! Versioned wire format.
+use crate::Node;
+
+/// Parsed nodes tagged with the version of the wire schema they follow,
+/// encoded as `{"version": 2, "nodes": [...]}`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Envelope {
+    /// Wire schema version, see [`Envelope::VERSION`].
+    pub version: u32,
+    /// Top-level nodes of the document.
+    pub nodes: Vec<Node>,
+}
+
+impl Envelope {
+    /// The wire schema version written by this crate.
+    ///
+    /// Bumped whenever the AST changes in a way existing decoders can't read.
+    /// Version 2 added admonitions, wiki links, embeds, definition lists and
+    /// the highlights of code blocks.
+    pub const VERSION: u32 = 2;
+    pub fn new(nodes: Vec<Node>) -> Self {
+        Self { version: Self::VERSION, nodes }
+    }
+    /// Decodes an envelope, or a bare node array as emitted before envelopes
+    /// (taken to be the current version).
+    pub fn from_json(json: &str) -> Result<Self, WireError> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Payload {
+            Envelope(Envelope),
+            Nodes(Vec<Node>),
+        }
+        let envelope = match serde_json::from_str(json).map_err(WireError::Json)? {
+            Payload::Envelope(envelope) => envelope,
+            Payload::Nodes(nodes) => Envelope::new(nodes),
+        };
+        if envelope.version > Self::VERSION {
+            return Err(WireError::UnsupportedVersion(envelope.version))
+        }
+        Ok(envelope)
+    }
+    pub fn to_json(&self) -> Result<String, WireError> {
+        serde_json::to_string(self).map_err(WireError::Json)
+    }
+}
+
+#[derive(Debug)]
+pub enum WireError {
+    Json(serde_json::Error),
+    /// Written by a newer version of this crate.
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for WireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Json(error) => error.fmt(f),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported wire schema version {version} (expected at most {})", Envelope::VERSION)
+            }
+        }
+    }
+}
+impl std::error::Error for WireError {}
+
+/// JSON Schema of [`Envelope`], nodes are discriminated by their `type` field.
+///
+/// The checked in copy lives at `schema/envelope.schema.json`, regenerate it with
+/// `cargo run -p markdown-format --example json_schema --features schema`.
+#[cfg(feature = "schema")]
+pub fn json_schema() -> schemars::schema::RootSchema {
+    schemars::schema_for!(Envelope)
+}
@@ -0,0 +1,150 @@
+use markdown_format::incremental::{ParsedDocument, TextEdit};
+use markdown_format::stream::StreamingParser;
+use markdown_format::{html, printer, Admonition, Node, ParseConfig};
+
+fn config() -> ParseConfig {
+    ParseConfig { admonition: true, ..ParseConfig::gfm() }
+}
+
+fn parse(source: &str) -> Vec<Node> {
+    markdown_format::parse_with(source, &config()).unwrap()
+}
+
+fn admonition(node: &Node) -> &Admonition {
+    let Node::Admonition(admonition) = node else { panic!("not an admonition: {node:?}") };
+    admonition
+}
+
+/// `(kind, title, text of the first paragraph)`.
+fn summary(admonition: &Admonition) -> (&str, Option<&str>, String) {
+    let text = match admonition.children.first() {
+        Some(Node::Paragraph(paragraph)) => paragraph
+            .children
+            .iter()
+            .map(|x| match x {
+                Node::Text(x) => x.value.as_str(),
+                _ => "",
+            })
+            .collect(),
+        _ => String::new(),
+    };
+    (admonition.kind.as_str(), admonition.title.as_deref(), text)
+}
+
+fn source_of<'a>(source: &'a str, node: &Node) -> &'a str {
+    let position = node.position().unwrap();
+    &source[position.start.offset..position.end.offset]
+}
+
+#[test]
+fn callouts() {
+    let source = "> [!NOTE]\n> Some text\n\n> [!Warning] Mind the gap\n>\n> Body\n\n> [!TIP]\n";
+    let nodes = parse(source);
+    assert_eq!(nodes.len(), 3);
+    assert_eq!(summary(admonition(&nodes[0])), ("note", None, "Some text".into()));
+    assert_eq!(summary(admonition(&nodes[1])), ("warning", Some("Mind the gap"), "Body".into()));
+    assert_eq!(summary(admonition(&nodes[2])), ("tip", None, String::new()));
+    assert_eq!(source_of(source, &nodes[0]), "> [!NOTE]\n> Some text");
+    let text = &admonition(&nodes[0]).children[0].children()[0];
+    assert_eq!(source_of(source, text), "Some text");
+}
+
+#[test]
+fn other_block_quotes_are_left_alone() {
+    for source in ["> [!NOTE] *x*\n> y\n", "> [!1] x\n", "> [NOTE]\n> x\n", "> text [!NOTE]\n"] {
+        assert!(matches!(parse(source)[..], [Node::Blockquote(_)]), "{source}");
+    }
+    let without = markdown_format::parse_with("> [!NOTE]\n> x\n", &ParseConfig::gfm()).unwrap();
+    assert!(matches!(without[..], [Node::Blockquote(_)]));
+}
+
+#[test]
+fn containers() {
+    let source = ":::warning Be careful\ncontent\n:::\n\n:::note[A title]\n\n- a\n- b\n\n:::\n\n:::danger\nunclosed\n";
+    let nodes = parse(source);
+    assert_eq!(nodes.len(), 3);
+    assert_eq!(summary(admonition(&nodes[0])), ("warning", Some("Be careful"), "content".into()));
+    assert_eq!(source_of(source, &nodes[0]), ":::warning Be careful\ncontent\n:::");
+    let note = admonition(&nodes[1]);
+    assert_eq!((note.kind.as_str(), note.title.as_deref()), ("note", Some("A title")));
+    assert!(matches!(note.children[..], [Node::List(_)]));
+    assert_eq!(summary(admonition(&nodes[2])), ("danger", None, "unclosed".into()));
+
+    // Inside other blocks, and a closing fence without an opening one.
+    let nodes = parse("- :::tip\n  x\n  :::\n\n:::\n");
+    let item = &nodes[0].children()[0];
+    assert_eq!(summary(admonition(&item.children()[0])), ("tip", None, "x".into()));
+    assert!(matches!(nodes[1], Node::Paragraph(_)));
+}
+
+#[test]
+fn nested_containers() {
+    let source = "::::info Parent\nParent content\n:::danger\nChild content\n:::\nAfter\n::::\n";
+    let nodes = parse(source);
+    assert_eq!(nodes.len(), 1);
+    let parent = admonition(&nodes[0]);
+    assert_eq!(summary(parent), ("info", Some("Parent"), "Parent content".into()));
+    assert_eq!(parent.children.len(), 3);
+    let child = admonition(&parent.children[1]);
+    assert_eq!(summary(child), ("danger", None, "Child content".into()));
+    assert_eq!(source_of(source, &parent.children[1]), ":::danger\nChild content\n:::");
+    assert_eq!(source_of(source, &parent.children[2]), "After");
+    assert_eq!(source_of(source, &nodes[0]), source.trim_end());
+
+    // Text around a fence in the same paragraph.
+    let nodes = parse("a *b*\n:::note\nc\n:::\nd\n");
+    assert_eq!(nodes.len(), 3);
+    assert_eq!(summary(admonition(&nodes[1])), ("note", None, "c".into()));
+}
+
+#[test]
+fn printing_and_html() {
+    let source = "> [!NOTE] Title\n> Body\n\n:::details-1\n\n:::tip\nInner\n:::\n\n:::\n";
+    let nodes = parse(source);
+    let printed = printer::print(&nodes, &Default::default());
+    assert_eq!(
+        printed,
+        "> [!NOTE] Title\n> Body\n\n::::details-1\n\n> [!TIP]\n> Inner\n\n::::\n",
+    );
+    let strip = |nodes: &[Node]| {
+        let mut value = serde_json::to_value(nodes).unwrap();
+        strip_positions(&mut value);
+        value
+    };
+    assert_eq!(strip(&parse(&printed)), strip(&nodes));
+
+    let rendered = html::render(&nodes[..1], &Default::default());
+    assert_eq!(
+        rendered,
+        "<div class=\"markdown-alert markdown-alert-note\">\n<p class=\"markdown-alert-title\">Title</p>\n<p>Body</p>\n</div>\n",
+    );
+}
+
+fn strip_positions(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            object.remove("position");
+            object.values_mut().for_each(strip_positions);
+        }
+        serde_json::Value::Array(array) => array.iter_mut().for_each(strip_positions),
+        _ => (),
+    }
+}
+
+#[test]
+fn incremental_and_streaming_match_full_parse() {
+    let source = "# T\n\n:::note\nfirst\n\nsecond\n:::\n\npara\n\n> [!TIP]\n> x\n";
+    let mut document = ParsedDocument::parse(source, config()).unwrap();
+    let edits = [(8..12, "warning"), (31..34, ""), (0..0, ":::danger\n"), (10..10, ":::\n\n")];
+    for (range, replacement) in edits {
+        let reparsed = document.apply(&TextEdit::new(range, replacement)).unwrap();
+        document = reparsed.document;
+        assert_eq!(document.nodes(), &parse(document.source())[..], "{:?}", document.source());
+    }
+
+    let mut parser = StreamingParser::new(config());
+    for chunk in source.split_inclusive('\n') {
+        parser.push(chunk).unwrap();
+    }
+    assert_eq!(parser.finish().unwrap(), parse(source));
+}
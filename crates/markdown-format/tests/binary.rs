@@ -0,0 +1,148 @@
+mod common;
+
+use markdown_format::binary::{self, BinaryDocument, BinaryError, Field};
+use markdown_format::visit::NodeKind;
+use markdown_format::{Node, ParseConfig};
+
+const EVERYTHING: &str = "---
+title: a
+---
+
+# Heading &amp; *emphasis* **strong** ~~gone~~
+
+> [!NOTE] Title
+> Body with $x^2$ and `code`.
+
+> Quote with a\\
+> hard break.
+
+- [x] done
+- [ ] todo
+
+3. three
+4. four
+
+| a | b | c |
+| :- | :-: | -: |
+| [link](/url \"title\") | ![alt](/image.png) | <b>html</b> |
+
+[full][ref], [ref][], [ref] and ![image][ref]. Footnote[^1] and [[Page#Heading|alias]] ![[embed.png|200]].
+
+[ref]: /reference 'Reference'
+[^1]: The *note*.
+
+```rust title=\"main\"
+fn main() {}
+```
+
+$$
+e = mc^2
+$$
+
+Term
+: Description
+
+***
+";
+
+fn samples() -> Vec<String> {
+    let mut samples = common::samples().into_iter().map(|(_, source)| source).collect::<Vec<_>>();
+    samples.push(EVERYTHING.to_owned());
+    samples
+}
+
+fn round_trip(nodes: &[Node], source: &str) {
+    let bytes = binary::encode(nodes, source).unwrap();
+    let document = BinaryDocument::new(&bytes, source).unwrap();
+    assert_eq!(document.decode(), nodes);
+}
+
+#[test]
+fn round_trips() {
+    let configs = [
+        ParseConfig::default(),
+        ParseConfig { unicode_offsets: true, highlight: true, ..ParseConfig::everything() },
+    ];
+    for source in samples() {
+        for config in &configs {
+            let mut nodes = markdown_format::parse_with(&source, config).unwrap();
+            round_trip(&nodes, &source);
+            markdown_format::resolve::resolve(&mut nodes);
+            round_trip(&nodes, &source);
+        }
+    }
+    let nodes = markdown_format::parse_with(EVERYTHING, &ParseConfig::everything()).unwrap();
+    let kinds = markdown_format::visit::depth_first(&nodes).map(Node::kind).collect::<std::collections::HashSet<_>>();
+    // All but TOML, a document has one frontmatter.
+    assert_eq!(kinds.len(), 33, "{kinds:?}");
+    round_trip(&[], "");
+}
+
+#[test]
+fn reads_in_place() {
+    let source = "# A *b*\n\n- [x] [c](d)\n";
+    let bytes = binary::encode(&markdown_format::parse_with(source, &ParseConfig::gfm()).unwrap(), source).unwrap();
+    let document = BinaryDocument::new(&bytes, source).unwrap();
+    assert_eq!((document.len(), document.roots()), (9, 0..2));
+    let heading = document.node(0).unwrap();
+    assert_eq!((heading.kind(), heading.parent()), (NodeKind::Heading, None));
+    assert_eq!(heading.heading_level(), Some(markdown_format::HeadingLevel::H1));
+    assert_eq!(heading.children(), 2..4);
+    let emphasis = document.node(3).unwrap();
+    assert_eq!((emphasis.kind(), emphasis.parent()), (NodeKind::Emphasis, Some(0)));
+    assert_eq!(emphasis.position().map(|x| (x.start.offset, x.end.offset)), Some((4, 7)));
+    let item = document.node(4).unwrap();
+    assert_eq!((item.kind(), item.checked(), item.spread()), (NodeKind::ListItem, Some(true), false));
+    let link = document.node(7).unwrap();
+    assert_eq!(link.kind(), NodeKind::Link);
+    assert_eq!((link.string(Field::Url), link.string(Field::Title)), (Some("d"), None));
+    assert!(document.node(9).is_none());
+}
+
+#[test]
+fn strings_reference_the_source() {
+    // Header, two nodes and one field, the text is in the source.
+    let bytes = binary::encode(&markdown_format::parse("abc").unwrap(), "abc").unwrap();
+    assert_eq!(bytes.len(), binary::HEADER_SIZE + 2 * binary::NODE_SIZE + binary::FIELD_SIZE);
+    // Decoded character references aren't.
+    let bytes = binary::encode(&markdown_format::parse("&lt;").unwrap(), "&lt;").unwrap();
+    assert_eq!(bytes.len(), binary::HEADER_SIZE + 2 * binary::NODE_SIZE + binary::FIELD_SIZE + "<".len());
+    // Neither are resolved URLs, which are pooled once: the paragraph, two
+    // references with an identifier, label and URL, three texts and the
+    // definition with a URL, identifier and label.
+    let source = "[a][x] [b][x]\n\n[x]: /url\n";
+    let mut nodes = markdown_format::parse(source).unwrap();
+    markdown_format::resolve::resolve(&mut nodes);
+    let bytes = binary::encode(&nodes, source).unwrap();
+    assert_eq!(bytes.len(), binary::HEADER_SIZE + 7 * binary::NODE_SIZE + 12 * binary::FIELD_SIZE + "/url".len());
+    assert_eq!(BinaryDocument::new(&bytes, source).unwrap().decode(), nodes);
+}
+
+#[test]
+fn rejects_invalid_buffers() {
+    let source = "# A *b*\n\n| a |\n| - |\n| `c` |\n";
+    let nodes = markdown_format::parse_with(source, &ParseConfig::gfm()).unwrap();
+    let bytes = binary::encode(&nodes, source).unwrap();
+    let error = |bytes: &[u8], source: &str| BinaryDocument::new(bytes, source).err();
+    assert_eq!(error(b"{}", source), Some(BinaryError::NotBinary));
+    assert_eq!(error(&bytes, "other"), Some(BinaryError::SourceMismatch));
+    let mut newer = bytes.clone();
+    newer[4] = 2;
+    assert_eq!(error(&newer, source), Some(BinaryError::UnsupportedVersion(2)));
+    assert!(matches!(error(&bytes[..bytes.len() - 1], source), Some(BinaryError::Malformed(_))));
+
+    // Every truncation and single bit flip is either rejected or decodes,
+    // without panicking.
+    for length in 0..bytes.len() {
+        assert!(BinaryDocument::new(&bytes[..length], source).is_err());
+    }
+    for index in 0..bytes.len() {
+        for bit in 0..8 {
+            let mut flipped = bytes.clone();
+            flipped[index] ^= 1 << bit;
+            if let Ok(document) = BinaryDocument::new(&flipped, source) {
+                document.decode();
+            }
+        }
+    }
+}
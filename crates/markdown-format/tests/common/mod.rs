@@ -0,0 +1,41 @@
+//! Helpers shared by the integration tests, the benchmarks and the fuzz targets,
+//! each of which uses only some of them.
+#![allow(dead_code)]
+use std::path::{Path, PathBuf};
+
+/// The documents in `crates/samples`, sorted. They are all parsed as
+/// Markdown, including the HTML one.
+pub fn sample_paths() -> Vec<PathBuf> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("../samples");
+    let mut paths = std::fs::read_dir(directory)
+        .unwrap()
+        .map(|x| x.unwrap().path())
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+/// The file name and contents of each sample.
+pub fn samples() -> Vec<(String, String)> {
+    sample_paths()
+        .into_iter()
+        .map(|path| {
+            let name = path.file_name().unwrap().to_string_lossy().into_owned();
+            (name, std::fs::read_to_string(&path).unwrap())
+        })
+        .collect()
+}
+
+/// The JSON of nodes without their `position`s, for comparing trees parsed
+/// from different sources.
+pub fn without_positions(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(object) => object
+            .into_iter()
+            .filter(|(key, _)| key != "position")
+            .map(|(key, value)| (key, without_positions(value)))
+            .collect(),
+        serde_json::Value::Array(array) => array.into_iter().map(without_positions).collect(),
+        value => value,
+    }
+}
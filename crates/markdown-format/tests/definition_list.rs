@@ -0,0 +1,139 @@
+use markdown_format::incremental::{ParsedDocument, TextEdit};
+use markdown_format::plain_text::to_plain_text;
+use markdown_format::{html, printer, DefinitionList, Node, ParseConfig};
+
+fn config() -> ParseConfig {
+    ParseConfig { definition_list: true, ..ParseConfig::gfm() }
+}
+
+fn parse(source: &str) -> Vec<Node> {
+    markdown_format::parse_with(source, &config()).unwrap()
+}
+
+fn list(node: &Node) -> &DefinitionList {
+    let Node::DefinitionList(list) = node else { panic!("not a definition list: {node:?}") };
+    list
+}
+
+/// `("term" | "description", plain text)` of each child.
+fn summary(list: &DefinitionList) -> Vec<(&'static str, String)> {
+    list.children
+        .iter()
+        .map(|x| {
+            let kind = match x {
+                Node::DefinitionTerm(_) => "term",
+                Node::DefinitionDescription(_) => "description",
+                _ => panic!("unexpected child: {x:?}"),
+            };
+            (kind, to_plain_text(std::slice::from_ref(x), &Default::default()).text)
+        })
+        .collect()
+}
+
+fn source_of<'a>(source: &'a str, node: &Node) -> &'a str {
+    let position = node.position().unwrap();
+    &source[position.start.offset..position.end.offset]
+}
+
+#[test]
+fn tight_lists() {
+    let source = "Apple\nPear\n: *Fruits*, lines without a marker\ncontinue it.\n: A second description.\n";
+    let nodes = parse(source);
+    assert_eq!(nodes.len(), 1);
+    let list = list(&nodes[0]);
+    assert!(!list.spread);
+    assert_eq!(
+        summary(list),
+        [
+            ("term", "Apple".into()),
+            ("term", "Pear".into()),
+            ("description", "Fruits, lines without a marker\ncontinue it.".into()),
+            ("description", "A second description.".into()),
+        ],
+    );
+    assert_eq!(source_of(source, &nodes[0]), source.trim_end());
+    assert_eq!(source_of(source, &list.children[2]), ": *Fruits*, lines without a marker\ncontinue it.");
+    let paragraph = &list.children[3].children()[0];
+    assert_eq!(source_of(source, paragraph), "A second description.");
+}
+
+#[test]
+fn spread_lists() {
+    let source = "Term\n\n: First\n\n: Second\n\nOther term\n: Third\n";
+    let nodes = parse(source);
+    assert_eq!(nodes.len(), 1);
+    let list = list(&nodes[0]);
+    assert!(list.spread);
+    assert_eq!(
+        summary(list),
+        [
+            ("term", "Term".into()),
+            ("description", "First".into()),
+            ("description", "Second".into()),
+            ("term", "Other term".into()),
+            ("description", "Third".into()),
+        ],
+    );
+}
+
+#[test]
+fn other_paragraphs_are_left_alone() {
+    for source in [": no term\n", "a\n\\: escaped\n", "a\n:no space\n", "a\n:\n", "# a\n: b\n", "> a\n\n: b\n"] {
+        assert!(!parse(source).iter().any(|x| matches!(x, Node::DefinitionList(_))), "{source}");
+    }
+    let without = markdown_format::parse_with("a\n: b\n", &ParseConfig::gfm()).unwrap();
+    assert!(matches!(without[..], [Node::Paragraph(_)]));
+
+    // Inside other blocks.
+    let nodes = parse("- a\n  : b\n");
+    let item = &nodes[0].children()[0];
+    assert_eq!(summary(list(&item.children()[0])), [("term", "a".into()), ("description", "b".into())]);
+}
+
+#[test]
+fn printing_html_and_plain_text() {
+    let source = "Term *a*\nTerm b\n: One\n  two\n: Three\n\n***\n\nLoose\n\n: Four\n: Five\n";
+    let nodes = parse(source);
+    assert_eq!(nodes.len(), 3);
+    let printed = printer::print(&nodes, &Default::default());
+    assert_eq!(printed, "Term *a*\nTerm b\n: One\n    two\n: Three\n\n***\n\nLoose\n\n: Four\n\n: Five\n");
+    let strip = |nodes: &[Node]| {
+        let mut value = serde_json::to_value(nodes).unwrap();
+        strip_positions(&mut value);
+        value
+    };
+    assert_eq!(strip(&parse(&printed)), strip(&nodes));
+
+    assert_eq!(
+        html::render(&nodes[..1], &Default::default()),
+        "<dl>\n<dt>Term <em>a</em></dt>\n<dt>Term b</dt>\n<dd>One\ntwo</dd>\n<dd>Three</dd>\n</dl>\n",
+    );
+    assert_eq!(to_plain_text(&nodes[..1], &Default::default()).text, "Term a\nTerm b\nOne\ntwo\nThree");
+
+    // A colon that would start a description is escaped.
+    let nodes = parse("a\n\\: b\n");
+    assert_eq!(printer::print(&nodes, &Default::default()), "a\n\\: b\n");
+}
+
+fn strip_positions(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(object) => {
+            object.remove("position");
+            object.values_mut().for_each(strip_positions);
+        }
+        serde_json::Value::Array(array) => array.iter_mut().for_each(strip_positions),
+        _ => (),
+    }
+}
+
+#[test]
+fn incremental_edits_match_full_parse() {
+    let source = "Term\n: a\n\nOther\n\npara\n";
+    let mut document = ParsedDocument::parse(source, config()).unwrap();
+    let edits = [(15..15, "\n: b"), (9..9, ": c\n\n"), (0..4, "New term"), (5..6, "")];
+    for (range, replacement) in edits {
+        let reparsed = document.apply(&TextEdit::new(range, replacement)).unwrap();
+        document = reparsed.document;
+        assert_eq!(document.nodes(), &parse(document.source())[..], "{:?}", document.source());
+    }
+}
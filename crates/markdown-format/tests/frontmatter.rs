@@ -0,0 +1,54 @@
+use markdown_format::frontmatter::{Frontmatter, FrontmatterFormat};
+
+fn find(source: &str) -> Frontmatter {
+    Frontmatter::find(&markdown_format::parse(source).unwrap()).unwrap()
+}
+
+#[test]
+fn values_and_typed_deserialization() {
+    let yaml = find("---\ntitle: Neptune\ndraft: false\ntags:\n  - ice\n  - giant\n---\n# Body\n");
+    assert_eq!(yaml.format, FrontmatterFormat::Yaml);
+    assert_eq!(yaml.value().unwrap(), serde_json::json!({
+        "title": "Neptune",
+        "draft": false,
+        "tags": ["ice", "giant"],
+    }));
+
+    let toml = find("+++\ntitle = \"Neptune\"\ndate = 1846-09-23\n[orbit]\nperiod = 164.8\n+++\n");
+    assert_eq!(toml.format, FrontmatterFormat::Toml);
+    assert_eq!(toml.value().unwrap(), serde_json::json!({
+        "title": "Neptune",
+        "date": "1846-09-23",
+        "orbit": {"period": 164.8},
+    }));
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Metadata {
+        title: String,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+    let metadata: Metadata = yaml.deserialize().unwrap();
+    assert_eq!(metadata, Metadata { title: "Neptune".into(), tags: vec!["ice".into(), "giant".into()] });
+    assert!(toml.deserialize::<Metadata>().is_ok());
+    assert_eq!(find("---\n---\n").value().unwrap(), serde_json::json!({}));
+
+    assert!(Frontmatter::find(&markdown_format::parse("# Title\n\n---\na: 1\n---\n").unwrap()).is_none());
+}
+
+#[test]
+fn errors_point_into_the_document() {
+    let cases = [
+        ("---\ntitle: ok\nbroken: [1, 2\n---\n", (3, 14, 27)),
+        ("---\na: 1\nb: [x\n  y\n---\n", (4, 4, 18)),
+        ("---\r\na: 1\r\nb: [\r\n---\r\n", (3, 5, 15)),
+        ("+++\ntitle = \"ok\"\nperiod = = 1\n+++\n", (3, 10, 26)),
+    ];
+    for (source, expected) in cases {
+        let error = find(source).value().unwrap_err();
+        let point = error.point.as_ref().unwrap();
+        assert_eq!((point.line, point.column, point.offset), expected, "{error}");
+    }
+    let error = find("+++\na = [\n+++\n").deserialize::<serde_json::Value>().unwrap_err();
+    assert!(error.to_string().starts_with("invalid TOML frontmatter at 2:"), "{error}");
+}
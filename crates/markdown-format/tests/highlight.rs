@@ -0,0 +1,72 @@
+#![cfg(feature = "highlight")]
+use markdown_format::{highlight, Node, ParseConfig};
+
+/// `(text, scope)` of the spans.
+fn tokens<'a>(code: &'a str, lang: &str) -> Vec<(&'a str, String)> {
+    highlight::highlight(code, lang)
+        .unwrap()
+        .into_iter()
+        .map(|x| (&code[x.range], x.scope))
+        .collect()
+}
+
+fn has(tokens: &[(&str, String)], text: &str, scope: &str) -> bool {
+    tokens.iter().any(|(x, y)| *x == text && y.starts_with(scope))
+}
+
+#[test]
+fn common_languages() {
+    let rust = tokens("fn main() {\n    let s = \"hi\"; // done\n}\n", "rust");
+    assert!(has(&rust, "fn", "storage.type"));
+    assert!(has(&rust, "main", "entity.name.function"));
+    assert!(has(&rust, "\"hi\"", "string.quoted.double"));
+    assert!(has(&rust, "// done\n", "comment.line"));
+
+    let python = tokens("def f(x):\n    return 'a' + 1\n", "py");
+    assert!(has(&python, "return", "keyword.control"));
+    assert!(has(&python, "1", "constant.numeric"));
+
+    let json = tokens("{\"a\": [true]}", "json");
+    assert!(has(&json, "true", "constant.language"));
+    assert!(has(&tokens("echo $HOME", "sh"), "HOME", "variable"));
+    assert!(has(&tokens("const a = 1;", "JavaScript"), "const", "storage.type"));
+}
+
+#[test]
+fn spans_are_ordered_and_disjoint() {
+    let code = "fn f<'a>(x: &'a str) -> String {\n    format!(\"{x}\\n\")\n}\n\n/* é 漢字 */\n";
+    let spans = highlight::highlight(code, "rs").unwrap();
+    assert!(!spans.is_empty());
+    for pair in spans.windows(2) {
+        assert!(pair[0].range.end <= pair[1].range.start, "{pair:?}");
+    }
+    assert!(spans.iter().all(|x| !x.range.is_empty() && code.get(x.range.clone()).is_some()));
+}
+
+#[test]
+fn unknown_languages() {
+    for lang in ["", "text", "no-such-language"] {
+        assert_eq!(highlight::highlight("x", lang), None, "{lang}");
+        assert!(!highlight::is_supported(lang));
+    }
+    assert!(highlight::is_supported("yml"));
+}
+
+#[test]
+fn parsing_with_highlights() {
+    let source = "```rust\nlet x = 1;\n```\n\n> ```\n> plain\n> ```\n\n```nope\nx\n```\n";
+    let config = ParseConfig { highlight: true, ..ParseConfig::default() };
+    let nodes = markdown_format::parse_with(source, &config).unwrap();
+    let Node::CodeBlock(code) = &nodes[0] else { panic!() };
+    assert_eq!(code.highlights, highlight::highlight(&code.value, "rust"));
+    let Node::CodeBlock(plain) = &nodes[1].children()[0] else { panic!() };
+    assert_eq!(plain.highlights, None);
+    let Node::CodeBlock(unknown) = &nodes[2] else { panic!() };
+    assert_eq!(unknown.highlights, None);
+
+    let json = serde_json::to_value(&nodes).unwrap();
+    assert_eq!(json[0]["highlights"][0], serde_json::json!({"range": {"start": 0, "end": 3}, "scope": "storage.type.rust"}));
+    assert!(json[2].get("highlights").is_none());
+    let without = serde_json::to_value(markdown_format::parse(source).unwrap()).unwrap();
+    assert!(without[0].get("highlights").is_none());
+}
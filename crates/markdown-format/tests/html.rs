@@ -0,0 +1,107 @@
+use markdown_format::html::{self, HtmlOptions};
+use markdown_format::visit::NodeKind;
+use markdown_format::{Node, ParseConfig};
+
+fn render(source: &str, options: &HtmlOptions) -> String {
+    html::render(&markdown_format::parse_with(source, &ParseConfig::everything()).unwrap(), options)
+}
+
+#[test]
+fn safe_mode_drops_html_and_dangerous_urls() {
+    let source = "<script>alert(1)</script>\n\na <b>b</b> [c](javascript:alert(1)) [d](JavaScript:x) [e](https://e.com) [f](/f)\n\n![g](data:text/html,x)\n";
+    assert_eq!(
+        render(source, &HtmlOptions::default()),
+        "<script>alert(1)</script>\n\
+         <p>a <b>b</b> <a href=\"javascript:alert(1)\">c</a> <a href=\"JavaScript:x\">d</a> <a href=\"https://e.com\">e</a> <a href=\"/f\">f</a></p>\n\
+         <p><img src=\"data:text/html,x\" alt=\"g\" /></p>\n",
+    );
+    let safe = HtmlOptions { safe: true, ..Default::default() };
+    assert_eq!(
+        render(source, &safe),
+        "<p>a b <a href=\"\">c</a> <a href=\"\">d</a> <a href=\"https://e.com\">e</a> <a href=\"/f\">f</a></p>\n\
+         <p><img src=\"\" alt=\"g\" /></p>\n",
+    );
+}
+
+#[test]
+fn class_prefixes() {
+    let options = HtmlOptions { class_prefix: String::from("md-"), ..Default::default() };
+    assert_eq!(
+        render("```rust\nfn main() {}\n```\n\n- [x] a\n", &options),
+        "<pre><code class=\"md-language-rust\">fn main() {}\n</code></pre>\n\
+         <ul class=\"md-contains-task-list\">\n\
+         <li class=\"md-task-list-item\"><input type=\"checkbox\" disabled=\"\" checked=\"\" /> a</li>\n\
+         </ul>\n",
+    );
+}
+
+#[test]
+fn class_prefixes_are_escaped() {
+    let options = HtmlOptions { class_prefix: String::from("x\"><script>"), safe: true, ..Default::default() };
+    let rendered = render("> [!NOTE]\n> a\n\n- [ ] b\n\n$c$\n", &options);
+    assert!(!rendered.contains("<script>"), "{rendered}");
+    assert!(rendered.starts_with(
+        "<div class=\"x&quot;&gt;&lt;script&gt;markdown-alert x&quot;&gt;&lt;script&gt;markdown-alert-note\">\n\
+         <p class=\"x&quot;&gt;&lt;script&gt;markdown-alert-title\">Note</p>\n",
+    ), "{rendered}");
+}
+
+#[test]
+fn footnotes_are_numbered_by_first_reference() {
+    let options = HtmlOptions { id_prefix: String::from("x-"), ..Default::default() };
+    assert_eq!(
+        render("a[^b] c[^d] e[^b]\n\n[^d]: D\n[^b]: B\n[^unused]: U\n", &options),
+        "<p>a<sup><a href=\"#x-fn-b\" id=\"x-fnref-b\" data-footnote-ref=\"\" aria-describedby=\"footnote-label\">1</a></sup> \
+         c<sup><a href=\"#x-fn-d\" id=\"x-fnref-d\" data-footnote-ref=\"\" aria-describedby=\"footnote-label\">2</a></sup> \
+         e<sup><a href=\"#x-fn-b\" id=\"x-fnref-b-2\" data-footnote-ref=\"\" aria-describedby=\"footnote-label\">1</a></sup></p>\n\
+         <section data-footnotes=\"\" class=\"footnotes\"><h2 id=\"footnote-label\" class=\"sr-only\">Footnotes</h2>\n\
+         <ol>\n\
+         <li id=\"x-fn-b\">\n\
+         <p>B <a href=\"#x-fnref-b\" data-footnote-backref=\"\" aria-label=\"Back to content\" class=\"data-footnote-backref\">↩</a> \
+         <a href=\"#x-fnref-b-2\" data-footnote-backref=\"\" aria-label=\"Back to content\" class=\"data-footnote-backref\">↩<sup>2</sup></a></p>\n\
+         </li>\n\
+         <li id=\"x-fn-d\">\n\
+         <p>D <a href=\"#x-fnref-d\" data-footnote-backref=\"\" aria-label=\"Back to content\" class=\"data-footnote-backref\">↩</a></p>\n\
+         </li>\n\
+         </ol>\n\
+         </section>\n",
+    );
+}
+
+#[test]
+fn task_list_checkboxes() {
+    assert_eq!(
+        render("- [x] done\n- [ ] todo\n- plain\n", &HtmlOptions::default()),
+        "<ul class=\"contains-task-list\">\n\
+         <li class=\"task-list-item\"><input type=\"checkbox\" disabled=\"\" checked=\"\" /> done</li>\n\
+         <li class=\"task-list-item\"><input type=\"checkbox\" disabled=\"\" /> todo</li>\n\
+         <li>plain</li>\n\
+         </ul>\n",
+    );
+}
+
+#[test]
+fn table_alignment() {
+    assert_eq!(
+        render("| a | b | c | d |\n| :- | :-: | -: | - |\n| 1 | 2 | 3 | 4 |\n", &HtmlOptions::default()),
+        "<table>\n<thead>\n<tr>\n\
+         <th align=\"left\">a</th>\n<th align=\"center\">b</th>\n<th align=\"right\">c</th>\n<th>d</th>\n\
+         </tr>\n</thead>\n<tbody>\n<tr>\n\
+         <td align=\"left\">1</td>\n<td align=\"center\">2</td>\n<td align=\"right\">3</td>\n<td>4</td>\n\
+         </tr>\n</tbody>\n</table>\n",
+    );
+}
+
+/// Emphasis and inline math used to be parsed as `Strikethrough` and
+/// `InlineCode` nodes.
+#[test]
+fn emphasis_and_inline_math_have_their_own_nodes() {
+    let nodes = markdown_format::parse_with("*a* ~~b~~ $c$ `d`", &ParseConfig::everything()).unwrap();
+    let Node::Paragraph(paragraph) = &nodes[0] else { panic!("{nodes:?}") };
+    let kinds = paragraph.children.iter().filter(|x| !matches!(x, Node::Text(_))).map(Node::kind).collect::<Vec<_>>();
+    assert_eq!(kinds, [NodeKind::Emphasis, NodeKind::Strikethrough, NodeKind::InlineMath, NodeKind::InlineCode]);
+    assert_eq!(
+        html::render(&nodes, &HtmlOptions::default()),
+        "<p><em>a</em> <del>b</del> <code class=\"language-math math-inline\">c</code> <code>d</code></p>\n",
+    );
+}
@@ -0,0 +1,119 @@
+//! Incremental reparsing must agree with parsing the edited source from scratch.
+mod common;
+
+use markdown_format::ParseConfig;
+use markdown_format::incremental::{ParsedDocument, TextEdit};
+
+/// Snippets that tend to change how neighbouring blocks parse.
+const INSERTIONS: &[&str] = &[
+    "\n", "\n\n", "x", "# ", "===\n", "---\n", "```\n", "~~~", "$$\n", "> ", "- ", "1. ",
+    "    ", "*", "**", "`", "|", "| a | b |\n| - | - |\n", "<div>\n", "[a]", "[a]: /url\n",
+    "[^1]: note\n", "[^1]", "\\", "ü", "😀", ":::note\n", "> [!NOTE]\n", ": ", "[[",
+];
+
+/// Deterministic pseudo random numbers (64-bit LCG).
+struct Random(u64);
+
+impl Random {
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 = self.0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound.max(1)
+    }
+}
+
+fn char_boundary(source: &str, mut index: usize) -> usize {
+    while !source.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn random_edit(source: &str, random: &mut Random) -> TextEdit {
+    let start = char_boundary(source, random.below(source.len() + 1));
+    let end = match random.below(3) {
+        0 => start,
+        _ => char_boundary(source, (start + random.below(16)).min(source.len())),
+    };
+    let replacement = match random.below(4) {
+        0 => "",
+        _ => INSERTIONS[random.below(INSERTIONS.len())],
+    };
+    TextEdit::new(start..end, replacement)
+}
+
+/// Reused blocks after the edit have their positions shifted.
+fn without_positions(node: &markdown_format::Node) -> serde_json::Value {
+    common::without_positions(serde_json::to_value(node).unwrap())
+}
+
+fn assert_edits_match_full_reparse(source: &str, config: ParseConfig, name: &str) {
+    let mut random = Random(source.len() as u64);
+    let mut document = ParsedDocument::parse(source, config.clone()).unwrap();
+    for _ in 0..100 {
+        let edit = random_edit(document.source(), &mut random);
+        let reparsed = document.apply(&edit).unwrap();
+        let expected = markdown_format::parse_with(reparsed.document.source(), &config).unwrap();
+        assert!(
+            reparsed.document.nodes() == expected,
+            "{name}: {edit:?} applied to\n{}\ndiffers from a full reparse",
+            document.source(),
+        );
+        let previous = document.nodes().iter().map(without_positions).collect::<Vec<_>>();
+        for (index, node) in reparsed.document.nodes().iter().enumerate() {
+            if !reparsed.changed.contains(&index) {
+                assert!(previous.contains(&without_positions(node)), "{name}: block {index} is not reused");
+            }
+        }
+        document = reparsed.document;
+    }
+}
+
+#[test]
+fn random_edits_match_full_reparse() {
+    for (name, source) in common::samples() {
+        assert_edits_match_full_reparse(&source, ParseConfig::default(), &name);
+        assert_edits_match_full_reparse(&source, ParseConfig::everything(), &name);
+    }
+}
+
+#[test]
+fn unaffected_blocks_are_reused() {
+    let source = "# Title\n\nFirst paragraph.\n\n- a\n- b\n\nLast paragraph.\n";
+    let document = ParsedDocument::parse(source, ParseConfig::default()).unwrap();
+    let start = source.find("First").unwrap();
+    let reparsed = document.apply(&TextEdit::new(start..start + 5, "Second")).unwrap();
+    assert_eq!(reparsed.changed, vec![1]);
+    assert_eq!(reparsed.document.nodes()[0], document.nodes()[0]);
+    assert_eq!(reparsed.document.nodes()[2..], markdown_format::parse(reparsed.document.source()).unwrap()[2..]);
+}
+
+#[test]
+fn unclosed_fence_swallows_the_rest() {
+    let source = "a\n\nb\n\nc\n\nd\n";
+    let document = ParsedDocument::parse(source, ParseConfig::default()).unwrap();
+    let reparsed = document.apply(&TextEdit::new(3..3, "```\n")).unwrap();
+    assert_eq!(reparsed.document.nodes().len(), 2);
+    assert_eq!(reparsed.changed, vec![1]);
+}
+
+#[test]
+fn frontmatter_opened_on_the_first_line() {
+    let source = "a\n\nb\n\n---\ntitle: x\n---\n";
+    for config in [ParseConfig::default(), ParseConfig::everything()] {
+        let document = ParsedDocument::parse(source, config.clone()).unwrap();
+        for range in [0..0, 0..1, 0..2] {
+            let reparsed = document.apply(&TextEdit::new(range.clone(), "---\n")).unwrap();
+            let expected = markdown_format::parse_with(reparsed.document.source(), &config).unwrap();
+            assert_eq!(reparsed.document.nodes(), expected, "{range:?}");
+        }
+    }
+}
+
+#[test]
+fn invalid_edits_are_rejected() {
+    let document = ParsedDocument::parse("ü", ParseConfig::default()).unwrap();
+    assert!(document.apply(&TextEdit::new(1..2, "")).is_err());
+    assert!(document.apply(&TextEdit::new(0..3, "")).is_err());
+}
@@ -0,0 +1,113 @@
+use markdown_format::incremental::{ParsedDocument, TextEdit};
+use markdown_format::stream::StreamingParser;
+use markdown_format::{Error, Limit, LimitError, ParseConfig, ParseLimits};
+
+fn config(limits: ParseLimits) -> ParseConfig {
+    ParseConfig { limits, ..ParseConfig::gfm() }
+}
+
+fn limit_error(result: Result<impl std::fmt::Debug, Error>) -> LimitError {
+    match result {
+        Err(Error::LimitExceeded(error)) => error,
+        result => panic!("not a limit error: {result:?}"),
+    }
+}
+
+#[test]
+fn depth() {
+    // Block quote, paragraph and text.
+    let at_limit = format!("{} a\n", ">".repeat(ParseLimits::DEFAULT_MAX_DEPTH - 2));
+    assert!(markdown_format::parse(&at_limit).is_ok());
+    let error = limit_error(markdown_format::parse(format!(">{at_limit}")));
+    assert_eq!((error.limit, error.maximum), (Limit::Depth, ParseLimits::DEFAULT_MAX_DEPTH));
+    assert_eq!(error.to_string(), "nodes nest deeper than the limit of 128 at 1:129");
+
+    // Far deeper than the recursive parts of the crate could handle.
+    for source in [">".repeat(10_000), format!("{}a", "- ".repeat(1_000)), format!("{}a{}", "*a ".repeat(1_000), " b*".repeat(1_000))] {
+        assert_eq!(limit_error(markdown_format::parse(&source)).limit, Limit::Depth);
+    }
+
+    // Rejected before parsing, unless it's code.
+    let error = limit_error(markdown_format::parse(format!("a\n\n{}", "> ".repeat(200))));
+    assert_eq!(error.to_string(), "nodes nest deeper than the limit of 128 at 3:257");
+    assert!(markdown_format::parse(format!("```\n{}\n```\n", ">".repeat(200))).is_ok());
+
+    let limits = ParseLimits { max_depth: Some(4), ..ParseLimits::none() };
+    assert!(markdown_format::parse_with("- *a*", &config(limits)).is_err());
+    assert!(markdown_format::parse_with("- a", &config(limits)).is_ok());
+    assert!(markdown_format::parse_with(">".repeat(500), &config(ParseLimits::none())).is_ok());
+}
+
+/// The tree of the `markdown` crate is as deep as the input, it has to be
+/// dropped without recursing as well.
+#[test]
+fn depth_on_a_small_stack() {
+    let parse = |source: String, stack_size| {
+        let thread = std::thread::Builder::new().stack_size(stack_size).spawn(move || {
+            limit_error(markdown_format::parse_with(&source, &ParseConfig::default())).limit
+        });
+        thread.unwrap().join().unwrap()
+    };
+    assert_eq!(parse(">".repeat(50_000) + " a", 2 << 20), Limit::Depth);
+    // Past the lines checked before parsing, on a stack the recursive drop
+    // would overflow.
+    assert_eq!(parse(format!("$\n\n{} a", ">".repeat(10_000)), 256 << 10), Limit::Depth);
+}
+
+#[test]
+fn table_cells() {
+    let source = "Intro\n\n| a | b | c |\n| - | - | - |\n| 1 | 2 |\n| 3 | 4 | 5 | 6 |\n";
+    // Rows are neither padded nor cut to the header: 3 + 2 + 4 cells.
+    let limits = ParseLimits { max_table_cells: Some(9), ..ParseLimits::none() };
+    assert!(markdown_format::parse_with(source, &config(limits)).is_ok());
+    let limits = ParseLimits { max_table_cells: Some(8), ..ParseLimits::none() };
+    let error = limit_error(markdown_format::parse_with(source, &config(limits)));
+    assert_eq!(error.to_string(), "table exceeds the limit of 8 cells at 3:1");
+}
+
+#[test]
+fn input_size_and_nodes() {
+    let limits = ParseLimits { max_input_bytes: Some(5), ..ParseLimits::none() };
+    assert!(markdown_format::parse_with("a *b*", &config(limits)).is_ok());
+    let error = limit_error(markdown_format::parse_with("a *b*c", &config(limits)));
+    assert_eq!((error.limit, &error.position), (Limit::InputBytes, &None));
+    assert_eq!(error.to_string(), "input exceeds the limit of 5 bytes");
+
+    let limits = ParseLimits { max_nodes: Some(4), ..ParseLimits::none() };
+    // Paragraph, text, emphasis and text.
+    assert!(markdown_format::parse_with("a *b*", &config(limits)).is_ok());
+    let error = limit_error(markdown_format::parse_with("a *b* c", &config(limits)));
+    assert_eq!((error.limit, &error.position), (Limit::Nodes, &None));
+    assert_eq!(error.to_string(), "document exceeds the limit of 4 nodes");
+}
+
+#[test]
+fn incremental_and_streaming() {
+    let limits = ParseLimits { max_input_bytes: Some(12), max_depth: Some(3), max_nodes: Some(6), max_table_cells: None };
+    let document = ParsedDocument::parse("a\n\nb\n", config(limits)).unwrap();
+    assert!(document.apply(&TextEdit::new(3..4, "c")).is_ok());
+    let error = limit_error(document.apply(&TextEdit::new(3..4, "b\n\nc\n\nd")));
+    assert_eq!(error.limit, Limit::Nodes);
+    let error = limit_error(document.apply(&TextEdit::new(3..4, "b c d e f g")));
+    assert_eq!(error.limit, Limit::InputBytes);
+    let error = limit_error(document.apply(&TextEdit::new(0..1, "> > a")));
+    assert_eq!(error.limit, Limit::Depth);
+
+    let mut parser = StreamingParser::new(config(limits));
+    parser.push("a\n\nb\n\n").unwrap();
+    parser.push("c").unwrap();
+    assert_eq!(limit_error(parser.clone().push("\n\nd")).limit, Limit::Nodes);
+    assert_eq!(limit_error(parser.push(" d e f g")).limit, Limit::InputBytes);
+}
+
+/// The `markdown` crate panics on this one ("Cannot push to non-parent").
+#[test]
+fn parser_panics_are_errors() {
+    let source = "a\n\nb\n---\n---\ntitle: x\n---\n";
+    for config in [ParseConfig::commonmark(), ParseConfig::gfm(), ParseConfig::default(), ParseConfig::everything()] {
+        match markdown_format::parse_with(source, &config) {
+            Err(error @ Error::Internal { .. }) => assert!(error.to_string().starts_with("internal parser error: ")),
+            result => panic!("not an internal error: {result:?}"),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use markdown_format::lint::{self, Context, Diagnostic, Finding, LintConfig, Linter, ListMarkerStyle, Rule, Severity};
+use markdown_format::{Node, ParseConfig};
+
+fn check(source: &str) -> Vec<Diagnostic> {
+    let nodes = markdown_format::parse_with(source, &ParseConfig::gfm()).unwrap();
+    lint::lint(source, &nodes)
+}
+
+/// `(rule, line, column, source)` of the diagnostics.
+fn found(source: &str) -> Vec<(String, usize, usize, &str)> {
+    check(source)
+        .into_iter()
+        .map(|x| {
+            let position = x.position.unwrap();
+            let text = &source[position.start.offset..position.end.offset];
+            (x.rule, position.start.line, position.start.column, text)
+        })
+        .collect()
+}
+
+fn rules(source: &str) -> Vec<String> {
+    check(source).into_iter().map(|x| x.rule).collect()
+}
+
+#[test]
+fn headings() {
+    let source = "## Start\n\n#### Skipped\n\n### Back\n\n# Setup\n\n> ## Setup\n";
+    assert_eq!(found(source), [
+        ("heading-increment".to_owned(), 3, 1, "#### Skipped"),
+        ("duplicate-heading-slug".to_owned(), 9, 3, "## Setup"),
+    ]);
+    let diagnostics = check(source);
+    assert_eq!(diagnostics[0].message, "heading level 4 after level 2, expected 3");
+    assert_eq!(diagnostics[1].to_string(), "heading slug `setup` is already used by the heading at line 7 (duplicate-heading-slug) at 9:3");
+    assert!(check("# A\n\n## B\n\n# C\n\n## D\n").is_empty());
+}
+
+#[test]
+fn links_and_images() {
+    let source = "[](/a) [b]() [c](#) [![logo](x.png)](/) ![](y.png) ![ ][z]\n\n[z]: /z\n";
+    assert_eq!(rules(source), ["empty-link", "empty-link", "empty-link", "image-alt", "image-alt"]);
+    let messages = check(source).into_iter().map(|x| x.message).collect::<Vec<_>>();
+    assert_eq!(&messages[..3], ["link without text", "link without destination", "link without destination"]);
+}
+
+#[test]
+fn bare_urls() {
+    let source = "See https://example.com/a?b=1. And <https://ok.com>, [https://ok.com](https://ok.com).\n\n`https://code.com`\n";
+    assert_eq!(found(source), [("bare-url".to_owned(), 1, 5, "https://example.com/a?b=1")]);
+    let commonmark = "Go to http://x.org\n";
+    let nodes = markdown_format::parse(commonmark).unwrap();
+    let diagnostics = lint::lint(commonmark, &nodes);
+    assert_eq!(diagnostics[0].message, "bare URL `http://x.org`, use `<http://x.org>`");
+    assert_eq!(diagnostics[0].position.as_ref().unwrap().start.column, 7);
+    assert!(check("xhttps://no.com and https:// alone\n").is_empty());
+}
+
+/// Each occurrence gets its own range, with Unicode offsets when the nodes
+/// have them.
+#[test]
+fn repeated_pieces_and_unicode_offsets() {
+    let source = "é http://a.com [x][] http://a.com [x][]\n";
+    let config = ParseConfig { unicode_offsets: true, ..ParseConfig::gfm() };
+    let nodes = markdown_format::parse_with(source, &config).unwrap();
+    let starts = lint::lint(source, &nodes)
+        .into_iter()
+        .map(|x| {
+            let start = x.position.unwrap().start;
+            (x.rule, start.offset, start.utf16_offset, start.scalar_offset)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(starts, [
+        ("bare-url".to_owned(), 3, Some(2), Some(2)),
+        ("undefined-reference".to_owned(), 16, Some(15), Some(15)),
+        ("bare-url".to_owned(), 22, Some(21), Some(21)),
+        ("undefined-reference".to_owned(), 35, Some(34), Some(34)),
+    ]);
+
+    let context = Context { source, nodes: &nodes };
+    assert_eq!(context.range(0..3).unwrap().end.utf16_offset, Some(2));
+    assert!(context.range(1..2).is_none());
+    assert!(context.range(3..100).is_none());
+}
+
+#[test]
+fn list_markers() {
+    let source = "- a\n- b\n\n* c\n\n1. d\n\n+ e\n  - f\n";
+    assert_eq!(found(source).into_iter().map(|x| (x.1, x.3.chars().next().unwrap())).collect::<Vec<_>>(), [(4, '*'), (8, '+')]);
+    let nodes = markdown_format::parse(source).unwrap();
+    let mut linter = Linter::empty();
+    linter.add(ListMarkerStyle { marker: Some('*') });
+    let lines = linter.lint(source, &nodes).into_iter().map(|x| x.position.unwrap().start.line).collect::<Vec<_>>();
+    assert_eq!(lines, [1, 8, 9]);
+}
+
+#[test]
+fn hard_breaks() {
+    let source = "a  \nb\\\nc\n\n    code  \n    block\n";
+    assert_eq!(found(source), [("hard-break-spaces".to_owned(), 1, 2, "  \n")]);
+}
+
+#[test]
+fn undefined_references() {
+    let source = "[a][missing] [b][] [c][d] \\[e\\][f] [^nope] [^ok]\n\n[d]: /d\n\n[^ok]: Fine.\n";
+    let found = found(source);
+    let pieces = found.iter().map(|x| (x.0.as_str(), x.3)).collect::<Vec<_>>();
+    assert_eq!(pieces, [
+        ("undefined-reference", "[a][missing]"),
+        ("undefined-reference", "[b][]"),
+        ("undefined-reference", "[^nope]"),
+    ]);
+    let messages = check(source).into_iter().map(|x| x.message).collect::<Vec<_>>();
+    assert_eq!(messages, ["reference `missing` is not defined", "reference `b` is not defined", "footnote `nope` is not defined"]);
+
+    // Edited trees can reference missing definitions.
+    let mut nodes = markdown_format::parse("[a][x]\n\n[x]: /x\n").unwrap();
+    nodes.pop();
+    assert_eq!(lint::lint("[a][x]\n\n[x]: /x\n", &nodes)[0].message, "reference `x` is not defined");
+}
+
+#[test]
+fn configuring() {
+    let source = "# A\n\n### B\n\nhttps://x.com\n";
+    let nodes = markdown_format::parse(source).unwrap();
+    let config: LintConfig = serde_json::from_str(r#"{"bare-url": "off", "heading-increment": "error", "other": "off"}"#).unwrap();
+    let mut linter = Linter::default();
+    linter.configure(&config);
+    assert!(!linter.rules().any(|x| x == "bare-url"));
+    let diagnostics = linter.lint(source, &nodes);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    let json = serde_json::to_value(&diagnostics).unwrap();
+    assert_eq!(json[0]["severity"], "error");
+    assert_eq!(json[0]["rule"], "heading-increment");
+}
+
+struct MaxLineLength(usize);
+
+impl Rule for MaxLineLength {
+    fn name(&self) -> &str {
+        "max-line-length"
+    }
+    fn check(&self, context: &Context<'_>) -> Vec<Finding> {
+        let mut offset = 0;
+        let mut findings = Vec::new();
+        for line in context.source.split_inclusive('\n') {
+            let length = line.trim_end_matches('\n').len();
+            if length > self.0 {
+                findings.push(Finding { message: format!("line of {length} bytes"), position: context.range(offset + self.0..offset + length) });
+            }
+            offset += line.len();
+        }
+        findings
+    }
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+}
+
+#[test]
+fn custom_rules() {
+    let source = "# Title\n\nshort\nthis line is long\n";
+    let nodes = markdown_format::parse(source).unwrap();
+    let mut linter = Linter::default();
+    linter.add(MaxLineLength(10));
+    assert_eq!(linter.rules().last(), Some("max-line-length"));
+    let diagnostics = linter.lint(source, &nodes);
+    assert_eq!(diagnostics.len(), 1);
+    let position = diagnostics[0].position.as_ref().unwrap();
+    assert_eq!((position.start.line, position.start.column, position.end.column), (4, 11, 18));
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    linter.remove("max-line-length");
+    assert!(linter.lint(source, &nodes).is_empty());
+    assert!(matches!(nodes[0], Node::Heading(_)));
+}
@@ -0,0 +1,65 @@
+use markdown_format::incremental::{ParsedDocument, TextEdit};
+use markdown_format::stream::StreamingParser;
+use markdown_format::visit;
+use markdown_format::{Node, ParseConfig, Point};
+
+fn config() -> ParseConfig {
+    ParseConfig { unicode_offsets: true, ..ParseConfig::everything() }
+}
+
+/// Every point of the tree, checked against the offsets computed from `source`.
+fn assert_offsets(source: &str, nodes: &[Node]) {
+    let points = visit::depth_first(nodes)
+        .filter_map(Node::position)
+        .flat_map(|x| [&x.start, &x.end]);
+    for point in points {
+        let before = &source[..point.offset];
+        assert_eq!(point.utf16_offset, Some(before.encode_utf16().count()), "{point:?}");
+        assert_eq!(point.scalar_offset, Some(before.chars().count()), "{point:?}");
+    }
+}
+
+/// `(offset, utf16_offset, scalar_offset)` of the first text node equal to `value`.
+fn text_start(nodes: &[Node], value: &str) -> (usize, Option<usize>, Option<usize>) {
+    let text = visit::depth_first(nodes)
+        .find(|x| matches!(x, Node::Text(x) if x.value == value))
+        .unwrap();
+    let Point { offset, utf16_offset, scalar_offset, .. } = text.position().unwrap().start.clone();
+    (offset, utf16_offset, scalar_offset)
+}
+
+#[test]
+fn emoji_cjk_and_combining_sequences() {
+    // 👍🏽 is two scalars of two UTF-16 units each, é is `e` and U+0301.
+    let source = "# 👍🏽 *a*\n\n漢字 **b**\n\ne\u{301}\u{301} `c` 🇫🇷 [d](/)\n";
+    let nodes = markdown_format::parse_with(source, &config()).unwrap();
+    assert_offsets(source, &nodes);
+    assert_eq!(text_start(&nodes, "a"), (12, Some(8), Some(6)));
+    assert_eq!(text_start(&nodes, "b"), (25, Some(17), Some(15)));
+    assert_eq!(text_start(&nodes, "d"), (50, Some(36), Some(32)));
+
+    let without = markdown_format::parse(source).unwrap();
+    assert_eq!(text_start(&without, "a"), (12, None, None));
+    assert!(!serde_json::to_string(&without).unwrap().contains("utf16_offset"));
+}
+
+#[test]
+fn incremental_and_streaming_keep_offsets() {
+    let source = "# Ünïcödé\n\n- 漢字\n- 👍🏽\n\n```\nφ\n```\n\nlast *é*\n";
+    let mut document = ParsedDocument::parse(source, config()).unwrap();
+    for (range, replacement) in [(17..23, "かな🎉"), (0..0, "ß"), (53..53, "\n\n> 🦀 q\n")] {
+        let reparsed = document.apply(&TextEdit::new(range, replacement)).unwrap();
+        document = reparsed.document;
+        assert_offsets(document.source(), document.nodes());
+    }
+
+    let mut parser = StreamingParser::new(config());
+    for chunk in source.split_inclusive(['\n', ' ']) {
+        parser.push(chunk).unwrap();
+        for block in parser.blocks() {
+            assert_offsets(parser.source(), std::slice::from_ref(block.node));
+        }
+    }
+    let nodes = parser.finish().unwrap();
+    assert_eq!(nodes, markdown_format::parse_with(source, &config()).unwrap());
+}
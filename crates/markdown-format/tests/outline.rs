@@ -0,0 +1,39 @@
+use markdown_format::outline::{self, Section, Slugger};
+
+/// One `<indent><level> <title> #<slug>` line per section.
+fn render(sections: &[Section], depth: usize, lines: &mut Vec<String>) {
+    for section in sections {
+        let indent = "  ".repeat(depth);
+        lines.push(format!("{indent}{} {} #{}", section.level, section.title, section.slug));
+        render(&section.children, depth + 1, lines);
+    }
+}
+
+#[test]
+fn sections_nest_by_level() {
+    let source = "## Before\n\n# Intro\n\n### Deep\n\n## *Set up* `cargo`\n\n> ## Quoted\n\n# Usage\n";
+    let sections = outline::outline(&markdown_format::parse(source).unwrap());
+    let mut lines = Vec::new();
+    render(&sections, 0, &mut lines);
+    assert_eq!(lines, [
+        "2 Before #before",
+        "1 Intro #intro",
+        "  3 Deep #deep",
+        "  2 Set up cargo #set-up-cargo",
+        "  2 Quoted #quoted",
+        "1 Usage #usage",
+    ]);
+    let position = sections[2].position.as_ref().unwrap();
+    assert_eq!(&source[position.start.offset..position.end.offset], "# Usage");
+}
+
+#[test]
+fn slugs_match_github() {
+    let mut slugger = Slugger::default();
+    assert_eq!(slugger.slug("Hello, World!"), "hello-world");
+    assert_eq!(slugger.slug("Hello, World!"), "hello-world-1");
+    assert_eq!(slugger.slug("hello world 1"), "hello-world-1-1");
+    assert_eq!(slugger.slug("Hello World"), "hello-world-2");
+    assert_eq!(slugger.slug("  Ünïcode & 日本語 😀 snake_case "), "--ünïcode--日本語--snake_case-");
+    assert_eq!(slugger.slug("1.2.3 - Release"), "123---release");
+}
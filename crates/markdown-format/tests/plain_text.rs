@@ -0,0 +1,46 @@
+use markdown_format::plain_text::{to_plain_text, Handling, PlainTextOptions};
+
+const SOURCE: &str = "# Title \\& *more*\n\n- one\n- two ![alt](x.png)\n\n| a | b |\n| - | - |\n| c | `d` |\n\n```rust\nfn x() {}\n```\n\n> quote\n> line two\n\nA $x^2$ and [link](/u)\\\nbreak.\n";
+
+#[test]
+fn text_and_options() {
+    let nodes = markdown_format::parse(SOURCE).unwrap();
+    let plain = to_plain_text(&nodes, &PlainTextOptions::default());
+    assert_eq!(plain.text, "Title & more\n\none\ntwo alt\n\na\tb\nc\td\n\nfn x() {}\n\nquote\nline two\n\nA x^2 and link\nbreak.");
+    let options = PlainTextOptions {
+        code_blocks: Handling::Replace("code".into()),
+        inline_code: Handling::Omit,
+        math: Handling::Omit,
+        images: Handling::Replace("image".into()),
+        tables: Handling::Omit,
+    };
+    let plain = to_plain_text(&nodes, &options);
+    assert_eq!(plain.text, "Title & more\n\none\ntwo image\n\ncode\n\nquote\nline two\n\nA  and link\nbreak.");
+    let image = plain.text.find("image").unwrap();
+    let range = plain.source_range(image..image + 5).unwrap();
+    assert_eq!(&SOURCE[range.start.offset..range.end.offset], "![alt](x.png)");
+}
+
+#[test]
+fn ranges_map_back_to_the_source() {
+    let nodes = markdown_format::parse(SOURCE).unwrap();
+    let plain = to_plain_text(&nodes, &PlainTextOptions::default());
+    for mapping in &plain.mappings {
+        assert!(mapping.range.end <= plain.text.len());
+        assert!(mapping.source.start.offset < mapping.source.end.offset);
+    }
+    let source = |query: &str| {
+        let start = plain.text.find(query).unwrap();
+        let range = plain.source_range(start..start + query.len()).unwrap();
+        (&SOURCE[range.start.offset..range.end.offset], range.start.line, range.start.column)
+    };
+    // Verbatim text maps exactly, across node boundaries too.
+    assert_eq!(source("or"), ("or", 1, 14));
+    assert_eq!(source("wo"), ("wo", 4, 4));
+    assert_eq!(source("x^2 and li"), ("$x^2$ and [li", 17, 3));
+    // Otherwise the whole node.
+    assert_eq!(source("Title &"), ("Title \\& ", 1, 3));
+    assert_eq!(source("line"), ("quote\n> line two", 14, 3));
+    assert_eq!(source("c\td"), ("c | `d`", 8, 3));
+    assert_eq!(plain.source_range(plain.text.len()..plain.text.len() + 1), None);
+}
@@ -0,0 +1,100 @@
+//! `parse(print(parse(x))) == parse(x)` over the sample documents.
+mod common;
+
+use markdown_format::printer::{self, PrinterOptions};
+use markdown_format::visit::NodeKind;
+use markdown_format::{Heading, HeadingLevel, Node, ParseConfig, Text};
+
+fn assert_round_trip(source: &str, options: &PrinterOptions, name: &str) {
+    assert_round_trip_with(source, &ParseConfig::default(), options, name);
+}
+
+fn assert_round_trip_with(source: &str, config: &ParseConfig, options: &PrinterOptions, name: &str) {
+    let nodes = markdown_format::parse_with(source, config).unwrap();
+    let printed = printer::print(&nodes, options);
+    let reparsed = markdown_format::parse_with(&printed, config).unwrap();
+    let expected = common::without_positions(serde_json::to_value(&nodes).unwrap());
+    let actual = common::without_positions(serde_json::to_value(&reparsed).unwrap());
+    assert!(
+        expected == actual,
+        "{name} does not round trip, printed:\n{printed}\nexpected:\n{expected:#}\nactual:\n{actual:#}",
+    );
+    // Printing is idempotent once normalized.
+    assert_eq!(printer::print(&reparsed, options), printed, "{name}");
+}
+
+fn alternate() -> PrinterOptions {
+    PrinterOptions {
+        bullet: printer::Bullet::Plus,
+        emphasis: printer::EmphasisMarker::Underscore,
+        fence: printer::Fence::Tilde,
+        table_padding: false,
+    }
+}
+
+#[test]
+fn samples_round_trip() {
+    let alternate = alternate();
+    for (name, source) in common::samples() {
+        assert_round_trip(&source, &PrinterOptions::default(), &name);
+        assert_round_trip(&source, &alternate, &name);
+    }
+}
+
+/// With the extensions, whose printing the default config never parses.
+#[test]
+fn extensions_round_trip() {
+    let config = ParseConfig::everything();
+    let cases = [
+        "---\ntitle: a\ntags: [b, c]\n---\n\n# d\n",
+        "+++\ntitle = \"a\"\n+++\n\nb\n",
+        "> [!WARNING] Mind the gap\n> a *b*\n>\n> c\n",
+        ":::note[A title]\n\n- a\n- b\n\n:::tip\nnested\n:::\n\n:::\n",
+        "See [[Page#Heading|alias]], [[Other]] and ![[image.png|200]].\n",
+        "Term *a*\nTerm b\n: One\n  two\n: Three\n\nLoose\n\n: Four\n",
+        "$$\ne = mc^2\n$$\n\nInline $x^2$ and ~~gone~~.\n",
+        "a[^1]\n\n[^1]: A *note*.\n",
+    ];
+    let kinds = cases
+        .iter()
+        .flat_map(|x| markdown_format::parse_with(x, &config).unwrap())
+        .collect::<Vec<_>>();
+    let kinds = markdown_format::visit::depth_first(&kinds).map(Node::kind).collect::<std::collections::HashSet<_>>();
+    for kind in [NodeKind::Yaml, NodeKind::Toml, NodeKind::Admonition, NodeKind::WikiLink, NodeKind::Embed, NodeKind::DefinitionList] {
+        assert!(kinds.contains(&kind), "{kind:?}");
+    }
+    let sources = common::samples().into_iter().chain(cases.iter().map(|x| (format!("{x:?}"), x.to_string())));
+    for (name, source) in sources {
+        assert_round_trip_with(&source, &config, &PrinterOptions::default(), &name);
+        assert_round_trip_with(&source, &config, &alternate(), &name);
+    }
+}
+
+#[test]
+fn cases_round_trip() {
+    let cases = [
+        "a\nb\n===\n",
+        "Foo *bar\nbaz*\n---\n",
+        "a\\\nb\n===\n",
+        "> a\n> b\n> ---\n",
+        "# a\n\nb\n===\n",
+    ];
+    for source in cases {
+        assert_round_trip(source, &PrinterOptions::default(), &format!("{source:?}"));
+    }
+}
+
+#[test]
+fn multi_line_headings() {
+    let print = |source: &str| printer::print(&markdown_format::parse(source).unwrap(), &PrinterOptions::default());
+    assert_eq!(print("a\nb\n===\n"), "a\nb\n===\n");
+    assert_eq!(print("Foo *bar\nbaz*\n---\n"), "Foo *bar\nbaz*\n---\n");
+    assert_eq!(print("a\n===\n"), "# a\n");
+    // ATX headings can't span lines.
+    let heading = Node::Heading(Heading {
+        level: HeadingLevel::H3,
+        children: vec![Node::Text(Text { value: String::from("a\nb"), position: None })],
+        position: None,
+    });
+    assert_eq!(printer::print(&[heading], &PrinterOptions::default()), "### a b\n");
+}
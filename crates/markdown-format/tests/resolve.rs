@@ -0,0 +1,80 @@
+use markdown_format::{Node, ParseConfig};
+use markdown_format::resolve::{self, DiagnosticKind};
+
+fn inlines(node: &Node) -> &[Node] {
+    match node {
+        Node::Paragraph(x) => &x.children,
+        _ => panic!("expected a paragraph, got {node:?}"),
+    }
+}
+
+#[test]
+fn references_and_footnotes_resolve() {
+    let source = "\
+[One][a], ![two][B], [c][] and [^y] then [^x] and [^y].
+
+[a]: /a \"A\"
+[b]: /b
+[c]: /c
+[a]: /shadowed
+[unused]: /unused
+
+[^x]: X.
+[^y]: Y.
+[^z]: Z.
+";
+    let mut nodes = markdown_format::parse_with(source, &ParseConfig::gfm()).unwrap();
+    let resolution = resolve::resolve(&mut nodes);
+    let paragraph = inlines(&nodes[0]);
+    let Node::LinkReference(one) = &paragraph[0] else { panic!() };
+    assert_eq!((one.url.as_deref(), one.title.as_deref()), (Some("/a"), Some("A")));
+    let Node::ImageReference(two) = &paragraph[2] else { panic!() };
+    assert_eq!((two.url.as_deref(), two.title.as_deref()), (Some("/b"), None));
+    let Node::LinkReference(three) = &paragraph[4] else { panic!() };
+    assert_eq!(three.url.as_deref(), Some("/c"));
+    let numbers = paragraph
+        .iter()
+        .filter_map(|x| match x {
+            Node::FootnoteReference(x) => x.number,
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(numbers, [1, 2, 1]);
+    assert_eq!(resolution.footnotes, ["y", "x"]);
+    assert_eq!(resolution.definitions["a"].url, "/a");
+    let definitions = nodes
+        .iter()
+        .filter_map(|x| match x {
+            Node::FootnoteDefinition(x) => Some((x.identifier.as_str(), x.number)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(definitions, [("x", Some(2)), ("y", Some(1)), ("z", None)]);
+    let diagnostics = resolution.diagnostics
+        .iter()
+        .map(|x| (x.kind, x.identifier.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(diagnostics, [
+        (DiagnosticKind::DuplicateDefinition, "a"),
+        (DiagnosticKind::UnusedDefinition, "unused"),
+        (DiagnosticKind::UnusedFootnoteDefinition, "z"),
+    ]);
+}
+
+#[test]
+fn dangling_references_are_reported() {
+    let mut nodes = markdown_format::parse_with("[a][x] [^y]\n\n[x]: /x\n\n[^y]: Y\n", &ParseConfig::gfm()).unwrap();
+    // Definitions removed after parsing leave the references dangling.
+    nodes.retain(|x| matches!(x, Node::Paragraph(_)));
+    let resolution = resolve::resolve(&mut nodes);
+    let Node::LinkReference(reference) = &inlines(&nodes[0])[0] else { panic!() };
+    assert_eq!(reference.url, None);
+    let diagnostics = resolution.diagnostics
+        .iter()
+        .map(|x| (x.kind, x.to_string()))
+        .collect::<Vec<_>>();
+    assert_eq!(diagnostics, [
+        (DiagnosticKind::UndefinedReference, "reference to undefined `x` at 1:1".to_owned()),
+        (DiagnosticKind::UndefinedFootnote, "reference to undefined footnote `y` at 1:8".to_owned()),
+    ]);
+}
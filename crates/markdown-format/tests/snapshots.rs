@@ -0,0 +1,61 @@
+//! Golden snapshots of the pretty tree, JSON and HTML of every sample in
+//! `crates/samples`, stored in `tests/snapshots`.
+//!
+//! After an intended change of the output, rerun with `UPDATE_SNAPSHOTS=1` to
+//! rewrite the snapshots and review their diff.
+mod common;
+
+use std::path::Path;
+use markdown_format::pretty_tree;
+
+#[test]
+fn samples_match_snapshots() {
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some_and(|x| x != "0");
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/snapshots");
+    let mut mismatches = Vec::new();
+    for sample in common::sample_paths() {
+        let source = std::fs::read_to_string(&sample).unwrap();
+        let nodes = markdown_format::parse(&source).unwrap();
+        let name = sample.file_stem().unwrap().to_str().unwrap();
+        let outputs = [
+            ("tree.txt", pretty_tree::branch_of("nodes", &nodes).to_string()),
+            ("json", format!("{}\n", serde_json::to_string_pretty(&nodes).unwrap())),
+            ("html", markdown_format::html::render(&nodes, &Default::default())),
+        ];
+        for (extension, output) in outputs {
+            let path = directory.join(format!("{name}.{extension}"));
+            if update {
+                std::fs::create_dir_all(&directory).unwrap();
+                std::fs::write(&path, output).unwrap();
+                continue
+            }
+            let expected = std::fs::read_to_string(&path).unwrap_or_default();
+            if let Some(difference) = first_difference(&expected, &output) {
+                mismatches.push(format!("{} differs in {difference}", path.display()));
+            }
+        }
+    }
+    assert!(
+        mismatches.is_empty(),
+        "{}\nrerun with UPDATE_SNAPSHOTS=1 if the change is intended",
+        mismatches.join("\n"),
+    );
+}
+
+/// The first line (1-indexed) that differs, or a description of the
+/// difference when all lines are equal.
+fn first_difference(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None
+    }
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(expected), Some(actual)) if expected == actual => line += 1,
+            (None, None) => return Some(String::from("its line endings or trailing newline")),
+            _ => return Some(format!("line {line}")),
+        }
+    }
+}
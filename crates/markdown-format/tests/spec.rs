@@ -0,0 +1,203 @@
+//! Spec conformance: the examples of the CommonMark and GFM specs through
+//! `parse_with` and `html::render`.
+//!
+//! `spec/commonmark.json` is <https://spec.commonmark.org/0.31.2/spec.json>,
+//! `spec/gfm.json` has the examples of the extension sections of the GFM spec
+//! (0.29-gfm) in the same shape, numbered as there. HTML is compared after
+//! normalizing whitespace between tags, attribute order and `/>`.
+//!
+//! Run with `cargo test --test spec -- --nocapture` for the pass/fail report
+//! per section. Examples known to fail are listed below, the tests fail when
+//! another example fails and when a listed one passes, so that the lists stay
+//! exact.
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use markdown_format::ParseConfig;
+use markdown_format::html::{self, HtmlOptions};
+
+#[derive(serde::Deserialize)]
+struct Example {
+    markdown: String,
+    html: String,
+    example: u32,
+    section: String,
+}
+
+struct Outcome {
+    example: Example,
+    /// `None` when parsing failed or panicked.
+    html: Option<String>,
+}
+
+const COMMONMARK_FAILURES: &[u32] = &[
+    // Code blocks in containers lose the columns left over from a tab.
+    5, 6, 7,
+    // Code spans keep their line endings and the padding of `` ` `` fences.
+    121, 335, 336, 337, 640, 641,
+    // Brackets in autolink URLs aren't percent-encoded.
+    526, 538, 603,
+    // The alt text of an image drops nested images.
+    574,
+];
+
+const GFM_FAILURES: &[u32] = &[
+    // Table rows aren't padded or cut to the number of header cells.
+    202, 204,
+    // Task lists get GitHub's `contains-task-list`/`task-list-item` classes.
+    279, 280,
+    // No `ftp:` autolink literals, as on GitHub.
+    628,
+    // No tag filter for raw HTML.
+    652,
+];
+
+#[test]
+fn commonmark() {
+    let outcomes = run(include_str!("spec/commonmark.json"), &ParseConfig::commonmark());
+    report("CommonMark 0.31.2", &outcomes, COMMONMARK_FAILURES);
+}
+
+#[test]
+fn gfm() {
+    let outcomes = run(include_str!("spec/gfm.json"), &ParseConfig::gfm());
+    report("GFM 0.29-gfm extensions", &outcomes, GFM_FAILURES);
+}
+
+fn run(json: &str, config: &ParseConfig) -> Vec<Outcome> {
+    let examples: Vec<Example> = serde_json::from_str(json).unwrap();
+    examples
+        .into_iter()
+        .map(|example| {
+            let html = catch_unwind(AssertUnwindSafe(|| {
+                let nodes = markdown_format::parse_with(&example.markdown, config).ok()?;
+                Some(html::render(&nodes, &HtmlOptions::default()))
+            }));
+            Outcome { html: html.ok().flatten(), example }
+        })
+        .collect()
+}
+
+/// Prints the passed examples per section and checks them against the known
+/// failures.
+fn report(name: &str, outcomes: &[Outcome], known_failures: &[u32]) {
+    let mut sections: Vec<(&str, usize, usize)> = Vec::new();
+    let mut regressions = Vec::new();
+    let mut fixed = Vec::new();
+    for outcome in outcomes {
+        let example = &outcome.example;
+        let passed = outcome.html.as_deref().is_some_and(|x| normalize(x) == normalize(&example.html));
+        match sections.last_mut() {
+            Some(section) if section.0 == example.section => (),
+            _ => sections.push((&example.section, 0, 0)),
+        }
+        let section = sections.last_mut().unwrap();
+        section.1 += passed as usize;
+        section.2 += 1;
+        match (passed, known_failures.contains(&example.example)) {
+            (false, false) => regressions.push(outcome),
+            (true, true) => fixed.push(example.example),
+            _ => (),
+        }
+    }
+    let passed = sections.iter().map(|x| x.1).sum::<usize>();
+    println!("{name}: {passed}/{} examples pass", outcomes.len());
+    for (section, passed, total) in &sections {
+        let mark = if passed == total { ' ' } else { '✗' };
+        println!("  {mark} {section:<40} {passed:>3}/{total}");
+    }
+    for outcome in &regressions {
+        let example = &outcome.example;
+        println!("\nexample {} ({})", example.example, example.section);
+        println!("markdown: {:?}\nexpected: {:?}\nactual:   {:?}", example.markdown, example.html, outcome.html);
+    }
+    let regressions = regressions.iter().map(|x| x.example.example).collect::<Vec<_>>();
+    assert!(regressions.is_empty(), "examples not known to fail do: {regressions:?}");
+    assert!(fixed.is_empty(), "examples known to fail pass, remove them from the list: {fixed:?}");
+}
+
+/// Drops whitespace between tags outside `<pre>`, sorts attributes and
+/// writes void elements without `/`.
+fn normalize(html: &str) -> String {
+    let mut output = String::new();
+    let mut in_pre = 0usize;
+    let mut rest = html;
+    while !rest.is_empty() {
+        let tag_end = rest
+            .strip_prefix('<')
+            .filter(|x| x.starts_with(|x: char| x.is_ascii_alphabetic() || x == '/'))
+            .and_then(|_| tag_length(rest));
+        if let Some(length) = tag_end {
+            let tag = normalize_tag(&rest[..length]);
+            if tag == "<pre>" || tag.starts_with("<pre ") {
+                in_pre += 1;
+            } else if tag == "</pre>" {
+                in_pre = in_pre.saturating_sub(1);
+            }
+            output.push_str(&tag);
+            rest = &rest[length..];
+            continue
+        }
+        let first = rest.chars().next().map_or(0, char::len_utf8);
+        let text_end = rest[first..].find('<').map_or(rest.len(), |x| x + first);
+        let text = &rest[..text_end];
+        let between_tags = output.ends_with('>') && text_end < rest.len();
+        if in_pre > 0 || !between_tags || !text.trim().is_empty() {
+            output.push_str(text);
+        }
+        rest = &rest[text_end..];
+    }
+    output.trim().to_owned()
+}
+
+/// Length of the tag at the start of `html`, quotes taken into account.
+fn tag_length(html: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, char) in html.char_indices() {
+        match (quote, char) {
+            (None, '"' | '\'') => quote = Some(char),
+            (Some(open), _) if open == char => quote = None,
+            (None, '>') => return Some(index + 1),
+            _ => (),
+        }
+    }
+    None
+}
+
+fn normalize_tag(tag: &str) -> String {
+    let inner = tag[1..tag.len() - 1].trim_end_matches('/').trim_end();
+    let (name, mut attributes) = match inner.split_once(char::is_whitespace) {
+        Some((name, attributes)) => (name, split_attributes(attributes)),
+        None => (inner, Vec::new()),
+    };
+    attributes.sort();
+    let mut output = format!("<{}", name.to_ascii_lowercase());
+    for attribute in attributes {
+        output.push(' ');
+        output.push_str(attribute);
+    }
+    output.push('>');
+    output
+}
+
+fn split_attributes(attributes: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut quote = None;
+    for (index, char) in attributes.char_indices() {
+        match (quote, char) {
+            (None, '"' | '\'') => quote = Some(char),
+            (Some(open), _) if open == char => quote = None,
+            (None, x) if x.is_whitespace() => {
+                if let Some(start) = start.take() {
+                    parts.push(&attributes[start..index]);
+                }
+                continue
+            }
+            _ => (),
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(start) = start {
+        parts.push(&attributes[start..]);
+    }
+    parts
+}
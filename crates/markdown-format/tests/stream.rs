@@ -0,0 +1,123 @@
+//! Streaming must agree with parsing the whole document once it is complete,
+//! and finalized blocks must never change.
+mod common;
+
+use markdown_format::{Node, ParseConfig};
+use markdown_format::stream::StreamingParser;
+
+fn chunks(source: &str) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = source;
+    let mut size = 1;
+    while !rest.is_empty() {
+        let mut end = size.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+        size = size % 7 + 1;
+    }
+    chunks
+}
+
+fn assert_streams(source: &str, config: ParseConfig, name: &str) {
+    assert_streams_chunks(&chunks(source), config, name);
+}
+
+fn assert_streams_chunks(chunks: &[&str], config: ParseConfig, name: &str) {
+    let source = chunks.concat();
+    let mut parser = StreamingParser::new(config.clone());
+    let mut finalized: Vec<Node> = Vec::new();
+    for chunk in chunks {
+        parser.push(chunk).unwrap();
+        assert!(
+            parser.finalized().starts_with(&finalized),
+            "{name}: a finalized block changed after {:?}",
+            parser.source(),
+        );
+        finalized = parser.finalized().to_vec();
+    }
+    let expected = markdown_format::parse_with(&source, &config).unwrap();
+    assert!(parser.finish().unwrap() == expected, "{name}: streaming differs from a full parse");
+}
+
+#[test]
+fn samples_stream() {
+    for (name, source) in common::samples() {
+        assert_streams(&source, ParseConfig::default(), &name);
+        assert_streams(&source, ParseConfig::everything(), &name);
+    }
+}
+
+/// Chunks ending where the next one turns the last block into a
+/// continuation of the one before it.
+#[test]
+fn continuations_match_a_full_parse() {
+    let splits: &[&[&str]] = &[
+        &["1. a\n\n2", ". b\n"],
+        &["1) a\n\n10", ") b\n"],
+        &["- a\n\n-", " b\n"],
+        &["> a\n***", "*x\n"],
+        &["> a\n#", "x\n"],
+        &["- a\n#", "x\n"],
+        &["a\n***", "*x\n"],
+        &["[^1]: a\n#", "x\n"],
+        &["| a |\n| - |\n#", "x\n"],
+        &["[a]: /url\n'ti", "tle'\n\n[a]\n"],
+        &["[a]\n\n[", "a]: /url\n"],
+        &["Term\n\n:", " d\n"],
+        &["a\n: b\n\n:", " c\n"],
+        &["a\n: b\n\nc", "\n: d\n"],
+    ];
+    for chunks in splits {
+        for config in [ParseConfig::default(), ParseConfig::everything()] {
+            assert_streams_chunks(chunks, config, &format!("{chunks:?}"));
+        }
+    }
+}
+
+fn open_block(source: &str) -> Node {
+    let mut parser = StreamingParser::new(ParseConfig::everything());
+    parser.push(source).unwrap();
+    let block = parser.blocks().last().unwrap();
+    assert!(block.open);
+    block.node.clone()
+}
+
+#[test]
+fn unclosed_constructs_are_closed_speculatively() {
+    let Node::CodeBlock(code) = open_block("```rust\nfn main() {") else { panic!() };
+    assert_eq!((code.lang.as_deref(), code.value.as_str()), (Some("rust"), "fn main() {"));
+    assert_eq!(code.position.unwrap().end.offset, 19);
+    let Node::DisplayMath(math) = open_block("$$\nx^2") else { panic!() };
+    assert_eq!(math.value, "x^2");
+    let Node::Table(table) = open_block("| a | b |\n| -") else { panic!() };
+    assert_eq!(table.alignment.len(), 2);
+    let Node::Paragraph(paragraph) = open_block("Some **bold `code") else { panic!() };
+    let [Node::Text(_), Node::Strong(strong)] = paragraph.children.as_slice() else { panic!() };
+    assert!(matches!(strong.children.as_slice(), [Node::Text(_), Node::InlineCode(_)]));
+    let Node::Paragraph(paragraph) = open_block("[a link](https://exam") else { panic!() };
+    assert!(matches!(paragraph.children.as_slice(), [Node::Link(_)]));
+}
+
+#[test]
+fn ambiguous_trailing_lines_wait() {
+    // Could still become `---` (a setext underline) or `- item`.
+    let Node::Paragraph(_) = open_block("Some text\n-") else { panic!() };
+}
+
+#[test]
+fn block_ids_are_stable() {
+    let mut parser = StreamingParser::new(ParseConfig::default());
+    parser.push("# Title\n\nFirst").unwrap();
+    let ids = parser.blocks().map(|x| (x.id, x.open)).collect::<Vec<_>>();
+    assert_eq!(ids.len(), 2);
+    assert!(!ids[0].1 && ids[1].1);
+    parser.push(" paragraph.\n\nSecond").unwrap();
+    let blocks = parser.blocks().collect::<Vec<_>>();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[1].id, ids[1].0);
+    assert!(!blocks[1].open && blocks[2].open);
+}
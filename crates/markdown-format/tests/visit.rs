@@ -0,0 +1,86 @@
+use markdown_format::{Emphasis, Node, Strong, Text};
+use markdown_format::visit::{self, Fold, NodeKind, Visit, VisitMut};
+
+const SOURCE: &str = "# A *b*\n\n- c **d**\n- e\n\n> f\n";
+
+fn kinds<'a>(nodes: impl Iterator<Item = &'a Node>) -> Vec<NodeKind> {
+    nodes.map(Node::kind).collect()
+}
+
+#[test]
+fn iterators_follow_document_and_level_order() {
+    use NodeKind::*;
+    let nodes = markdown_format::parse(SOURCE).unwrap();
+    assert_eq!(kinds(visit::depth_first(&nodes)), [
+        Heading, Text, Emphasis, Text,
+        List, ListItem, Paragraph, Text, Strong, Text, ListItem, Paragraph, Text,
+        Blockquote, Paragraph, Text,
+    ]);
+    assert_eq!(kinds(visit::breadth_first(&nodes)), [
+        Heading, List, Blockquote,
+        Text, Emphasis, ListItem, ListItem, Paragraph,
+        Text, Paragraph, Paragraph, Text,
+        Text, Strong, Text,
+        Text,
+    ]);
+    assert_eq!(kinds(nodes[0].descendants()), [Heading, Text, Emphasis, Text]);
+    let text = visit::depth_first(&nodes).filter(|x| x.kind() == Text).nth(1).unwrap();
+    let position = text.position().unwrap();
+    assert_eq!(&SOURCE[position.start.offset..position.end.offset], "b");
+}
+
+#[test]
+fn visitors_and_folds() {
+    #[derive(Default)]
+    struct Counter { emphasis: usize, text: usize }
+    impl Visit for Counter {
+        fn visit_emphasis(&mut self, node: &Emphasis) {
+            self.emphasis += 1;
+            self.visit_nodes(&node.children);
+        }
+        fn visit_text(&mut self, _: &Text) {
+            self.text += 1;
+        }
+    }
+    let mut nodes = markdown_format::parse(SOURCE).unwrap();
+    let mut counter = Counter::default();
+    counter.visit_nodes(&nodes);
+    assert_eq!((counter.emphasis, counter.text), (1, 6));
+
+    /// Drops strong emphasis along with its content.
+    struct Prune;
+    impl VisitMut for Prune {
+        fn visit_nodes_mut(&mut self, nodes: &mut Vec<Node>) {
+            nodes.retain(|x| x.kind() != NodeKind::Strong);
+            for node in nodes {
+                self.visit_node_mut(node);
+            }
+        }
+    }
+    Prune.visit_nodes_mut(&mut nodes);
+    assert!(visit::depth_first(&nodes).all(|x| x.kind() != NodeKind::Strong));
+
+    /// Unwraps emphasis and uppercases text.
+    struct Flatten;
+    impl Fold for Flatten {
+        fn fold_nodes(&mut self, nodes: Vec<Node>) -> Vec<Node> {
+            nodes.into_iter().flat_map(|node| match node {
+                Node::Emphasis(x) => self.fold_nodes(x.children),
+                node => vec![self.fold_node(node)],
+            }).collect()
+        }
+        fn fold_text(&mut self, node: Text) -> Text {
+            Text { value: node.value.to_uppercase(), ..node }
+        }
+        fn fold_strong(&mut self, _: Strong) -> Strong {
+            unreachable!()
+        }
+    }
+    let nodes = Flatten.fold_nodes(nodes);
+    let texts = visit::depth_first(&nodes).filter_map(|x| match x {
+        Node::Text(x) => Some(x.value.as_str()),
+        _ => None,
+    });
+    assert_eq!(texts.collect::<Vec<_>>(), ["A ", "B", "C ", "E", "F"]);
+    assert_eq!(kinds(nodes[0].descendants()), [NodeKind::Heading, NodeKind::Text, NodeKind::Text]);
+}
@@ -0,0 +1,135 @@
+use markdown_format::wikilink::{self, Target};
+use markdown_format::{html, printer, visit, Embed, Node, ParseConfig, WikiLink};
+
+fn config() -> ParseConfig {
+    ParseConfig { wikilink: true, ..ParseConfig::gfm() }
+}
+
+fn parse(source: &str) -> Vec<Node> {
+    markdown_format::parse_with(source, &config()).unwrap()
+}
+
+fn source_of<'a>(source: &'a str, node: &Node) -> &'a str {
+    let position = node.position().unwrap();
+    &source[position.start.offset..position.end.offset]
+}
+
+fn links(nodes: &[Node]) -> Vec<&WikiLink> {
+    visit::depth_first(nodes)
+        .filter_map(|x| match x {
+            Node::WikiLink(x) => Some(x),
+            _ => None,
+        })
+        .collect()
+}
+
+fn embeds(nodes: &[Node]) -> Vec<&Embed> {
+    visit::depth_first(nodes)
+        .filter_map(|x| match x {
+            Node::Embed(x) => Some(x),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn links_and_embeds() {
+    let source = "See [[Page Name|alias]], [[Other#Some heading]] and [[#Local]].\n\n![[image.png|200]] ![[Note#Part]]\n";
+    let nodes = parse(source);
+    let parts = links(&nodes)
+        .into_iter()
+        .map(|x| (x.target.as_str(), x.heading_anchor.as_deref(), x.alias.as_deref()))
+        .collect::<Vec<_>>();
+    assert_eq!(parts, [
+        ("Page Name", None, Some("alias")),
+        ("Other", Some("Some heading"), None),
+        ("", Some("Local"), None),
+    ]);
+    let embeds = embeds(&nodes);
+    assert_eq!((embeds[0].target.as_str(), embeds[0].alias.as_deref()), ("image.png", Some("200")));
+    assert_eq!(embeds[0].alt(), "image.png");
+    assert_eq!(embeds[1].heading_anchor.as_deref(), Some("Part"));
+
+    let Node::Paragraph(paragraph) = &nodes[0] else { panic!() };
+    let pieces = paragraph.children.iter().map(|x| source_of(source, x)).collect::<Vec<_>>();
+    assert_eq!(pieces, [
+        "See ",
+        "[[Page Name|alias]]",
+        ", ",
+        "[[Other#Some heading]]",
+        " and ",
+        "[[#Local]]",
+        ".",
+    ]);
+    let Node::Paragraph(paragraph) = &nodes[1] else { panic!() };
+    let start = &paragraph.children[2].position().unwrap().start;
+    assert_eq!((start.line, start.column), (3, 20));
+}
+
+#[test]
+fn positions_across_lines_and_containers() {
+    let source = "> a\n> b [[x]]\n\n- c\n  d ![[y]]\n\n| h |\n| - |\n| [[p\\|q]] |\n";
+    let nodes = parse(source);
+    let links = links(&nodes);
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[1].alias.as_deref(), Some("q"));
+    let sources = visit::depth_first(&nodes)
+        .filter(|x| matches!(x, Node::WikiLink(_) | Node::Embed(_)))
+        .map(|x| source_of(source, x))
+        .collect::<Vec<_>>();
+    assert_eq!(sources, ["[[x]]", "![[y]]", "[[p\\|q]]"]);
+    for node in visit::depth_first(&nodes).filter(|x| matches!(x, Node::Text(_))) {
+        let Node::Text(text) = node else { unreachable!() };
+        assert_eq!(source_of(source, node).replace("\n  ", "\n").replace("\n> ", "\n"), text.value);
+    }
+}
+
+#[test]
+fn not_wiki_links() {
+    let sources = [
+        "\\[\\[escaped]]",
+        "[[a\nb]]",
+        "[[]]",
+        "[[|alias]]",
+        "`[[code]]`",
+        "[[a *b* c]]",
+        "[x [[y]]](/url)",
+    ];
+    for source in sources {
+        assert!(links(&parse(source)).is_empty(), "{source}");
+    }
+    let without = markdown_format::parse_with("[[a]]", &ParseConfig::gfm()).unwrap();
+    assert!(links(&without).is_empty());
+}
+
+#[test]
+fn resolving() {
+    let mut nodes = parse("[[Home]] [[Missing|gone]] ![[cat.png]] [[Home#Top]]\n");
+    let mut calls = Vec::new();
+    wikilink::resolve(&mut nodes, &mut |x: Target| {
+        calls.push((x.target.to_owned(), x.is_embed));
+        match x.target {
+            "Missing" => None,
+            target => Some(format!("/{}{}", target.to_lowercase(), x.heading_anchor.map(|x| format!("#{x}")).unwrap_or_default())),
+        }
+    });
+    assert_eq!(calls.len(), 4);
+    assert!(calls[2].1);
+    let urls = links(&nodes).iter().map(|x| x.url.as_deref()).collect::<Vec<_>>();
+    assert_eq!(urls, [Some("/home"), None, Some("/home#Top")]);
+    assert_eq!(
+        html::render(&nodes, &Default::default()),
+        "<p><a href=\"/home\">Home</a> gone <img src=\"/cat.png\" alt=\"cat.png\" /> <a href=\"/home#Top\">Home#Top</a></p>\n",
+    );
+}
+
+#[test]
+fn printing_round_trips() {
+    let source = "a [[Page#H|alias]] b ![[img.png]]\n\n| [[x\\|y]] |\n| - |\n";
+    let nodes = parse(source);
+    let printed = printer::print(&nodes, &Default::default());
+    assert_eq!(printed, "a [[Page#H|alias]] b ![[img.png]]\n\n| [[x\\|y]] |\n| -------- |\n");
+    let reparsed = parse(&printed);
+    assert_eq!(links(&reparsed), links(&nodes));
+    assert_eq!(embeds(&reparsed), embeds(&nodes));
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use markdown_format::{Envelope, ParseConfig, WireError};
+
+#[test]
+fn envelopes_round_trip() {
+    let config = ParseConfig { unicode_offsets: true, ..ParseConfig::everything() };
+    for (name, source) in common::samples() {
+        let mut nodes = markdown_format::parse_with(&source, &config).unwrap();
+        markdown_format::resolve::resolve(&mut nodes);
+        let envelope = Envelope::new(nodes);
+        let json = envelope.to_json().unwrap();
+        assert!(json.starts_with(r#"{"version":2,"nodes":["#), "{name}");
+        assert_eq!(Envelope::from_json(&json).unwrap(), envelope, "{name}");
+    }
+}
+
+#[test]
+fn bare_node_arrays_are_the_current_version() {
+    let nodes = markdown_format::parse("# a").unwrap();
+    let json = serde_json::to_string(&nodes).unwrap();
+    assert_eq!(Envelope::from_json(&json).unwrap(), Envelope::new(nodes));
+}
+
+#[test]
+fn newer_versions_are_rejected() {
+    let json = format!(r#"{{"version": {}, "nodes": []}}"#, Envelope::VERSION + 1);
+    let error = Envelope::from_json(&json).unwrap_err();
+    assert!(matches!(error, WireError::UnsupportedVersion(3)), "{error}");
+    assert_eq!(error.to_string(), "unsupported wire schema version 3 (expected at most 2)");
+    assert!(matches!(Envelope::from_json(r#"{"version": 2}"#), Err(WireError::Json(_))));
+}
+
+/// The checked in schema is what `examples/json_schema.rs` prints.
+#[test]
+fn checked_in_schema_is_generated() {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("schema/envelope.schema.json");
+    let generated = format!("{}\n", serde_json::to_string_pretty(&markdown_format::json_schema()).unwrap());
+    assert!(
+        std::fs::read_to_string(&path).unwrap() == generated,
+        "{} is out of date, regenerate it with `cargo run -p markdown-format --example json_schema --features schema`",
+        path.display(),
+    );
+}
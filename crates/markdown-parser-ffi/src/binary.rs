@@ -0,0 +1,159 @@
+//! Flat binary AST encoding, see `markdown_format::binary` for the layout.
+//!
+//! `markdown_parser_ffi_utf8_parse_to_binary` returns a byte vector that callers read in place
+//! through `markdown_parser_ffi_byte_vector_data`: a `BinaryHeader`, `node_count` `BinaryNode`s,
+//! `node_count` `BinaryUnicodeOffsets` if the header's `flags` is `1`, `field_count`
+//! `BinaryField`s and finally the string pool. Fields point into the pool or into the source, which
+//! the caller still holds.
+//!
+//! Kinds, string fields, reference kinds, checked states and table alignments are numbered as
+//! `NodeKind`, `NodeStringField`, `ReferenceKind`, `CheckedState` and `AlignKind`. Integers are
+//! little-endian and every section starts at a multiple of 4 bytes from the start of the data.
+//!
+//! The data is the buffer of a Rust `Vec<u8>`, which is only guaranteed to be aligned to 1 byte.
+//! Don't cast it to `BinaryHeader *` or `BinaryNode *` and dereference those: copy the records out
+//! with `memcpy` (or read them with unaligned loads, such as Swift's `loadUnaligned`).
+use std::os::raw::c_char;
+use markdown_format::binary::{self, BinaryDocument};
+use crate::{ByteArray, ByteVectorParseResult, ErrorStatus, Failure, ParseConfig};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryHeader {
+    /// `MDAB`.
+    pub magic: [u8; 4],
+    pub version: u32,
+    /// `1` if the `BinaryUnicodeOffsets` section is present.
+    pub flags: u32,
+    pub node_count: u32,
+    /// The top-level nodes are `0..root_count`.
+    pub root_count: u32,
+    pub field_count: u32,
+    pub pool_length: u32,
+    /// In bytes.
+    pub source_length: u32,
+}
+
+/// Nodes are in level order, so that the children of a node are contiguous.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryNode {
+    /// A `NodeKind`.
+    pub kind: u8,
+    /// `1` has position, `2` ordered (lists), `4` spread (lists, list items and definition lists), `8` has `number`.
+    pub flags: u8,
+    /// The heading level, the `ReferenceKind` of references or the `CheckedState` of list items.
+    pub detail: u8,
+    pub padding: u8,
+    /// `UINT32_MAX` for top-level nodes.
+    pub parent: u32,
+    pub children_start: u32,
+    pub children_length: u32,
+    /// The node’s fields run from `fields_start` up to the `fields_start` of the next node, or
+    /// `field_count` for the last node.
+    pub fields_start: u32,
+    /// The start of ordered lists or the number of footnotes.
+    pub number: u32,
+    pub start_line: u32,
+    pub start_column: u32,
+    pub start_offset: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+    pub end_offset: u32,
+}
+
+/// `UINT32_MAX` when unknown.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryUnicodeOffsets {
+    pub start_utf16_offset: u32,
+    pub start_scalar_offset: u32,
+    pub end_utf16_offset: u32,
+    pub end_scalar_offset: u32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryField {
+    /// A `NodeStringField`, or `13` for the `AlignKind` bytes of tables and `14` for the
+    /// `BinaryHighlight`s of code blocks.
+    pub key: u16,
+    /// `0` if the bytes are in the string pool, `1` if they are in the source.
+    pub storage: u16,
+    pub offset: u32,
+    pub length: u32,
+}
+
+/// A highlighted token of a code block, in the string pool.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BinaryHighlight {
+    /// Byte range in the `Value` of the code block.
+    pub start: u32,
+    pub end: u32,
+    /// The TextMate scope, in the string pool.
+    pub scope_offset: u32,
+    pub scope_length: u32,
+}
+
+const _: () = {
+    assert!(std::mem::size_of::<BinaryHeader>() == binary::HEADER_SIZE);
+    assert!(std::mem::size_of::<BinaryNode>() == binary::NODE_SIZE);
+    assert!(std::mem::size_of::<BinaryUnicodeOffsets>() == binary::UNICODE_OFFSETS_SIZE);
+    assert!(std::mem::size_of::<BinaryField>() == binary::FIELD_SIZE);
+    assert!(std::mem::size_of::<BinaryHighlight>() == binary::HIGHLIGHT_SIZE);
+};
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// C API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+
+/// Parses a null terminated UTF-8 string into the binary encoding, pass `NULL` for the default
+/// config. Keep the string around to read the fields stored in the source. The bytes are only
+/// aligned to 1 byte, copy the records out of them (see the module documentation).
+///
+/// This will include an error message if `status` is an error.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_utf8_parse_to_binary(
+    c_str: *const c_char,
+    config: *const ParseConfig,
+) -> ByteVectorParseResult {
+    ByteVectorParseResult::from_output(crate::catch_panic(|| parse_to_binary(c_str, config)))
+}
+
+/// Checks that `buffer` is a binary encoded document of the UTF-8 `source` that can be read in
+/// place without going out of bounds, for buffers that weren't just returned by
+/// `markdown_parser_ffi_utf8_parse_to_binary` for the same source.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_binary_validate(buffer: ByteArray, source: ByteArray) -> ErrorStatus {
+    let is_valid = std::panic::catch_unwind(|| {
+        let source = byte_array(&source).and_then(|x| std::str::from_utf8(x).ok());
+        match (byte_array(&buffer), source) {
+            (Some(buffer), Some(source)) => BinaryDocument::new(buffer, source).is_ok(),
+            _ => false,
+        }
+    });
+    match is_valid {
+        Ok(true) => ErrorStatus::Ok,
+        _ => ErrorStatus::Error,
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+fn parse_to_binary(c_str: *const c_char, config: *const ParseConfig) -> Result<Vec<u8>, Failure> {
+    let source = unsafe { crate::read_c_str(c_str, "given input string") }?;
+    let config = unsafe { ParseConfig::read(config) };
+    let nodes = crate::parse_resolved(source, &config)?;
+    Ok(binary::encode(&nodes, source)?)
+}
+
+/// `None` for a `NULL` array that isn't empty.
+fn byte_array<'a>(array: &ByteArray) -> Option<&'a [u8]> {
+    match (array.data.is_null(), array.length) {
+        (_, 0) => Some(&[]),
+        (true, _) => None,
+        (false, length) => Some(unsafe { std::slice::from_raw_parts(array.data, length) }),
+    }
+}
@@ -0,0 +1,708 @@
+//! Opaque AST handle.
+//!
+//! Instead of decoding a serialized AST, callers can parse into a `Document` and walk it lazily by index.
+//! Nodes are laid out such that the children of every node occupy a contiguous range of indices, so a node's
+//! children are described by an `IndexRange` and the top-level nodes by `markdown_parser_ffi_document_roots`.
+//!
+//! Strings returned as `ByteArray`s point into memory owned by the document, they stay valid until
+//! `markdown_parser_ffi_document_free` is called and must not be freed by the caller.
+use std::collections::VecDeque;
+use std::os::raw::c_char;
+use markdown_format::Node;
+use crate::{ByteArray, ByteVector, ErrorCode, ErrorPosition, ErrorStatus, Failure, ParseConfig};
+
+/// Opaque type that owns a flattened markdown AST.
+pub struct Document {
+    nodes: Vec<DocumentNode>,
+    roots: IndexRange,
+    /// Backing storage of every string in the document.
+    strings: Vec<u8>,
+}
+
+struct DocumentNode {
+    kind: NodeKind,
+    parent: Option<usize>,
+    children: IndexRange,
+    position: Option<::markdown_format::SourceRange>,
+    attributes: NodeAttributes,
+    alignment: Vec<AlignKind>,
+    strings: Vec<(NodeStringField, std::ops::Range<usize>)>,
+    /// Byte ranges in the code block's value and of the scope in `strings`.
+    highlights: Vec<(std::ops::Range<usize>, std::ops::Range<usize>)>,
+}
+
+/// A contiguous range of node indices.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexRange {
+    pub start: libc::size_t,
+    pub length: libc::size_t,
+}
+
+/// Mirrors the variants of `markdown_format::Node`.
+///
+/// cbindgen:prefix-with-name
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    /// Returned for out of range indices.
+    Invalid = 0,
+    Text = 1,
+    Newline = 2,
+    Emphasis = 3,
+    Strong = 4,
+    Strikethrough = 5,
+    Image = 6,
+    ImageReference = 7,
+    Link = 8,
+    LinkReference = 9,
+    CodeBlock = 10,
+    InlineCode = 11,
+    List = 12,
+    ListItem = 13,
+    Heading = 14,
+    Table = 15,
+    TableRow = 16,
+    TableCell = 17,
+    HorizontalDivider = 18,
+    Definition = 19,
+    Paragraph = 20,
+    Blockquote = 21,
+    FootnoteReference = 22,
+    FootnoteDefinition = 23,
+    DisplayMath = 24,
+    InlineMath = 25,
+    Toml = 26,
+    Yaml = 27,
+    Html = 28,
+    Admonition = 29,
+    WikiLink = 30,
+    Embed = 31,
+    DefinitionList = 32,
+    DefinitionTerm = 33,
+    DefinitionDescription = 34,
+}
+
+/// The string valued fields of a node, see `markdown_parser_ffi_document_node_string`, which
+/// takes it as a `uint32_t`.
+///
+/// cbindgen:prefix-with-name
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeStringField {
+    /// Content of `Text`, `CodeBlock`, `InlineCode`, `DisplayMath`, `InlineMath`, `Toml`, `Yaml` and `Html`.
+    Value = 1,
+    Url = 2,
+    Title = 3,
+    Alt = 4,
+    Lang = 5,
+    Meta = 6,
+    Identifier = 7,
+    Label = 8,
+    /// Of `Admonition`.
+    Kind = 9,
+    /// Of `WikiLink` and `Embed`, which also have `Url` once resolved.
+    Target = 10,
+    Alias = 11,
+    HeadingAnchor = 12,
+}
+
+/// cbindgen:prefix-with-name
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckedState {
+    /// Not a task list item.
+    None = 0,
+    Unchecked = 1,
+    Checked = 2,
+}
+
+/// cbindgen:prefix-with-name
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// Not a reference.
+    None = 0,
+    Shortcut = 1,
+    Collapsed = 2,
+    Full = 3,
+}
+
+/// cbindgen:prefix-with-name
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignKind {
+    None = 0,
+    Left = 1,
+    Right = 2,
+    Center = 3,
+}
+
+/// The non-string fields of a node, fields that don't apply to the node’s kind are zeroed.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct NodeAttributes {
+    /// `1` to `6` for headings.
+    pub heading_level: u8,
+    /// Lists.
+    pub ordered: bool,
+    /// Lists, check `has_start`.
+    pub start: u32,
+    pub has_start: bool,
+    /// Lists, list items and definition lists.
+    pub spread: bool,
+    /// List items.
+    pub checked: CheckedState,
+    /// Link and image references.
+    pub reference_kind: ReferenceKind,
+    /// Footnote references and definitions, `0` if unreferenced.
+    pub footnote_number: libc::size_t,
+}
+
+impl Default for NodeAttributes {
+    fn default() -> Self {
+        Self {
+            heading_level: 0,
+            ordered: false,
+            start: 0,
+            has_start: false,
+            spread: false,
+            checked: CheckedState::None,
+            reference_kind: ReferenceKind::None,
+            footnote_number: 0,
+        }
+    }
+}
+
+/// One place in the source, see `markdown_format::Point`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Point {
+    pub line: libc::size_t,
+    pub column: libc::size_t,
+    pub offset: libc::size_t,
+    /// UTF-16 code unit offset, `0` unless the document was parsed with
+    /// `unicode_offsets`.
+    pub utf16_offset: libc::size_t,
+    /// Unicode scalar value offset, `0` unless the document was parsed with
+    /// `unicode_offsets`.
+    pub scalar_offset: libc::size_t,
+}
+
+/// A highlighted token of a code block, see `markdown_format::HighlightSpan`.
+#[repr(C)]
+pub struct CodeHighlight {
+    /// Byte range in the `Value` of the code block.
+    pub start: libc::size_t,
+    pub end: libc::size_t,
+    /// TextMate scope, such as `keyword.control.rust`.
+    pub scope: ByteArray,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NodePosition {
+    /// Whether the node has positional info, `start` and `end` are zeroed otherwise.
+    pub has_position: bool,
+    pub start: Point,
+    pub end: Point,
+}
+
+#[repr(C)]
+pub struct DocumentParseResult {
+    pub status: ErrorStatus,
+    /// `NULL` if `status` is an error.
+    pub document: *mut Document,
+    /// The error message if `status` is an error, `NULL` otherwise.
+    pub error: *mut ByteVector,
+    pub error_code: ErrorCode,
+    pub error_position: ErrorPosition,
+}
+
+impl DocumentParseResult {
+    fn from_output(output: Result<Vec<Node>, Failure>) -> Self {
+        match output {
+            Ok(nodes) => DocumentParseResult {
+                status: ErrorStatus::Ok,
+                document: Box::into_raw(Box::new(Document::new(&nodes))),
+                error: std::ptr::null_mut(),
+                error_code: ErrorCode::None,
+                error_position: ErrorPosition::default(),
+            },
+            Err(failure) => DocumentParseResult {
+                status: ErrorStatus::Error,
+                document: std::ptr::null_mut(),
+                error: Box::into_raw(Box::new(ByteVector(failure.message.into_bytes()))),
+                error_code: failure.code,
+                error_position: failure.position,
+            },
+        }
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// C API
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+
+/// Parses a null terminated UTF-8 string into a `Document`, pass `NULL` for the default config.
+///
+/// Free the document with `markdown_parser_ffi_document_free` and the error (if any) with
+/// `markdown_parser_ffi_byte_vector_free`.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_parse(
+    c_str: *const c_char,
+    config: *const ParseConfig,
+) -> DocumentParseResult {
+    let output = crate::catch_panic(|| {
+        let source = unsafe { crate::read_c_str(c_str, "given input string") }?;
+        let config = unsafe { ParseConfig::read(config) };
+        Ok(crate::parse_resolved(source, &config)?)
+    });
+    DocumentParseResult::from_output(output)
+}
+
+/// Same as `markdown_parser_ffi_document_parse` but reads caller owned UTF-8 bytes without copying them.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_parse_byte_array(
+    source: ByteArray,
+    config: *const ParseConfig,
+) -> DocumentParseResult {
+    let output = crate::catch_panic(|| {
+        if source.data.is_null() && source.length > 0 {
+            return Err(Failure::null("given input array"))
+        }
+        let bytes = match source.length {
+            0 => &[][..],
+            length => unsafe { std::slice::from_raw_parts(source.data, length) },
+        };
+        let config = unsafe { ParseConfig::read(config) };
+        Ok(crate::parse_resolved(crate::read_utf8(bytes)?, &config)?)
+    });
+    DocumentParseResult::from_output(output)
+}
+
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_free(document: *mut Document) {
+    if !document.is_null() {
+        unsafe {
+            drop(Box::from_raw(document));
+        }
+    }
+}
+
+/// Total number of nodes, valid indices are `0..count`.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_node_count(document: *const Document) -> libc::size_t {
+    unsafe { document.as_ref() }.map_or(0, |document| document.nodes.len())
+}
+
+/// The indices of the top-level nodes.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_roots(document: *const Document) -> IndexRange {
+    unsafe { document.as_ref() }.map(|document| document.roots).unwrap_or_default()
+}
+
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_node_kind(
+    document: *const Document,
+    index: libc::size_t,
+) -> NodeKind {
+    node(document, index).map_or(NodeKind::Invalid, |node| node.kind)
+}
+
+/// The indices of the node’s children (empty for leaves and out of range indices).
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_node_children(
+    document: *const Document,
+    index: libc::size_t,
+) -> IndexRange {
+    node(document, index).map(|node| node.children).unwrap_or_default()
+}
+
+/// Writes the index of the node’s parent to `out_parent`, returns an error for top-level nodes.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_node_parent(
+    document: *const Document,
+    index: libc::size_t,
+    out_parent: *mut libc::size_t,
+) -> ErrorStatus {
+    match (node(document, index).and_then(|node| node.parent), out_parent.is_null()) {
+        (Some(parent), false) => {
+            unsafe { *out_parent = parent };
+            ErrorStatus::Ok
+        }
+        _ => ErrorStatus::Error,
+    }
+}
+
+/// The given UTF-8 `NodeStringField` of the node, `data` is `NULL` if the node has no such field
+/// (or it is `None`) and for values that aren't a `NodeStringField`.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_node_string(
+    document: *const Document,
+    index: libc::size_t,
+    field: u32,
+) -> ByteArray {
+    let empty = ByteArray { data: std::ptr::null(), length: 0 };
+    let (Some(document), Some(field)) = (unsafe { document.as_ref() }, NodeStringField::read(field)) else {
+        return empty
+    };
+    let range = document.nodes
+        .get(index)
+        .and_then(|node| node.strings.iter().find(|(x, _)| *x == field))
+        .map(|(_, range)| range.clone());
+    match range {
+        Some(range) => ByteArray {
+            data: document.strings[range.clone()].as_ptr(),
+            length: range.len(),
+        },
+        None => empty,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_node_attributes(
+    document: *const Document,
+    index: libc::size_t,
+) -> NodeAttributes {
+    node(document, index).map(|node| node.attributes).unwrap_or_default()
+}
+
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_node_position(
+    document: *const Document,
+    index: libc::size_t,
+) -> NodePosition {
+    let Some(position) = node(document, index).and_then(|node| node.position.as_ref()) else {
+        return NodePosition::default()
+    };
+    let point = |point: &::markdown_format::Point| Point {
+        line: point.line,
+        column: point.column,
+        offset: point.offset,
+        utf16_offset: point.utf16_offset.unwrap_or_default(),
+        scalar_offset: point.scalar_offset.unwrap_or_default(),
+    };
+    NodePosition {
+        has_position: true,
+        start: point(&position.start),
+        end: point(&position.end),
+    }
+}
+
+/// Number of columns of a table node, `0` for other nodes.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_table_column_count(
+    document: *const Document,
+    index: libc::size_t,
+) -> libc::size_t {
+    node(document, index).map_or(0, |node| node.alignment.len())
+}
+
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_table_column_alignment(
+    document: *const Document,
+    index: libc::size_t,
+    column: libc::size_t,
+) -> AlignKind {
+    node(document, index)
+        .and_then(|node| node.alignment.get(column).copied())
+        .unwrap_or(AlignKind::None)
+}
+
+/// Number of highlighted tokens of a code block, `0` for other nodes and unless the document was
+/// parsed with `highlight`.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_code_highlight_count(
+    document: *const Document,
+    index: libc::size_t,
+) -> libc::size_t {
+    node(document, index).map_or(0, |node| node.highlights.len())
+}
+
+/// The given highlighted token of a code block, in order, the `scope` data is `NULL` if out of range.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_document_code_highlight(
+    document: *const Document,
+    index: libc::size_t,
+    highlight: libc::size_t,
+) -> CodeHighlight {
+    let empty = CodeHighlight { start: 0, end: 0, scope: ByteArray { data: std::ptr::null(), length: 0 } };
+    let Some((range, scope)) = node(document, index).and_then(|node| node.highlights.get(highlight)) else {
+        return empty
+    };
+    let strings = &document_ref(document).unwrap().strings;
+    CodeHighlight {
+        start: range.start,
+        end: range.end,
+        scope: ByteArray { data: strings[scope.clone()].as_ptr(), length: scope.len() },
+    }
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+fn document_ref<'a>(document: *const Document) -> Option<&'a Document> {
+    unsafe { document.as_ref() }
+}
+
+fn node<'a>(document: *const Document, index: libc::size_t) -> Option<&'a DocumentNode> {
+    document_ref(document)?.nodes.get(index)
+}
+
+impl Document {
+    fn new(nodes: &[Node]) -> Self {
+        let mut document = Document {
+            nodes: Vec::new(),
+            roots: IndexRange::default(),
+            strings: Vec::new(),
+        };
+        // Breadth first, so that siblings end up next to each other.
+        let mut queue = VecDeque::new();
+        document.roots = document.push_children(None, nodes, &mut queue);
+        while let Some((index, node)) = queue.pop_front() {
+            let children = document.push_children(Some(index), node.children(), &mut queue);
+            document.nodes[index].children = children;
+        }
+        document
+    }
+    fn push_children<'a>(
+        &mut self,
+        parent: Option<usize>,
+        children: &'a [Node],
+        queue: &mut VecDeque<(usize, &'a Node)>,
+    ) -> IndexRange {
+        let start = self.nodes.len();
+        for child in children {
+            let node = self.flatten(parent, child);
+            queue.push_back((self.nodes.len(), child));
+            self.nodes.push(node);
+        }
+        IndexRange { start, length: children.len() }
+    }
+    fn string(&mut self, value: &str) -> std::ops::Range<usize> {
+        let start = self.strings.len();
+        self.strings.extend_from_slice(value.as_bytes());
+        start..self.strings.len()
+    }
+    fn flatten(&mut self, parent: Option<usize>, node: &Node) -> DocumentNode {
+        use markdown_format::Node as N;
+        let mut strings = Vec::new();
+        let mut attributes = NodeAttributes::default();
+        let mut alignment = Vec::new();
+        let mut string = |field: NodeStringField, value: Option<&String>| {
+            if let Some(value) = value {
+                strings.push((field, self.string(value)));
+            }
+        };
+        let (kind, position) = match node {
+            N::Text(x) => {
+                string(NodeStringField::Value, Some(&x.value));
+                (NodeKind::Text, &x.position)
+            }
+            N::Newline(x) => (NodeKind::Newline, &x.position),
+            N::Emphasis(x) => (NodeKind::Emphasis, &x.position),
+            N::Strong(x) => (NodeKind::Strong, &x.position),
+            N::Strikethrough(x) => (NodeKind::Strikethrough, &x.position),
+            N::Image(x) => {
+                string(NodeStringField::Alt, Some(&x.alt));
+                string(NodeStringField::Url, Some(&x.url));
+                string(NodeStringField::Title, x.title.as_ref());
+                (NodeKind::Image, &x.position)
+            }
+            N::ImageReference(x) => {
+                string(NodeStringField::Alt, Some(&x.alt));
+                string(NodeStringField::Identifier, Some(&x.identifier));
+                string(NodeStringField::Label, x.label.as_ref());
+                string(NodeStringField::Url, x.url.as_ref());
+                string(NodeStringField::Title, x.title.as_ref());
+                attributes.reference_kind = (&x.reference_kind).into();
+                (NodeKind::ImageReference, &x.position)
+            }
+            N::Link(x) => {
+                string(NodeStringField::Url, Some(&x.url));
+                string(NodeStringField::Title, x.title.as_ref());
+                (NodeKind::Link, &x.position)
+            }
+            N::LinkReference(x) => {
+                string(NodeStringField::Identifier, Some(&x.identifier));
+                string(NodeStringField::Label, x.label.as_ref());
+                string(NodeStringField::Url, x.url.as_ref());
+                string(NodeStringField::Title, x.title.as_ref());
+                attributes.reference_kind = (&x.reference_kind).into();
+                (NodeKind::LinkReference, &x.position)
+            }
+            N::CodeBlock(x) => {
+                string(NodeStringField::Value, Some(&x.value));
+                string(NodeStringField::Lang, x.lang.as_ref());
+                string(NodeStringField::Meta, x.meta.as_ref());
+                (NodeKind::CodeBlock, &x.position)
+            }
+            N::InlineCode(x) => {
+                string(NodeStringField::Value, Some(&x.value));
+                (NodeKind::InlineCode, &x.position)
+            }
+            N::List(x) => {
+                attributes.ordered = x.ordered;
+                attributes.start = x.start.unwrap_or_default();
+                attributes.has_start = x.start.is_some();
+                attributes.spread = x.spread;
+                (NodeKind::List, &x.position)
+            }
+            N::ListItem(x) => {
+                attributes.spread = x.spread;
+                attributes.checked = match x.checked {
+                    None => CheckedState::None,
+                    Some(false) => CheckedState::Unchecked,
+                    Some(true) => CheckedState::Checked,
+                };
+                (NodeKind::ListItem, &x.position)
+            }
+            N::Heading(x) => {
+                attributes.heading_level = match x.level {
+                    markdown_format::HeadingLevel::H1 => 1,
+                    markdown_format::HeadingLevel::H2 => 2,
+                    markdown_format::HeadingLevel::H3 => 3,
+                    markdown_format::HeadingLevel::H4 => 4,
+                    markdown_format::HeadingLevel::H5 => 5,
+                    markdown_format::HeadingLevel::H6 => 6,
+                };
+                (NodeKind::Heading, &x.position)
+            }
+            N::Table(x) => {
+                alignment = x.alignment.iter().map(Into::into).collect();
+                (NodeKind::Table, &x.position)
+            }
+            N::TableRow(x) => (NodeKind::TableRow, &x.position),
+            N::TableCell(x) => (NodeKind::TableCell, &x.position),
+            N::HorizontalDivider(x) => (NodeKind::HorizontalDivider, &x.position),
+            N::Definition(x) => {
+                string(NodeStringField::Url, Some(&x.url));
+                string(NodeStringField::Title, x.title.as_ref());
+                string(NodeStringField::Identifier, Some(&x.identifier));
+                string(NodeStringField::Label, x.label.as_ref());
+                (NodeKind::Definition, &x.position)
+            }
+            N::Paragraph(x) => (NodeKind::Paragraph, &x.position),
+            N::Blockquote(x) => (NodeKind::Blockquote, &x.position),
+            N::FootnoteReference(x) => {
+                string(NodeStringField::Identifier, Some(&x.identifier));
+                string(NodeStringField::Label, x.label.as_ref());
+                attributes.footnote_number = x.number.unwrap_or_default();
+                (NodeKind::FootnoteReference, &x.position)
+            }
+            N::FootnoteDefinition(x) => {
+                string(NodeStringField::Identifier, Some(&x.identifier));
+                string(NodeStringField::Label, x.label.as_ref());
+                attributes.footnote_number = x.number.unwrap_or_default();
+                (NodeKind::FootnoteDefinition, &x.position)
+            }
+            N::DisplayMath(x) => {
+                string(NodeStringField::Value, Some(&x.value));
+                string(NodeStringField::Meta, x.meta.as_ref());
+                (NodeKind::DisplayMath, &x.position)
+            }
+            N::InlineMath(x) => {
+                string(NodeStringField::Value, Some(&x.value));
+                (NodeKind::InlineMath, &x.position)
+            }
+            N::Toml(x) => {
+                string(NodeStringField::Value, Some(&x.value));
+                (NodeKind::Toml, &x.position)
+            }
+            N::Yaml(x) => {
+                string(NodeStringField::Value, Some(&x.value));
+                (NodeKind::Yaml, &x.position)
+            }
+            N::Html(x) => {
+                string(NodeStringField::Value, Some(&x.value));
+                (NodeKind::Html, &x.position)
+            }
+            N::Admonition(x) => {
+                string(NodeStringField::Kind, Some(&x.kind));
+                string(NodeStringField::Title, x.title.as_ref());
+                (NodeKind::Admonition, &x.position)
+            }
+            N::WikiLink(x) => {
+                string(NodeStringField::Target, Some(&x.target));
+                string(NodeStringField::Alias, x.alias.as_ref());
+                string(NodeStringField::HeadingAnchor, x.heading_anchor.as_ref());
+                string(NodeStringField::Url, x.url.as_ref());
+                (NodeKind::WikiLink, &x.position)
+            }
+            N::Embed(x) => {
+                string(NodeStringField::Target, Some(&x.target));
+                string(NodeStringField::Alias, x.alias.as_ref());
+                string(NodeStringField::HeadingAnchor, x.heading_anchor.as_ref());
+                string(NodeStringField::Url, x.url.as_ref());
+                (NodeKind::Embed, &x.position)
+            }
+            N::DefinitionList(x) => {
+                attributes.spread = x.spread;
+                (NodeKind::DefinitionList, &x.position)
+            }
+            N::DefinitionTerm(x) => (NodeKind::DefinitionTerm, &x.position),
+            N::DefinitionDescription(x) => (NodeKind::DefinitionDescription, &x.position),
+        };
+        let highlights = match node {
+            N::CodeBlock(x) => x.highlights
+                .iter()
+                .flatten()
+                .map(|span| (span.range.clone(), self.string(&span.scope)))
+                .collect(),
+            _ => Vec::new(),
+        };
+        DocumentNode {
+            kind,
+            parent,
+            children: IndexRange::default(),
+            position: position.clone(),
+            attributes,
+            alignment,
+            strings,
+            highlights,
+        }
+    }
+}
+
+impl NodeStringField {
+    fn read(field: u32) -> Option<Self> {
+        let field = match field {
+            1 => Self::Value,
+            2 => Self::Url,
+            3 => Self::Title,
+            4 => Self::Alt,
+            5 => Self::Lang,
+            6 => Self::Meta,
+            7 => Self::Identifier,
+            8 => Self::Label,
+            9 => Self::Kind,
+            10 => Self::Target,
+            11 => Self::Alias,
+            12 => Self::HeadingAnchor,
+            _ => return None,
+        };
+        Some(field)
+    }
+}
+
+impl From<&markdown_format::ReferenceKind> for ReferenceKind {
+    fn from(value: &markdown_format::ReferenceKind) -> Self {
+        match value {
+            markdown_format::ReferenceKind::Shortcut => ReferenceKind::Shortcut,
+            markdown_format::ReferenceKind::Collapsed => ReferenceKind::Collapsed,
+            markdown_format::ReferenceKind::Full => ReferenceKind::Full,
+        }
+    }
+}
+
+impl From<&markdown_format::AlignKind> for AlignKind {
+    fn from(value: &markdown_format::AlignKind) -> Self {
+        match value {
+            markdown_format::AlignKind::None => AlignKind::None,
+            markdown_format::AlignKind::Left => AlignKind::Left,
+            markdown_format::AlignKind::Right => AlignKind::Right,
+            markdown_format::AlignKind::Center => AlignKind::Center,
+        }
+    }
+}
@@ -1,5 +1,13 @@
-use libc;
+// The C API takes raw pointers from C and Swift, which can't call `unsafe` functions any
+// differently, every entry point documents what it accepts and checks for `NULL`.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
 use std::os::raw::c_char;
+use markdown_format::Envelope;
+
+mod document;
+mod binary;
+pub use document::*;
+pub use binary::*;
 
 /// Slower but safer interface for sending strings over FFI boundaries.
 /// 
@@ -48,11 +56,234 @@ pub enum ErrorStatus {
     Error = 1,
 }
 
+/// What went wrong in a call whose `status` is `Error`.
+///
+/// cbindgen:prefix-with-name
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    /// The call succeeded.
+    None = 0,
+    /// A required pointer argument is `NULL`.
+    NullArgument = 1,
+    /// The source is not UTF-8, the position is where the valid prefix ends.
+    InvalidUtf8 = 2,
+    /// The document is beyond one of the `max_*` limits of the config, the position is of the
+    /// first node past a depth or table cell limit.
+    LimitExceeded = 3,
+    /// A syntax error of the parser, with its position.
+    Syntax = 4,
+    /// Encoding the output in the requested format failed.
+    Serialization = 5,
+    /// Another argument is invalid, e.g. a lint config that isn't a JSON object of rule levels.
+    InvalidArgument = 6,
+    /// The parser panicked, a bug rather than a problem with the arguments. The message is the
+    /// panic message.
+    Internal = 7,
+}
+
+/// Where in the source a call failed, `has_position` is `false` for errors not about one place.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErrorPosition {
+    pub has_position: bool,
+    /// 1-indexed.
+    pub line: libc::size_t,
+    /// 1-indexed, in bytes.
+    pub column: libc::size_t,
+    /// In bytes.
+    pub offset: libc::size_t,
+}
+
 /// The encoding format of a data model (like the parsed markdown AST).
+///
+/// Functions take it as a `uint32_t` and fail with `InvalidArgument` for other values.
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DataModelFormatType {
     JSON = 1,
     BinaryPropertyList = 2,
+    XmlPropertyList = 3,
+}
+
+/// C mirror of `markdown_format::ParseConfig`, selects which Markdown constructs
+/// are recognized.
+/// 
+/// Get one of the presets (e.g. `markdown_parser_ffi_parse_config_gfm`) and flip
+/// individual fields as needed.
+#[repr(C)]
+pub struct ParseConfig {
+    pub attention: bool,
+    pub autolink: bool,
+    pub block_quote: bool,
+    pub character_escape: bool,
+    pub character_reference: bool,
+    pub code_indented: bool,
+    pub code_fenced: bool,
+    pub code_text: bool,
+    pub definition: bool,
+    pub frontmatter: bool,
+    pub gfm_autolink_literal: bool,
+    pub gfm_footnote_definition: bool,
+    pub gfm_label_start_footnote: bool,
+    pub gfm_strikethrough: bool,
+    pub gfm_table: bool,
+    pub gfm_task_list_item: bool,
+    pub hard_break_escape: bool,
+    pub hard_break_trailing: bool,
+    pub heading_atx: bool,
+    pub heading_setext: bool,
+    pub html_flow: bool,
+    pub html_text: bool,
+    pub label_start_image: bool,
+    pub label_start_link: bool,
+    pub label_end: bool,
+    pub list_item: bool,
+    pub math_flow: bool,
+    pub math_text: bool,
+    pub thematic_break: bool,
+    /// GitHub-style callouts and `:::` containers, as `Admonition` nodes.
+    pub admonition: bool,
+    /// `[[Page|alias]]` and `![[image.png]]`, as `WikiLink` and `Embed` nodes.
+    pub wikilink: bool,
+    /// `Term` lines followed by `: Description` lines, as `DefinitionList` nodes.
+    pub definition_list: bool,
+    /// Also compute UTF-16 and Unicode scalar offsets of positions.
+    pub unicode_offsets: bool,
+    /// Also compute the highlights of code blocks in known languages.
+    pub highlight: bool,
+    /// Bounds of `markdown_format::ParseLimits`, `0` for unbounded. Parsing fails with an error
+    /// message for documents beyond them.
+    pub max_input_bytes: libc::size_t,
+    pub max_depth: libc::size_t,
+    pub max_nodes: libc::size_t,
+    pub max_table_cells: libc::size_t,
+}
+
+impl ParseConfig {
+    /// Reads the config behind the given pointer, `NULL` means the default config.
+    unsafe fn read(config: *const ParseConfig) -> ::markdown_format::ParseConfig {
+        if config.is_null() {
+            return ::markdown_format::ParseConfig::default()
+        }
+        ::markdown_format::ParseConfig::from(&*config)
+    }
+}
+
+impl From<&ParseConfig> for ::markdown_format::ParseConfig {
+    fn from(config: &ParseConfig) -> Self {
+        Self {
+            attention: config.attention,
+            autolink: config.autolink,
+            block_quote: config.block_quote,
+            character_escape: config.character_escape,
+            character_reference: config.character_reference,
+            code_indented: config.code_indented,
+            code_fenced: config.code_fenced,
+            code_text: config.code_text,
+            definition: config.definition,
+            frontmatter: config.frontmatter,
+            gfm_autolink_literal: config.gfm_autolink_literal,
+            gfm_footnote_definition: config.gfm_footnote_definition,
+            gfm_label_start_footnote: config.gfm_label_start_footnote,
+            gfm_strikethrough: config.gfm_strikethrough,
+            gfm_table: config.gfm_table,
+            gfm_task_list_item: config.gfm_task_list_item,
+            hard_break_escape: config.hard_break_escape,
+            hard_break_trailing: config.hard_break_trailing,
+            heading_atx: config.heading_atx,
+            heading_setext: config.heading_setext,
+            html_flow: config.html_flow,
+            html_text: config.html_text,
+            label_start_image: config.label_start_image,
+            label_start_link: config.label_start_link,
+            label_end: config.label_end,
+            list_item: config.list_item,
+            math_flow: config.math_flow,
+            math_text: config.math_text,
+            thematic_break: config.thematic_break,
+            admonition: config.admonition,
+            wikilink: config.wikilink,
+            definition_list: config.definition_list,
+            unicode_offsets: config.unicode_offsets,
+            highlight: config.highlight,
+            limits: ::markdown_format::ParseLimits {
+                max_input_bytes: Some(config.max_input_bytes).filter(|x| *x > 0),
+                max_depth: Some(config.max_depth).filter(|x| *x > 0),
+                max_nodes: Some(config.max_nodes).filter(|x| *x > 0),
+                max_table_cells: Some(config.max_table_cells).filter(|x| *x > 0),
+            },
+        }
+    }
+}
+
+impl From<::markdown_format::ParseConfig> for ParseConfig {
+    fn from(config: ::markdown_format::ParseConfig) -> Self {
+        Self {
+            attention: config.attention,
+            autolink: config.autolink,
+            block_quote: config.block_quote,
+            character_escape: config.character_escape,
+            character_reference: config.character_reference,
+            code_indented: config.code_indented,
+            code_fenced: config.code_fenced,
+            code_text: config.code_text,
+            definition: config.definition,
+            frontmatter: config.frontmatter,
+            gfm_autolink_literal: config.gfm_autolink_literal,
+            gfm_footnote_definition: config.gfm_footnote_definition,
+            gfm_label_start_footnote: config.gfm_label_start_footnote,
+            gfm_strikethrough: config.gfm_strikethrough,
+            gfm_table: config.gfm_table,
+            gfm_task_list_item: config.gfm_task_list_item,
+            hard_break_escape: config.hard_break_escape,
+            hard_break_trailing: config.hard_break_trailing,
+            heading_atx: config.heading_atx,
+            heading_setext: config.heading_setext,
+            html_flow: config.html_flow,
+            html_text: config.html_text,
+            label_start_image: config.label_start_image,
+            label_start_link: config.label_start_link,
+            label_end: config.label_end,
+            list_item: config.list_item,
+            math_flow: config.math_flow,
+            math_text: config.math_text,
+            thematic_break: config.thematic_break,
+            admonition: config.admonition,
+            wikilink: config.wikilink,
+            definition_list: config.definition_list,
+            unicode_offsets: config.unicode_offsets,
+            highlight: config.highlight,
+            max_input_bytes: config.limits.max_input_bytes.unwrap_or_default(),
+            max_depth: config.limits.max_depth.unwrap_or_default(),
+            max_nodes: config.limits.max_nodes.unwrap_or_default(),
+            max_table_cells: config.limits.max_table_cells.unwrap_or_default(),
+        }
+    }
+}
+
+/// The construct set used when no config is given.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_parse_config_default() -> ParseConfig {
+    ::markdown_format::ParseConfig::default().into()
+}
+
+/// Plain CommonMark.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_parse_config_commonmark() -> ParseConfig {
+    ::markdown_format::ParseConfig::commonmark().into()
+}
+
+/// CommonMark plus GitHub Flavored Markdown.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_parse_config_gfm() -> ParseConfig {
+    ::markdown_format::ParseConfig::gfm().into()
+}
+
+/// GFM plus frontmatter, math, admonitions, wiki links and definition lists.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_parse_config_everything() -> ParseConfig {
+    ::markdown_format::ParseConfig::everything().into()
 }
 
 #[no_mangle]
@@ -72,64 +303,67 @@ pub extern "C" fn markdown_parser_ffi_byte_vector_free(byte_vector: *mut ByteVec
     }
 }
 
+/// The byte at `index`, an error for `NULL` or out of bounds indices.
 #[no_mangle]
 pub extern "C" fn markdown_parser_ffi_byte_vector_get(
     byte_vector: *const ByteVector,
     index: libc::size_t,
 ) -> ByteResult {
-    unsafe {
-        let byte_vector = &*byte_vector;
-        if index < byte_vector.0.len() {
-            ByteResult {
-                data: byte_vector.0[index],
-                status: ErrorStatus::Ok,
-            }
-        } else {
-            ByteResult {
-                data: 0,
-                status: ErrorStatus::Error,
-            }
-        }
+    match unsafe { byte_vector.as_ref() }.and_then(|x| x.0.get(index)) {
+        Some(byte) => ByteResult {
+            data: *byte,
+            status: ErrorStatus::Ok,
+        },
+        None => ByteResult {
+            data: 0,
+            status: ErrorStatus::Error,
+        },
     }
 }
 
+/// Writes the byte at `index` to `out_char`, an error for `NULL` pointers or out of bounds indices.
 #[no_mangle]
 pub extern "C" fn markdown_parser_ffi_byte_vector_read(
     byte_vector: *const ByteVector,
     index: libc::size_t,
     out_char: *mut u8,
 ) -> ErrorStatus {
-    unsafe {
-        let byte_vector = &*byte_vector;
-        if byte_vector.0.len() > index {
-            if !out_char.is_null() {
-                *out_char = byte_vector.0[index];
-                ErrorStatus::Ok
-            } else {
-                ErrorStatus::Error
-            }
-        } else {
-            ErrorStatus::Error
+    match unsafe { byte_vector.as_ref() }.and_then(|x| x.0.get(index)) {
+        Some(byte) if !out_char.is_null() => {
+            unsafe { *out_char = *byte };
+            ErrorStatus::Ok
         }
+        _ => ErrorStatus::Error,
     }
 }
 
+/// `0` for `NULL`.
 #[no_mangle]
 pub extern "C" fn markdown_parser_ffi_byte_vector_length(
     byte_vector: *const ByteVector,
 ) -> libc::size_t {
-    unsafe {
-        (*byte_vector).0.len() as libc::size_t
+    unsafe { byte_vector.as_ref() }.map_or(0, |x| x.0.len() as libc::size_t)
+}
+
+/// The bytes of the vector, valid until it is pushed to or freed, `data` is `NULL` for `NULL`.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_byte_vector_data(
+    byte_vector: *const ByteVector,
+) -> ByteArray {
+    match byte_vector_ref(byte_vector) {
+        Some(byte_vector) => ByteArray { data: byte_vector.0.as_ptr(), length: byte_vector.0.len() },
+        None => ByteArray { data: std::ptr::null(), length: 0 },
     }
 }
 
+/// Does nothing for `NULL`.
 #[no_mangle]
 pub extern "C" fn markdown_parser_ffi_byte_vector_push(
     byte_vector: *mut ByteVector,
     byte: u8,
 ) {
-    unsafe {
-        (*byte_vector).0.push(byte);
+    if let Some(byte_vector) = unsafe { byte_vector.as_mut() } {
+        byte_vector.0.push(byte);
     }
 }
 
@@ -138,6 +372,8 @@ pub struct ByteVectorParseResult {
     pub status: ErrorStatus,
     /// Either the parsed markdown string-encoded AST or an error message (check `status` to determine which one)
     pub output: *mut ByteVector,
+    pub error_code: ErrorCode,
+    pub error_position: ErrorPosition,
 }
 
 /// I wonder this to be a slightly safer version compared to `markdown_parser_ffi_parse_utf8_markdown_unsafe`.
@@ -147,35 +383,117 @@ pub struct ByteVectorParseResult {
 pub extern "C" fn markdown_parser_ffi_utf8_byte_vector_parse(
     markdown_source: *const ByteVector,
 ) -> ByteVectorParseResult {
-    let markdown_source = unsafe {
-        &*markdown_source
-    };
-    let output = std::str::from_utf8(&markdown_source.0)
-        .map_err(|x| Box::new(x) as Box<dyn std::error::Error>)
-        .and_then(::markdown_format::parse)
-        .and_then(|nodes| {
-            Ok(serde_json::to_string::<Vec<::markdown_format::Node>>(&nodes)?)
-        })
-        .map(String::into_bytes)
-        .map(ByteVector)
-        .map(Box::new)
-        .map(Box::into_raw)
-        .map_err(|x| x.to_string())
-        .map_err(String::into_bytes)
-        .map_err(ByteVector)
-        .map_err(Box::new)
-        .map_err(Box::into_raw);
-    match output {
-        Ok(payload) => {
-            ByteVectorParseResult {
-                output: payload,
-                status: ErrorStatus::Ok,
+    markdown_parser_ffi_utf8_byte_vector_parse_with_config(markdown_source, std::ptr::null())
+}
+
+/// Same as `markdown_parser_ffi_utf8_byte_vector_parse` but with the given construct set,
+/// pass `NULL` for the default config.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_utf8_byte_vector_parse_with_config(
+    markdown_source: *const ByteVector,
+    config: *const ParseConfig,
+) -> ByteVectorParseResult {
+    markdown_parser_ffi_utf8_byte_vector_parse_to_format(markdown_source, config, DataModelFormatType::JSON as u32)
+}
+
+/// Parses the given UTF-8 source and encodes the AST with the given `DataModelFormatType`, pass `NULL` for the default config.
+/// 
+/// The nodes are wrapped in a `markdown_format::Envelope`, `{"version": 2, "nodes": [...]}`, as in
+/// every AST this library outputs.
+/// 
+/// Property list output is what Swift’s `PropertyListDecoder` expects; `None` fields are omitted instead of being `null`.
+/// 
+/// This will include an error message if `status` is an error.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_utf8_byte_vector_parse_to_format(
+    markdown_source: *const ByteVector,
+    config: *const ParseConfig,
+    format: u32,
+) -> ByteVectorParseResult {
+    let output = catch_panic(|| {
+        let markdown_source = unsafe { markdown_source.as_ref() }.ok_or_else(|| Failure::null("given byte vector"))?;
+        let config = unsafe { ParseConfig::read(config) };
+        let format = DataModelFormatType::read(format)?;
+        let source = read_utf8(&markdown_source.0)?;
+        Ok(encode_data_model(&Envelope::new(parse_resolved(source, &config)?), format)?)
+    });
+    ByteVectorParseResult::from_output(output)
+}
+
+/// Same as `markdown_parser_ffi_utf8_byte_vector_parse_to_format` but reads a null terminated string.
+/// 
+/// The output is always a `ByteVector` since binary property lists may contain null bytes.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_utf8_parse_to_format(
+    c_str: *const c_char,
+    config: *const ParseConfig,
+    format: u32,
+) -> ByteVectorParseResult {
+    let output = catch_panic(|| {
+        let source = unsafe { read_c_str(c_str, "given input string") }?;
+        let config = unsafe { ParseConfig::read(config) };
+        let format = DataModelFormatType::read(format)?;
+        Ok(encode_data_model(&Envelope::new(parse_resolved(source, &config)?), format)?)
+    });
+    ByteVectorParseResult::from_output(output)
+}
+
+/// Parses the given null terminated UTF-8 string and encodes its outline (a tree of
+/// `markdown_format::outline::Section`s) with the given format, pass `NULL` for the default config.
+/// 
+/// This will include an error message if `status` is an error.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_utf8_outline_to_format(
+    c_str: *const c_char,
+    config: *const ParseConfig,
+    format: u32,
+) -> ByteVectorParseResult {
+    let output = catch_panic(|| {
+        let source = unsafe { read_c_str(c_str, "given input string") }?;
+        let config = unsafe { ParseConfig::read(config) };
+        let format = DataModelFormatType::read(format)?;
+        let nodes = parse_resolved(source, &config)?;
+        Ok(encode_data_model(&::markdown_format::outline::outline(&nodes), format)?)
+    });
+    ByteVectorParseResult::from_output(output)
+}
+
+/// Parses the given null terminated UTF-8 string and encodes its lint diagnostics (a list of
+/// `markdown_format::lint::Diagnostic`s in source order) with the given format.
+///
+/// `lint_config` is an optional null terminated JSON object of rule levels, as in
+/// `{"bare-url": "off", "heading-increment": "error"}`, pass `NULL` for the built-in rules at
+/// their default level. Pass `NULL` as `config` for the default parse config.
+///
+/// This will include an error message if `status` is an error.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_utf8_lint_to_format(
+    c_str: *const c_char,
+    config: *const ParseConfig,
+    lint_config: *const c_char,
+    format: u32,
+) -> ByteVectorParseResult {
+    ByteVectorParseResult::from_output(catch_panic(|| lint_to_format(c_str, config, lint_config, format)))
+}
+
+impl ByteVectorParseResult {
+    fn from_output(output: Result<Vec<u8>, Failure>) -> Self {
+        match output {
+            Ok(payload) => {
+                ByteVectorParseResult {
+                    output: Box::into_raw(Box::new(ByteVector(payload))),
+                    status: ErrorStatus::Ok,
+                    error_code: ErrorCode::None,
+                    error_position: ErrorPosition::default(),
+                }
             }
-        }
-        Err(message) => {
-            ByteVectorParseResult {
-                output: message,
-                status: ErrorStatus::Error,
+            Err(failure) => {
+                ByteVectorParseResult {
+                    output: Box::into_raw(Box::new(ByteVector(failure.message.into_bytes()))),
+                    status: ErrorStatus::Error,
+                    error_code: failure.code,
+                    error_position: failure.position,
+                }
             }
         }
     }
@@ -220,7 +538,7 @@ pub extern "C" fn markdown_parser_ffi_utf8_byte_vector_parse(
 /// As far as I know the above code should be generally safe. But if you’re paranoid use the possibly safer `ByteVector` type and associated API.
 /// 
 /// Also while less safe if you’re a **no copying** kinda guy with no fear; here’s a very simply alternative that that will be automatically freed when no longer in use by the Swift runtime:
-/// ```
+/// ```swift
 /// // Function to convert Swift String to C String
 /// func toCString(_ string: String) -> UnsafePointer<CChar> {
 ///     return (string as NSString).utf8String!
@@ -230,38 +548,22 @@ pub extern "C" fn markdown_parser_ffi_utf8_byte_vector_parse(
 pub extern "C" fn markdown_parser_ffi_utf8_parse_to_json_string(
     c_str: *const c_char,
 ) -> RustCStringParseResult {
-    if c_str.is_null() {
-        return RustCStringParseResult {
-            output: RustCStringPointer::from_string("given input string is NULL").unwrap_or(RustCStringPointer::EMPTY),
-            status: ErrorStatus::Error,
-        };
-    }
-
-    let c_str = unsafe { std::ffi::CStr::from_ptr(c_str) };
-    let r_str: &str = match c_str.to_str() {
-        Ok(s) => s,
-        Err(_) => {
-            return RustCStringParseResult {
-                output: RustCStringPointer::from_string("given input string is not valid UTF8").unwrap_or(RustCStringPointer::EMPTY),
-                status: ErrorStatus::Error,
-            }
-        }
-    };
+    markdown_parser_ffi_utf8_parse_to_json_string_with_config(c_str, std::ptr::null())
+}
 
-    match parse_markdown(r_str) {
-        Ok(result) => {
-            RustCStringParseResult {
-                status: ErrorStatus::Ok,
-                output: RustCStringPointer::from_string(result).unwrap_or(RustCStringPointer::EMPTY),
-            }
-        }
-        Err(error) => {
-            RustCStringParseResult {
-                status: ErrorStatus::Error,
-                output: RustCStringPointer::from_string(error.to_string()).unwrap_or(RustCStringPointer::EMPTY),
-            }
-        }
-    }
+/// Same as `markdown_parser_ffi_utf8_parse_to_json_string` but with the given construct set,
+/// pass `NULL` for the default config.
+#[no_mangle]
+pub extern "C" fn markdown_parser_ffi_utf8_parse_to_json_string_with_config(
+    c_str: *const c_char,
+    config: *const ParseConfig,
+) -> RustCStringParseResult {
+    let output = catch_panic(|| {
+        let source = unsafe { read_c_str(c_str, "given input string") }?;
+        let config = unsafe { ParseConfig::read(config) };
+        parse_markdown(source, &config)
+    });
+    RustCStringParseResult::from_output(output)
 }
 
 #[repr(C)]
@@ -269,6 +571,23 @@ pub struct RustCStringParseResult {
     pub status: ErrorStatus,
     /// Either the parsed markdown string-encoded AST or an error message (check `status` to determine which one)
     pub output: RustCStringPointer,
+    pub error_code: ErrorCode,
+    pub error_position: ErrorPosition,
+}
+
+impl RustCStringParseResult {
+    fn from_output(output: Result<String, Failure>) -> Self {
+        let (status, output, error_code, error_position) = match output {
+            Ok(output) => (ErrorStatus::Ok, output, ErrorCode::None, ErrorPosition::default()),
+            Err(failure) => (ErrorStatus::Error, failure.message, failure.code, failure.position),
+        };
+        RustCStringParseResult {
+            status,
+            output: RustCStringPointer::from_string(output).unwrap_or(RustCStringPointer::EMPTY),
+            error_code,
+            error_position,
+        }
+    }
 }
 
 #[no_mangle]
@@ -289,7 +608,508 @@ pub extern "C" fn markdown_parser_ffi_hello_world() {
 
 
 // implementation
-fn parse_markdown(input: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let nodes = markdown_format::parse(input)?;
-    Ok(serde_json::to_string::<Vec<::markdown_format::Node>>(&nodes)?)
+fn parse_markdown(input: &str, config: &::markdown_format::ParseConfig) -> Result<String, Failure> {
+    let envelope = Envelope::new(parse_resolved(input, config)?);
+    Ok(serde_json::to_string(&envelope).map_err(::markdown_format::Error::from)?)
+}
+
+/// Parses with references resolved (see `markdown_format::resolve`), so that consumers don’t have to.
+fn parse_resolved(input: &str, config: &::markdown_format::ParseConfig) -> Result<Vec<::markdown_format::Node>, ::markdown_format::Error> {
+    let mut nodes = markdown_format::parse_with(input, config)?;
+    markdown_format::resolve::resolve(&mut nodes);
+    Ok(nodes)
+}
+
+fn lint_to_format(
+    c_str: *const c_char,
+    config: *const ParseConfig,
+    lint_config: *const c_char,
+    format: u32,
+) -> Result<Vec<u8>, Failure> {
+    let format = DataModelFormatType::read(format)?;
+    let source = unsafe { read_c_str(c_str, "given input string") }?;
+    let config = unsafe { ParseConfig::read(config) };
+    let mut linter = ::markdown_format::lint::Linter::default();
+    linter.configure(&unsafe { read_lint_config(lint_config) }?);
+    let nodes = parse_resolved(source, &config)?;
+    Ok(encode_data_model(&linter.lint(source, &nodes), format)?)
+}
+
+fn byte_vector_ref<'a>(byte_vector: *const ByteVector) -> Option<&'a ByteVector> {
+    unsafe { byte_vector.as_ref() }
+}
+
+/// A failed call, spread over the `status`, `error_code`, `error_position` and error message of
+/// the result structs.
+#[derive(Debug)]
+struct Failure {
+    code: ErrorCode,
+    position: ErrorPosition,
+    message: String,
+}
+
+impl Failure {
+    /// `what` names the argument in the message.
+    fn null(what: &str) -> Self {
+        Self { code: ErrorCode::NullArgument, position: ErrorPosition::default(), message: format!("{what} is NULL") }
+    }
+}
+
+/// Runs the body of an entry point, a panic must not unwind into the caller.
+fn catch_panic<T>(call: impl FnOnce() -> Result<T, Failure>) -> Result<T, Failure> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(call)).unwrap_or_else(|payload| {
+        let reason = payload
+            .downcast_ref::<&str>()
+            .map(|x| x.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| String::from("unknown panic"));
+        Err(::markdown_format::Error::Internal { reason }.into())
+    })
+}
+
+impl From<::markdown_format::Error> for Failure {
+    fn from(error: ::markdown_format::Error) -> Self {
+        use ::markdown_format::Error;
+        let code = match &error {
+            Error::InvalidUtf8(_) => ErrorCode::InvalidUtf8,
+            Error::LimitExceeded(_) => ErrorCode::LimitExceeded,
+            Error::Syntax { .. } => ErrorCode::Syntax,
+            Error::Serialization(_) => ErrorCode::Serialization,
+            Error::Internal { .. } => ErrorCode::Internal,
+            Error::InvalidEdit(_) => ErrorCode::InvalidArgument,
+        };
+        let position = error.position().map_or_else(ErrorPosition::default, |point| ErrorPosition {
+            has_position: true,
+            line: point.line,
+            column: point.column,
+            offset: point.offset,
+        });
+        Self { code, position, message: error.to_string() }
+    }
+}
+
+/// Invalid UTF-8 fails with the position of the end of the valid prefix.
+fn read_utf8(bytes: &[u8]) -> Result<&str, Failure> {
+    std::str::from_utf8(bytes).map_err(|error| {
+        let valid = &bytes[..error.valid_up_to()];
+        let line_start = valid.iter().rposition(|x| *x == b'\n').map_or(0, |x| x + 1);
+        let position = ErrorPosition {
+            has_position: true,
+            line: valid.iter().filter(|x| **x == b'\n').count() + 1,
+            column: valid.len() - line_start + 1,
+            offset: valid.len(),
+        };
+        Failure { position, ..::markdown_format::Error::from(error).into() }
+    })
+}
+
+/// `what` names the string in error messages.
+unsafe fn read_c_str<'a>(c_str: *const c_char, what: &str) -> Result<&'a str, Failure> {
+    if c_str.is_null() {
+        return Err(Failure::null(what))
+    }
+    read_utf8(std::ffi::CStr::from_ptr(c_str).to_bytes())
+}
+
+unsafe fn read_lint_config(json: *const c_char) -> Result<::markdown_format::lint::LintConfig, Failure> {
+    if json.is_null() {
+        return Ok(Default::default())
+    }
+    let invalid = |message: String| Failure {
+        code: ErrorCode::InvalidArgument,
+        position: ErrorPosition::default(),
+        message: format!("invalid lint config: {message}"),
+    };
+    let json = read_c_str(json, "given lint config").map_err(|x| invalid(x.message))?;
+    serde_json::from_str(json).map_err(|x| invalid(x.to_string()))
+}
+
+impl DataModelFormatType {
+    fn read(format: u32) -> Result<Self, Failure> {
+        match format {
+            1 => Ok(Self::JSON),
+            2 => Ok(Self::BinaryPropertyList),
+            3 => Ok(Self::XmlPropertyList),
+            _ => Err(Failure {
+                code: ErrorCode::InvalidArgument,
+                position: ErrorPosition::default(),
+                message: format!("unknown data model format {format}"),
+            }),
+        }
+    }
+}
+
+fn encode_data_model<T: serde::Serialize>(value: &T, format: DataModelFormatType) -> Result<Vec<u8>, ::markdown_format::Error> {
+    let plist_error = |x: plist::Error| ::markdown_format::Error::Serialization(Box::new(x));
+    match format {
+        DataModelFormatType::JSON => Ok(serde_json::to_vec(value)?),
+        DataModelFormatType::BinaryPropertyList => {
+            let mut output = Vec::new();
+            plist::to_writer_binary(&mut output, value).map_err(plist_error)?;
+            Ok(output)
+        }
+        DataModelFormatType::XmlPropertyList => {
+            let mut output = Vec::new();
+            plist::to_writer_xml(&mut output, value).map_err(plist_error)?;
+            Ok(output)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLES: &[&str] = &[
+        include_str!("../../samples/battery-1.md"),
+        include_str!("../../samples/general-1.md"),
+        include_str!("../../samples/misc-markdown-1.md"),
+        include_str!("../../samples/misc-markdown-2.md"),
+    ];
+
+    fn parse_to_format(source: &str, format: DataModelFormatType) -> Vec<u8> {
+        let source = std::ffi::CString::new(source).unwrap();
+        let result = markdown_parser_ffi_utf8_parse_to_format(source.as_ptr(), std::ptr::null(), format as u32);
+        let output = unsafe { Box::from_raw(result.output) };
+        assert!(matches!(result.status, ErrorStatus::Ok), "{}", String::from_utf8_lossy(&output.0));
+        output.0
+    }
+
+    /// Property lists have no null, `None` fields are omitted instead.
+    fn without_nulls(value: serde_json::Value) -> serde_json::Value {
+        match value {
+            serde_json::Value::Object(object) => object
+                .into_iter()
+                .filter(|(_, value)| !value.is_null())
+                .map(|(key, value)| (key, without_nulls(value)))
+                .collect(),
+            serde_json::Value::Array(array) => array
+                .into_iter()
+                .map(without_nulls)
+                .collect(),
+            value => value,
+        }
+    }
+
+    #[test]
+    fn property_list_encodings_match_json() {
+        for source in SAMPLES {
+            let json = parse_to_format(source, DataModelFormatType::JSON);
+            let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+            let json = without_nulls(json);
+            let binary = parse_to_format(source, DataModelFormatType::BinaryPropertyList);
+            assert!(binary.starts_with(b"bplist00"));
+            let xml = parse_to_format(source, DataModelFormatType::XmlPropertyList);
+            assert!(xml.starts_with(b"<?xml"));
+            for encoded in [binary, xml] {
+                let decoded = plist::Value::from_reader(std::io::Cursor::new(encoded)).unwrap();
+                assert_eq!(serde_json::to_value(decoded).unwrap(), json);
+            }
+        }
+    }
+
+    #[test]
+    fn outputs_are_envelopes() {
+        for source in SAMPLES {
+            let expected = Envelope::new(parse_resolved(source, &Default::default()).unwrap());
+            let json = parse_to_format(source, DataModelFormatType::JSON);
+            assert_eq!(Envelope::from_json(std::str::from_utf8(&json).unwrap()).unwrap(), expected);
+            for format in [DataModelFormatType::BinaryPropertyList, DataModelFormatType::XmlPropertyList] {
+                let encoded = parse_to_format(source, format);
+                assert_eq!(plist::from_bytes::<Envelope>(&encoded).unwrap(), expected);
+            }
+            let c_string = std::ffi::CString::new(*source).unwrap();
+            let result = markdown_parser_ffi_utf8_parse_to_json_string(c_string.as_ptr());
+            assert!(matches!(result.status, ErrorStatus::Ok));
+            let json = unsafe { std::ffi::CStr::from_ptr(result.output.pointer) }.to_str().unwrap();
+            assert_eq!(Envelope::from_json(json).unwrap(), expected);
+            markdown_parser_ffi_rust_c_string_free(result.output);
+        }
+    }
+
+    #[test]
+    fn byte_vector_null_checks() {
+        let null = std::ptr::null_mut::<ByteVector>();
+        let mut byte = 0;
+        assert_eq!(markdown_parser_ffi_byte_vector_length(null), 0);
+        assert!(matches!(markdown_parser_ffi_byte_vector_get(null, 0).status, ErrorStatus::Error));
+        assert!(matches!(markdown_parser_ffi_byte_vector_read(null, 0, &mut byte), ErrorStatus::Error));
+        markdown_parser_ffi_byte_vector_push(null, b'a');
+        let result = markdown_parser_ffi_utf8_byte_vector_parse(null);
+        let output = unsafe { Box::from_raw(result.output) };
+        assert!(matches!(result.status, ErrorStatus::Error));
+        assert_eq!(output.0, b"given byte vector is NULL");
+
+        let byte_vector = markdown_parser_ffi_byte_vector_new();
+        markdown_parser_ffi_byte_vector_push(byte_vector, b'a');
+        assert!(matches!(markdown_parser_ffi_byte_vector_read(byte_vector, 0, std::ptr::null_mut()), ErrorStatus::Error));
+        assert!(matches!(markdown_parser_ffi_byte_vector_read(byte_vector, 1, &mut byte), ErrorStatus::Error));
+        assert!(matches!(markdown_parser_ffi_byte_vector_read(byte_vector, 0, &mut byte), ErrorStatus::Ok));
+        assert_eq!(byte, b'a');
+        markdown_parser_ffi_byte_vector_free(byte_vector);
+    }
+
+    #[test]
+    fn parse_config_limits() {
+        let mut config = markdown_parser_ffi_parse_config_gfm();
+        assert_eq!(config.max_depth, ::markdown_format::ParseLimits::DEFAULT_MAX_DEPTH);
+        assert_eq!(config.max_nodes, 0);
+        config.max_depth = 2;
+        let source = std::ffi::CString::new("> a").unwrap();
+        let result = markdown_parser_ffi_utf8_parse_to_format(source.as_ptr(), &config, DataModelFormatType::JSON as u32);
+        let output = unsafe { Box::from_raw(result.output) };
+        assert!(matches!(result.status, ErrorStatus::Error));
+        assert_eq!(output.0, b"nodes nest deeper than the limit of 2 at 1:3");
+        config.max_depth = 0;
+        let result = markdown_parser_ffi_utf8_parse_to_format(source.as_ptr(), &config, DataModelFormatType::JSON as u32);
+        markdown_parser_ffi_byte_vector_free(result.output);
+        assert!(matches!(result.status, ErrorStatus::Ok));
+    }
+
+    #[test]
+    fn error_codes_and_positions() {
+        let parse = |source: &[u8], config: &ParseConfig| {
+            let source = std::ffi::CString::new(source).unwrap();
+            let result = markdown_parser_ffi_utf8_parse_to_format(source.as_ptr(), config, DataModelFormatType::JSON as u32);
+            markdown_parser_ffi_byte_vector_free(result.output);
+            let position = result.error_position;
+            (result.error_code, position.has_position.then_some((position.line, position.column, position.offset)))
+        };
+        let mut config = markdown_parser_ffi_parse_config_default();
+        assert_eq!(parse(b"a", &config), (ErrorCode::None, None));
+        assert_eq!(parse(b"a\nb\xffc", &config), (ErrorCode::InvalidUtf8, Some((2, 2, 3))));
+        config.max_depth = 2;
+        assert_eq!(parse(b"a\n\n> b", &config), (ErrorCode::LimitExceeded, Some((3, 3, 5))));
+        config.max_depth = 0;
+        config.max_input_bytes = 1;
+        assert_eq!(parse(b"ab", &config), (ErrorCode::LimitExceeded, None));
+
+        let result = markdown_parser_ffi_utf8_parse_to_json_string(std::ptr::null());
+        assert_eq!(result.error_code, ErrorCode::NullArgument);
+        markdown_parser_ffi_rust_c_string_free(result.output);
+        let result = markdown_parser_ffi_document_parse_byte_array(ByteArray { data: b"\xff".as_ptr(), length: 1 }, std::ptr::null());
+        assert_eq!((result.error_code, result.error_position.offset), (ErrorCode::InvalidUtf8, 0));
+        markdown_parser_ffi_byte_vector_free(result.error);
+        let source = std::ffi::CString::new("a").unwrap();
+        let lint_config = std::ffi::CString::new("[]").unwrap();
+        let result = markdown_parser_ffi_utf8_lint_to_format(source.as_ptr(), std::ptr::null(), lint_config.as_ptr(), DataModelFormatType::JSON as u32);
+        assert_eq!(result.error_code, ErrorCode::InvalidArgument);
+        markdown_parser_ffi_byte_vector_free(result.output);
+        for format in [0, 4, u32::MAX] {
+            let result = markdown_parser_ffi_utf8_parse_to_format(source.as_ptr(), std::ptr::null(), format);
+            let output = unsafe { Box::from_raw(result.output) };
+            assert_eq!(result.error_code, ErrorCode::InvalidArgument);
+            assert_eq!(output.0, format!("unknown data model format {format}").into_bytes());
+        }
+    }
+
+    #[test]
+    fn panics_are_internal_errors() {
+        // The `markdown` crate panics on it.
+        let source = std::ffi::CString::new("a\n\nb\n---\n---\ntitle: x\n---\n").unwrap();
+        let config = markdown_parser_ffi_parse_config_everything();
+        let result = markdown_parser_ffi_utf8_parse_to_format(source.as_ptr(), &config, DataModelFormatType::JSON as u32);
+        let output = unsafe { Box::from_raw(result.output) };
+        assert!(matches!(result.status, ErrorStatus::Error));
+        assert_eq!(result.error_code, ErrorCode::Internal);
+        assert!(output.0.starts_with(b"internal parser error: "));
+        let result = markdown_parser_ffi_utf8_parse_to_json_string(source.as_ptr());
+        assert_eq!(result.error_code, ErrorCode::Internal);
+        markdown_parser_ffi_rust_c_string_free(result.output);
+        let result = markdown_parser_ffi_utf8_parse_to_binary(source.as_ptr(), std::ptr::null());
+        assert_eq!(result.error_code, ErrorCode::Internal);
+        markdown_parser_ffi_byte_vector_free(result.output);
+        let result = markdown_parser_ffi_document_parse(source.as_ptr(), std::ptr::null());
+        assert_eq!(result.error_code, ErrorCode::Internal);
+        markdown_parser_ffi_byte_vector_free(result.error);
+
+        // Panics of this crate are caught as well.
+        let result = catch_panic::<()>(|| panic!("oops"));
+        assert_eq!(result.unwrap_err().message, "internal parser error: oops");
+    }
+
+    #[test]
+    fn binary_records_read_in_place() {
+        let source = "# A *b*\n\n| a |\n| :-: |\n| [c](/url \"title\") |\n";
+        let c_string = std::ffi::CString::new(source).unwrap();
+        let config = markdown_parser_ffi_parse_config_gfm();
+        let result = markdown_parser_ffi_utf8_parse_to_binary(c_string.as_ptr(), &config);
+        assert!(matches!(result.status, ErrorStatus::Ok));
+        let data = markdown_parser_ffi_byte_vector_data(result.output);
+        let bytes = unsafe { std::slice::from_raw_parts(data.data, data.length) };
+        let source_array = ByteArray { data: source.as_ptr(), length: source.len() };
+        let buffer = || ByteArray { data: data.data, length: data.length };
+        assert!(matches!(markdown_parser_ffi_binary_validate(buffer(), source_array), ErrorStatus::Ok));
+        let other = ByteArray { data: b"other".as_ptr(), length: 5 };
+        assert!(matches!(markdown_parser_ffi_binary_validate(buffer(), other), ErrorStatus::Error));
+
+        let read = |offset: usize| unsafe { data.data.add(offset) };
+        let header = unsafe { std::ptr::read_unaligned(read(0) as *const BinaryHeader) };
+        assert_eq!((&header.magic, header.version, header.flags), (b"MDAB", 1, 0));
+        assert_eq!((header.root_count, header.source_length as usize), (2, source.len()));
+        let node = |index: usize| unsafe {
+            std::ptr::read_unaligned(read(::markdown_format::binary::HEADER_SIZE + index * ::markdown_format::binary::NODE_SIZE) as *const BinaryNode)
+        };
+        let fields_offset = ::markdown_format::binary::HEADER_SIZE + header.node_count as usize * ::markdown_format::binary::NODE_SIZE;
+        let field = |index: u32| unsafe {
+            std::ptr::read_unaligned(read(fields_offset + index as usize * ::markdown_format::binary::FIELD_SIZE) as *const BinaryField)
+        };
+        let pool = &bytes[fields_offset + header.field_count as usize * ::markdown_format::binary::FIELD_SIZE..];
+        let field_bytes = |field: BinaryField| match field.storage {
+            0 => &pool[field.offset as usize..][..field.length as usize],
+            _ => &source.as_bytes()[field.offset as usize..][..field.length as usize],
+        };
+        let (heading, table) = (node(0), node(1));
+        assert_eq!((heading.kind, heading.detail, heading.parent), (NodeKind::Heading as u8, 1, u32::MAX));
+        assert_eq!((table.kind, table.children_length), (NodeKind::Table as u8, 2));
+        let alignment = field(table.fields_start);
+        assert_eq!((alignment.key, field_bytes(alignment)), (13, &[AlignKind::Center as u8][..]));
+
+        let document = ::markdown_format::binary::BinaryDocument::new(bytes, source).unwrap();
+        let link = (0..header.node_count as usize).find(|x| node(*x).kind == NodeKind::Link as u8).unwrap();
+        assert_eq!(document.node(link).unwrap().parent(), Some(node(link).parent as usize));
+        let url = field(node(link).fields_start);
+        assert_eq!((url.key, url.storage), (NodeStringField::Url as u16, 1));
+        assert_eq!(field_bytes(url), b"/url");
+        assert_eq!(field_bytes(field(node(link).fields_start + 1)), b"title");
+        markdown_parser_ffi_byte_vector_free(result.output);
+        assert!(markdown_parser_ffi_byte_vector_data(std::ptr::null()).data.is_null());
+    }
+
+    #[test]
+    fn byte_vector_parse_defaults_to_json() {
+        let source = Box::into_raw(Box::new(ByteVector(b"# Hello *World*".to_vec())));
+        let result = markdown_parser_ffi_utf8_byte_vector_parse(source);
+        let output = unsafe { Box::from_raw(result.output) };
+        markdown_parser_ffi_byte_vector_free(source);
+        assert!(matches!(result.status, ErrorStatus::Ok));
+        let expected = parse_markdown("# Hello *World*", &::markdown_format::ParseConfig::default()).unwrap();
+        assert_eq!(output.0, expected.into_bytes());
+    }
+
+    #[test]
+    fn document_handle_walks_the_tree() {
+        let source = std::ffi::CString::new("# Hello *World*\n\n- [x] [a](b)\n").unwrap();
+        let config = markdown_parser_ffi_parse_config_gfm();
+        let result = markdown_parser_ffi_document_parse(source.as_ptr(), &config);
+        assert!(matches!(result.status, ErrorStatus::Ok));
+        assert!(result.error.is_null());
+        let document = result.document;
+        let string = |index, field: NodeStringField| {
+            let bytes = markdown_parser_ffi_document_node_string(document, index, field as u32);
+            unsafe { std::slice::from_raw_parts(bytes.data, bytes.length) }.to_vec()
+        };
+        let roots = markdown_parser_ffi_document_roots(document);
+        assert_eq!(roots, IndexRange { start: 0, length: 2 });
+        assert_eq!(markdown_parser_ffi_document_node_kind(document, 0), NodeKind::Heading);
+        assert_eq!(markdown_parser_ffi_document_node_attributes(document, 0).heading_level, 1);
+        assert_eq!(markdown_parser_ffi_document_node_kind(document, 1), NodeKind::List);
+        let heading = markdown_parser_ffi_document_node_children(document, 0);
+        assert_eq!(heading.length, 2);
+        assert_eq!(markdown_parser_ffi_document_node_kind(document, heading.start), NodeKind::Text);
+        assert_eq!(string(heading.start, NodeStringField::Value), b"Hello ");
+        let position = markdown_parser_ffi_document_node_position(document, heading.start + 1);
+        assert!(position.has_position);
+        assert_eq!((position.start.offset, position.end.offset), (8, 15));
+        let mut parent = 0;
+        assert!(matches!(markdown_parser_ffi_document_node_parent(document, heading.start + 1, &mut parent), ErrorStatus::Ok));
+        assert_eq!(parent, 0);
+        assert!(matches!(markdown_parser_ffi_document_node_parent(document, 1, &mut parent), ErrorStatus::Error));
+        let items = markdown_parser_ffi_document_node_children(document, 1);
+        let item = markdown_parser_ffi_document_node_attributes(document, items.start);
+        assert_eq!(item.checked, CheckedState::Checked);
+        let paragraph = markdown_parser_ffi_document_node_children(document, items.start).start;
+        let link = markdown_parser_ffi_document_node_children(document, paragraph).start;
+        assert_eq!(markdown_parser_ffi_document_node_kind(document, link), NodeKind::Link);
+        assert_eq!(string(link, NodeStringField::Url), b"b");
+        assert!(markdown_parser_ffi_document_node_string(document, link, NodeStringField::Title as u32).data.is_null());
+        for field in [0, 13, u32::MAX] {
+            assert!(markdown_parser_ffi_document_node_string(document, heading.start, field).data.is_null());
+        }
+        let count = markdown_parser_ffi_document_node_count(document);
+        assert_eq!(markdown_parser_ffi_document_node_kind(document, count), NodeKind::Invalid);
+        markdown_parser_ffi_document_free(document);
+    }
+
+    #[test]
+    fn document_definition_lists() {
+        let source = std::ffi::CString::new("Term\n\n: Description\n").unwrap();
+        let config = ParseConfig { definition_list: true, ..markdown_parser_ffi_parse_config_gfm() };
+        let result = markdown_parser_ffi_document_parse(source.as_ptr(), &config);
+        assert!(matches!(result.status, ErrorStatus::Ok));
+        let document = result.document;
+        assert_eq!(markdown_parser_ffi_document_node_kind(document, 0), NodeKind::DefinitionList);
+        assert!(markdown_parser_ffi_document_node_attributes(document, 0).spread);
+        let children = markdown_parser_ffi_document_node_children(document, 0);
+        assert_eq!(children.length, 2);
+        assert_eq!(markdown_parser_ffi_document_node_kind(document, children.start), NodeKind::DefinitionTerm);
+        assert_eq!(markdown_parser_ffi_document_node_kind(document, children.start + 1), NodeKind::DefinitionDescription);
+        markdown_parser_ffi_document_free(document);
+    }
+
+    #[test]
+    fn document_code_highlights() {
+        let source = std::ffi::CString::new("```rust\nlet x = 1;\n```\n").unwrap();
+        let config = ParseConfig { highlight: true, ..markdown_parser_ffi_parse_config_default() };
+        let document = markdown_parser_ffi_document_parse(source.as_ptr(), &config).document;
+        assert!(markdown_parser_ffi_document_code_highlight_count(document, 0) > 0);
+        let first = markdown_parser_ffi_document_code_highlight(document, 0, 0);
+        let scope = unsafe { std::slice::from_raw_parts(first.scope.data, first.scope.length) };
+        assert_eq!((first.start, first.end, scope), (0, 3, &b"storage.type.rust"[..]));
+        assert!(markdown_parser_ffi_document_code_highlight(document, 0, 100).scope.data.is_null());
+        markdown_parser_ffi_document_free(document);
+    }
+
+    #[test]
+    fn outline() {
+        use ::markdown_format::outline::Section;
+        let source = std::ffi::CString::new("# Intro\n\n## Setup\n\n## Setup\n\n# *Usage*\n").unwrap();
+        let outline = |format: u32| {
+            let result = markdown_parser_ffi_utf8_outline_to_format(source.as_ptr(), std::ptr::null(), format);
+            let output = unsafe { Box::from_raw(result.output) };
+            assert!(matches!(result.status, ErrorStatus::Ok), "{}", String::from_utf8_lossy(&output.0));
+            output.0
+        };
+        let sections = serde_json::from_slice::<Vec<Section>>(&outline(DataModelFormatType::JSON as u32)).unwrap();
+        let titles = |sections: &[Section]| sections.iter().map(|x| (x.level, x.title.clone(), x.slug.clone())).collect::<Vec<_>>();
+        assert_eq!(titles(&sections), [(1, "Intro".into(), "intro".into()), (1, "Usage".into(), "usage".into())]);
+        assert_eq!(titles(&sections[0].children), [(2, "Setup".into(), "setup".into()), (2, "Setup".into(), "setup-1".into())]);
+        assert_eq!(sections[1].position.as_ref().unwrap().start.line, 7);
+        for format in [DataModelFormatType::BinaryPropertyList, DataModelFormatType::XmlPropertyList] {
+            assert_eq!(plist::from_bytes::<Vec<Section>>(&outline(format as u32)).unwrap(), sections);
+        }
+        let result = markdown_parser_ffi_utf8_outline_to_format(source.as_ptr(), std::ptr::null(), 0);
+        assert_eq!(result.error_code, ErrorCode::InvalidArgument);
+        markdown_parser_ffi_byte_vector_free(result.output);
+    }
+
+    #[test]
+    fn lint_diagnostics() {
+        let source = std::ffi::CString::new("# A\n\n### B\n\n![](x.png)\n").unwrap();
+        let lint = |lint_config: Option<&str>| {
+            let lint_config = lint_config.map(|x| std::ffi::CString::new(x).unwrap());
+            let lint_config = lint_config.as_ref().map_or(std::ptr::null(), |x| x.as_ptr());
+            let result = markdown_parser_ffi_utf8_lint_to_format(source.as_ptr(), std::ptr::null(), lint_config, DataModelFormatType::JSON as u32);
+            let output = unsafe { Box::from_raw(result.output) };
+            (matches!(result.status, ErrorStatus::Ok), serde_json::from_slice::<serde_json::Value>(&output.0).ok())
+        };
+        let (ok, diagnostics) = lint(None);
+        assert!(ok);
+        let diagnostics = diagnostics.unwrap();
+        assert_eq!(diagnostics[0]["rule"], "heading-increment");
+        assert_eq!(diagnostics[0]["severity"], "warning");
+        assert_eq!(diagnostics[0]["position"]["start"]["line"], 3);
+        assert_eq!(diagnostics[1]["rule"], "image-alt");
+        let (_, diagnostics) = lint(Some(r#"{"heading-increment": "off", "image-alt": "error"}"#));
+        assert_eq!(diagnostics.unwrap(), serde_json::json!([{
+            "rule": "image-alt",
+            "severity": "error",
+            "message": "image without alternative text",
+            "position": {
+                "start": {"line": 5, "column": 1, "offset": 12},
+                "end": {"line": 5, "column": 11, "offset": 22},
+            },
+        }]));
+        assert_eq!(lint(Some("{\"image-alt\": \"loud\"}")), (false, None));
+    }
 }
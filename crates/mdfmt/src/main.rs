@@ -0,0 +1,377 @@
+//! `mdfmt`, the command line front end of `markdown-format`, so that CI runs
+//! the same engine as the app.
+//!
+//! Exits with `1` when `lint` reports errors or `fmt --check` finds files to
+//! format, and with `2` on unreadable input or bad arguments.
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use markdown_format::lint::{Diagnostic, LintConfig, Linter, Severity};
+use markdown_format::outline::Section;
+use markdown_format::{Envelope, Node, ParseConfig};
+use markdown_format::pretty_tree::{self, ToPrettyTree};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # ARGUMENTS
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+/// Parses, renders, lints and formats Markdown. Reads standard input when no
+/// file (or `-`) is given.
+#[derive(Parser)]
+#[command(name = "mdfmt", version)]
+struct Cli {
+    #[command(flatten)]
+    parse: ParseOptions,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct ParseOptions {
+    /// Construct set to start from.
+    #[arg(long, value_enum, default_value_t = Preset::Default, global = true)]
+    preset: Preset,
+    /// Turns on a construct such as `gfm_table`, `wikilink` or `highlight`, may
+    /// be repeated.
+    #[arg(long, value_name = "CONSTRUCT", global = true)]
+    enable: Vec<String>,
+    /// Turns off a construct, may be repeated.
+    #[arg(long, value_name = "CONSTRUCT", global = true)]
+    disable: Vec<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Prints the syntax tree.
+    Parse {
+        #[arg(long, short, value_enum, default_value_t = TreeFormat::Tree)]
+        format: TreeFormat,
+        file: Option<PathBuf>,
+    },
+    /// Renders the document.
+    Render {
+        #[arg(long, short, value_enum, default_value_t = RenderFormat::Html)]
+        format: RenderFormat,
+        /// Drops raw HTML and dangerous URLs from the HTML output.
+        #[arg(long)]
+        safe: bool,
+        file: Option<PathBuf>,
+    },
+    /// Reports lint diagnostics, fails when there are errors.
+    Lint {
+        #[arg(long, short, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        /// JSON file of rule levels, as in `{"bare-url": "off"}`.
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Fails on warnings too.
+        #[arg(long)]
+        deny_warnings: bool,
+        files: Vec<PathBuf>,
+    },
+    /// Prints the headings with their anchors.
+    Outline {
+        #[arg(long, short, value_enum, default_value_t = ReportFormat::Text)]
+        format: ReportFormat,
+        file: Option<PathBuf>,
+    },
+    /// Prints the documents as normalized Markdown.
+    Fmt {
+        /// Formats the files in place.
+        #[arg(long, conflicts_with = "check")]
+        write: bool,
+        /// Lists the files that aren't formatted and fails if there are any.
+        #[arg(long)]
+        check: bool,
+        files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Preset {
+    Default,
+    Commonmark,
+    Gfm,
+    Everything,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TreeFormat {
+    /// Human readable tree.
+    Tree,
+    /// The versioned wire format.
+    Json,
+    /// The wire format as an XML property list.
+    Plist,
+    /// The wire format as a binary property list.
+    BinaryPlist,
+    /// The flat binary encoding of `markdown_format::binary`, without the source.
+    Binary,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum RenderFormat {
+    Html,
+    Markdown,
+    Text,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Text,
+    Json,
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # MAIN
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        // Such as `mdfmt parse | head`.
+        Err(error) if error.downcast_ref::<std::io::Error>().is_some_and(|x| x.kind() == std::io::ErrorKind::BrokenPipe) => {
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("mdfmt: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Whether the checks passed.
+fn run(cli: Cli) -> Result<bool> {
+    let config = cli.parse.config()?;
+    let mut stdout = std::io::stdout().lock();
+    match cli.command {
+        Command::Parse { format, file } => {
+            let input = Input::read(file.as_deref())?;
+            let nodes = input.parse(&config)?;
+            match format {
+                TreeFormat::Tree => {
+                    write!(stdout, "{}", MarkdownFile { file_path: input.name, nodes }.to_pretty_tree())?;
+                }
+                TreeFormat::Json => writeln!(stdout, "{}", Envelope::new(nodes).to_json()?)?,
+                TreeFormat::Plist => plist::to_writer_xml(&mut stdout, &Envelope::new(nodes))?,
+                TreeFormat::BinaryPlist => plist::to_writer_binary(&mut stdout, &Envelope::new(nodes))?,
+                TreeFormat::Binary => stdout.write_all(&markdown_format::binary::encode(&nodes, &input.source)?)?,
+            }
+        }
+        Command::Render { format, safe, file } => {
+            let nodes = Input::read(file.as_deref())?.parse(&config)?;
+            let output = match format {
+                RenderFormat::Html => {
+                    let options = markdown_format::html::HtmlOptions { safe, ..Default::default() };
+                    markdown_format::html::render(&nodes, &options)
+                }
+                RenderFormat::Markdown => markdown_format::printer::print(&nodes, &Default::default()),
+                RenderFormat::Text => {
+                    let mut text = markdown_format::plain_text::to_plain_text(&nodes, &Default::default()).text;
+                    text.push('\n');
+                    text
+                }
+            };
+            stdout.write_all(output.as_bytes())?;
+        }
+        Command::Lint { format, config: lint_config, deny_warnings, files } => {
+            let mut linter = Linter::default();
+            if let Some(path) = lint_config {
+                let json = std::fs::read_to_string(&path).map_err(|x| format!("{}: {x}", path.display()))?;
+                let lint_config: LintConfig = serde_json::from_str(&json).map_err(|x| format!("{}: {x}", path.display()))?;
+                linter.configure(&lint_config);
+            }
+            let mut reports = Vec::new();
+            for input in Input::read_all(&files)? {
+                let nodes = input.parse(&config)?;
+                let diagnostics = linter.lint(&input.source, &nodes);
+                reports.push((input.name, diagnostics));
+            }
+            match format {
+                ReportFormat::Text => {
+                    for (name, diagnostics) in &reports {
+                        for diagnostic in diagnostics {
+                            writeln!(stdout, "{}", report_line(name, diagnostic))?;
+                        }
+                    }
+                }
+                ReportFormat::Json => {
+                    let reports = reports
+                        .iter()
+                        .map(|(name, diagnostics)| serde_json::json!({"file": name, "diagnostics": diagnostics}))
+                        .collect::<Vec<_>>();
+                    writeln!(stdout, "{}", serde_json::to_string_pretty(&reports)?)?;
+                }
+            }
+            let failed = reports
+                .iter()
+                .flat_map(|(_, x)| x)
+                .any(|x| deny_warnings || x.severity == Severity::Error);
+            return Ok(!failed)
+        }
+        Command::Outline { format, file } => {
+            let nodes = Input::read(file.as_deref())?.parse(&config)?;
+            let sections = markdown_format::outline::outline(&nodes);
+            match format {
+                ReportFormat::Text => write_sections(&mut stdout, &sections, 0)?,
+                ReportFormat::Json => writeln!(stdout, "{}", serde_json::to_string_pretty(&sections)?)?,
+            }
+        }
+        Command::Fmt { write, check, files } => {
+            if write && files.iter().all(|x| x == Path::new("-")) {
+                return Err("--write needs files".into())
+            }
+            let mut formatted_all = true;
+            for input in Input::read_all(&files)? {
+                let nodes = input.parse(&config)?;
+                let formatted = markdown_format::printer::print(&nodes, &Default::default());
+                if check {
+                    if formatted != input.source {
+                        writeln!(stdout, "{}", input.name)?;
+                        formatted_all = false;
+                    }
+                } else if write {
+                    if formatted != input.source {
+                        std::fs::write(&input.name, formatted).map_err(|x| format!("{}: {x}", input.name))?;
+                    }
+                } else {
+                    stdout.write_all(formatted.as_bytes())?;
+                }
+            }
+            return Ok(formatted_all)
+        }
+    }
+    Ok(true)
+}
+
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+// # INTERNAL
+//―――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――――
+struct Input {
+    /// The path, `-` for standard input.
+    name: String,
+    source: String,
+}
+
+impl Input {
+    fn read(path: Option<&Path>) -> Result<Self> {
+        match path.filter(|x| *x != Path::new("-")) {
+            Some(path) => {
+                let source = std::fs::read_to_string(path).map_err(|x| format!("{}: {x}", path.display()))?;
+                Ok(Input { name: path.display().to_string(), source })
+            }
+            None => {
+                let mut source = String::new();
+                std::io::stdin().read_to_string(&mut source).map_err(|x| format!("standard input: {x}"))?;
+                Ok(Input { name: String::from("-"), source })
+            }
+        }
+    }
+    fn read_all(paths: &[PathBuf]) -> Result<Vec<Self>> {
+        if paths.is_empty() {
+            return Ok(vec![Input::read(None)?])
+        }
+        paths.iter().map(|x| Input::read(Some(x))).collect()
+    }
+    /// With references resolved, like the FFI does.
+    fn parse(&self, config: &ParseConfig) -> Result<Vec<Node>> {
+        let mut nodes = markdown_format::parse_with(&self.source, config).map_err(|x| format!("{}: {x}", self.name))?;
+        markdown_format::resolve::resolve(&mut nodes);
+        Ok(nodes)
+    }
+}
+
+impl ParseOptions {
+    fn config(&self) -> Result<ParseConfig> {
+        let mut config = match self.preset {
+            Preset::Default => ParseConfig::default(),
+            Preset::Commonmark => ParseConfig::commonmark(),
+            Preset::Gfm => ParseConfig::gfm(),
+            Preset::Everything => ParseConfig::everything(),
+        };
+        for (names, value) in [(&self.enable, true), (&self.disable, false)] {
+            for name in names {
+                *construct(&mut config, name).ok_or_else(|| format!("unknown construct `{name}`"))? = value;
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// The field of the config named `name`, in snake or kebab case.
+fn construct<'a>(config: &'a mut ParseConfig, name: &str) -> Option<&'a mut bool> {
+    let field = match name.replace('-', "_").as_str() {
+        "attention" => &mut config.attention,
+        "autolink" => &mut config.autolink,
+        "block_quote" => &mut config.block_quote,
+        "character_escape" => &mut config.character_escape,
+        "character_reference" => &mut config.character_reference,
+        "code_indented" => &mut config.code_indented,
+        "code_fenced" => &mut config.code_fenced,
+        "code_text" => &mut config.code_text,
+        "definition" => &mut config.definition,
+        "frontmatter" => &mut config.frontmatter,
+        "gfm_autolink_literal" => &mut config.gfm_autolink_literal,
+        "gfm_footnote_definition" => &mut config.gfm_footnote_definition,
+        "gfm_label_start_footnote" => &mut config.gfm_label_start_footnote,
+        "gfm_strikethrough" => &mut config.gfm_strikethrough,
+        "gfm_table" => &mut config.gfm_table,
+        "gfm_task_list_item" => &mut config.gfm_task_list_item,
+        "hard_break_escape" => &mut config.hard_break_escape,
+        "hard_break_trailing" => &mut config.hard_break_trailing,
+        "heading_atx" => &mut config.heading_atx,
+        "heading_setext" => &mut config.heading_setext,
+        "html_flow" => &mut config.html_flow,
+        "html_text" => &mut config.html_text,
+        "label_start_image" => &mut config.label_start_image,
+        "label_start_link" => &mut config.label_start_link,
+        "label_end" => &mut config.label_end,
+        "list_item" => &mut config.list_item,
+        "math_flow" => &mut config.math_flow,
+        "math_text" => &mut config.math_text,
+        "thematic_break" => &mut config.thematic_break,
+        "admonition" => &mut config.admonition,
+        "wikilink" => &mut config.wikilink,
+        "definition_list" => &mut config.definition_list,
+        "unicode_offsets" => &mut config.unicode_offsets,
+        "highlight" => &mut config.highlight,
+        _ => return None,
+    };
+    Some(field)
+}
+
+/// `file:line:column: severity: message [rule]`, the format editors and CI
+/// annotations pick up.
+fn report_line(name: &str, diagnostic: &Diagnostic) -> String {
+    let (line, column) = diagnostic.position.as_ref().map_or((1, 1), |x| (x.start.line, x.start.column));
+    let severity = match diagnostic.severity {
+        Severity::Warning => "warning",
+        Severity::Error => "error",
+    };
+    format!("{name}:{line}:{column}: {severity}: {} [{}]", diagnostic.message, diagnostic.rule)
+}
+
+fn write_sections(output: &mut impl Write, sections: &[Section], depth: usize) -> Result<()> {
+    for section in sections {
+        writeln!(output, "{}- {} (#{})", "  ".repeat(depth), section.title, section.slug)?;
+        write_sections(output, &section.children, depth + 1)?;
+    }
+    Ok(())
+}
+
+struct MarkdownFile {
+    file_path: String,
+    nodes: Vec<Node>,
+}
+
+impl ToPrettyTree for MarkdownFile {
+    fn to_pretty_tree(&self) -> pretty_tree::PrettyTree {
+        pretty_tree::PrettyTree::branch_of("MarkdownFile", vec![
+            pretty_tree::field("file_path", &self.file_path),
+            pretty_tree::branch_of("nodes", &self.nodes),
+        ])
+    }
+}
@@ -0,0 +1,93 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+fn mdfmt(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_mdfmt"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> &str {
+    std::str::from_utf8(&output.stdout).unwrap()
+}
+
+/// A new directory of its own, so that tests running in parallel (or other
+/// runs of the suite) don't share files.
+fn temp_dir() -> PathBuf {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let name = format!("mdfmt-test-{}-{}", std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed));
+    let directory = std::env::temp_dir().join(name);
+    std::fs::create_dir_all(&directory).unwrap();
+    directory
+}
+
+#[test]
+fn parse_and_render() {
+    let output = mdfmt(&["parse", "--format", "json"], "# Hi\n");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_str(stdout(&output)).unwrap();
+    assert_eq!(json["version"], 2);
+    assert_eq!(json["nodes"][0]["type"], "Heading");
+    assert!(stdout(&mdfmt(&["parse"], "a")).contains("MarkdownFile"));
+    assert!(mdfmt(&["parse", "-f", "binary-plist"], "a").stdout.starts_with(b"bplist00"));
+
+    assert_eq!(stdout(&mdfmt(&["render"], "*a*\n")), "<p><em>a</em></p>\n");
+    assert_eq!(stdout(&mdfmt(&["render", "-f", "markdown"], "_a_\n")), "*a*\n");
+    assert_eq!(stdout(&mdfmt(&["render", "-f", "text"], "# A\n\nb *c*\n")), "A\n\nb c\n");
+}
+
+#[test]
+fn parse_options() {
+    let source = "| a |\n| - |\n\n~~b~~ [[c]]\n";
+    let gfm = stdout(&mdfmt(&["render", "--preset", "commonmark", "--enable", "gfm-table"], source)).to_owned();
+    assert!(gfm.contains("<table>") && gfm.contains("~~b~~"));
+    let html = stdout(&mdfmt(&["--disable", "gfm_table", "render", "--enable", "wikilink"], source)).to_owned();
+    assert!(!html.contains("<table>") && html.contains("<del>b</del> c"));
+    let unknown = mdfmt(&["render", "--enable", "nope"], source);
+    assert_eq!(unknown.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&unknown.stderr).contains("unknown construct `nope`"));
+}
+
+#[test]
+fn lint_exit_codes() {
+    let source = "# A\n\n### B\n";
+    let warnings = mdfmt(&["lint"], source);
+    assert_eq!(warnings.status.code(), Some(0));
+    assert_eq!(stdout(&warnings), "-:3:1: warning: heading level 3 after level 1, expected 2 [heading-increment]\n");
+    assert_eq!(mdfmt(&["lint", "--deny-warnings"], source).status.code(), Some(1));
+
+    let directory = temp_dir();
+    let config = directory.join("lint-config.json");
+    std::fs::write(&config, r#"{"heading-increment": "error"}"#).unwrap();
+    let errors = mdfmt(&["lint", "--format", "json", "--config", config.to_str().unwrap()], source);
+    assert_eq!(errors.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_str(stdout(&errors)).unwrap();
+    assert_eq!(json[0]["diagnostics"][0]["severity"], "error");
+    assert_eq!(mdfmt(&["lint", "no-such-file.md"], "").status.code(), Some(2));
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn outline_and_fmt() {
+    assert_eq!(stdout(&mdfmt(&["outline"], "# A\n\n## B c\n\n# A\n")), "- A (#a)\n  - B c (#b-c)\n- A (#a-1)\n");
+
+    let directory = temp_dir();
+    let path = directory.join("fmt.md");
+    std::fs::write(&path, "* a\n* b\n").unwrap();
+    let path = path.to_str().unwrap();
+    let check = mdfmt(&["fmt", "--check", path], "");
+    assert_eq!((check.status.code(), stdout(&check)), (Some(1), format!("{path}\n").as_str()));
+    assert!(mdfmt(&["fmt", "--write", path], "").status.success());
+    assert_eq!(std::fs::read_to_string(path).unwrap(), "- a\n- b\n");
+    assert_eq!(mdfmt(&["fmt", "--check", path], "").status.code(), Some(0));
+    assert_eq!(stdout(&mdfmt(&["fmt"], "Title\n===\n")), "# Title\n");
+    std::fs::remove_dir_all(directory).unwrap();
+}